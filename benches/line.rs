@@ -0,0 +1,51 @@
+use std::{sync::Arc, time::Instant};
+
+use criterion::*;
+use prodash::{render::line, tree::Root as Tree, Root};
+
+const NUM_TASKS: usize = 1000;
+const FRAMES: usize = 30;
+
+fn line_renderer(c: &mut Criterion) {
+    let root = Tree::new();
+    let tasks: Vec<_> = (0..NUM_TASKS)
+        .map(|idx| {
+            let task = root.add_child(if idx % 2 == 0 {
+                format!("Downloading crate {idx}")
+            } else {
+                format!("下载板条箱 {idx}")
+            });
+            task.init(Some(FRAMES), Some("items".into()));
+            task
+        })
+        .collect();
+    let tasks = Arc::new(tasks);
+
+    c.benchmark_group("render::line")
+        .throughput(Throughput::Elements((NUM_TASKS * FRAMES) as u64))
+        .sample_size(10)
+        .bench_function(format!("draw {NUM_TASKS} lines for {FRAMES} frames"), |b| {
+            b.iter(|| {
+                let tasks = Arc::clone(&tasks);
+                let handle = line::render_with_ticks(
+                    std::io::sink(),
+                    root.downgrade(),
+                    line::Options {
+                        terminal_dimensions: (120, NUM_TASKS as u16 + 20),
+                        ..Default::default()
+                    },
+                    // Make progress before each frame to have every line redrawn.
+                    (0..FRAMES).map(move |step| {
+                        for task in tasks.iter() {
+                            task.set(step);
+                        }
+                        Instant::now()
+                    }),
+                );
+                handle.shutdown_and_wait();
+            });
+        });
+}
+
+criterion_group!(benches, line_renderer);
+criterion_main!(benches);
@@ -0,0 +1,113 @@
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Duration,
+};
+
+use criterion::*;
+use prodash::{
+    progress::{Key, Task},
+    tree::Root as Tree,
+    unit::{self, display},
+};
+
+/// Count all allocations to show how many of them happen per frame.
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+const NUM_TASKS: usize = 500;
+
+fn allocations_during(f: impl FnOnce()) -> usize {
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    f();
+    ALLOCATIONS.load(Ordering::Relaxed) - before
+}
+
+/// Format the unit of each task into a new string, as renderers used to do.
+fn format_frame(tasks: &[(Key, Task)], out: &mut Vec<String>) {
+    out.clear();
+    out.extend(tasks.iter().filter_map(|(_, task)| {
+        let progress = task.progress.as_ref()?;
+        let unit = progress.unit.as_ref()?;
+        Some(format!(
+            "{}",
+            unit.display(progress.step.load(Ordering::Relaxed), progress.done_at, None)
+        ))
+    }));
+}
+
+/// Write the unit of each task into a buffer reused across frames.
+fn write_frame(tasks: &[(Key, Task)], out: &mut [String]) {
+    for ((_, task), buf) in tasks.iter().zip(out.iter_mut()) {
+        buf.clear();
+        if let Some((progress, unit)) = task
+            .progress
+            .as_ref()
+            .and_then(|p| p.unit.as_ref().map(|unit| (p, unit)))
+        {
+            unit.write_to(buf, progress.step.load(Ordering::Relaxed), progress.done_at, None)
+                .expect("writing to a string never fails");
+        }
+    }
+}
+
+fn unit_formatting(c: &mut Criterion) {
+    let root = Tree::new();
+    let _tasks: Vec<_> = (0..NUM_TASKS)
+        .map(|idx| {
+            let task = root.add_child(format!("task {idx}"));
+            task.init(
+                Some(1000),
+                Some(unit::label_and_mode("items", display::Mode::with_percentage())),
+            );
+            task.set(idx);
+            task
+        })
+        .collect();
+    let mut tasks = Vec::new();
+    root.sorted_snapshot(&mut tasks);
+
+    let mut formatted = Vec::with_capacity(NUM_TASKS);
+    let mut pooled = vec![String::with_capacity(32); NUM_TASKS];
+    format_frame(&tasks, &mut formatted);
+    write_frame(&tasks, &mut pooled);
+    assert_eq!(formatted[1], pooled[1], "both produce the same text");
+
+    eprintln!(
+        "allocations per frame with {NUM_TASKS} tasks: format!() = {}, write_to() = {}",
+        allocations_during(|| format_frame(&tasks, &mut formatted)),
+        allocations_during(|| write_frame(&tasks, &mut pooled)),
+    );
+
+    c.benchmark_group("unit::display")
+        .throughput(Throughput::Elements(NUM_TASKS as u64))
+        .measurement_time(Duration::from_secs(2))
+        .bench_function(format!("format!() units of {NUM_TASKS} tasks"), |b| {
+            b.iter(|| format_frame(&tasks, &mut formatted))
+        })
+        .bench_function(
+            format!("write_to() units of {NUM_TASKS} tasks into pooled buffers"),
+            |b| b.iter(|| write_frame(&tasks, &mut pooled)),
+        );
+}
+
+criterion_group!(benches, unit_formatting);
+criterion_main!(benches);
@@ -6,11 +6,21 @@ use prodash::{
 };
 use std::sync::atomic::Ordering;
 
+/// The storage backend of the progress tree, as selected by cargo features.
+const BACKEND: &str = if cfg!(feature = "progress-tree-hp-hashmap") {
+    "dashmap"
+} else if cfg!(feature = "progress-tree-btreemap") {
+    "btreemap"
+} else {
+    "hashmap"
+};
+
 fn usage(c: &mut Criterion) {
     fn small_tree() -> std::sync::Arc<Tree> {
         TreeOptions {
             initial_capacity: 10,
             message_buffer_capacity: 2,
+            ..Default::default()
         }
         .create()
         .into()
@@ -136,5 +146,27 @@ fn usage(c: &mut Criterion) {
         });
 }
 
-criterion_group!(benches, usage);
+fn backend(c: &mut Criterion) {
+    const TASKS: usize = 1000;
+    let group_name = format!("Tree::backend ({BACKEND})");
+    c.benchmark_group(group_name.as_str())
+        .throughput(Throughput::Elements(TASKS as u64))
+        .bench_function("insert and remove 1000 tasks", |b| {
+            let root = Tree::new();
+            b.iter(|| {
+                let children: Vec<_> = (0..TASKS).map(|_| root.add_child("task")).collect();
+                children
+            });
+        })
+        .bench_function("snapshot 1000 tasks", |b| {
+            let root = Tree::new();
+            let _children: Vec<_> = (0..TASKS).map(|_| root.add_child("task")).collect();
+            let mut out = Vec::with_capacity(TASKS);
+            b.iter(|| {
+                root.sorted_snapshot(&mut out);
+            });
+        });
+}
+
+criterion_group!(benches, usage, backend);
 criterion_main!(benches);
@@ -73,6 +73,11 @@ pub struct Options {
     #[argh(option, short = 'R')]
     pub renderer: Option<String>,
 
+    /// if set, serve an HTTP endpoint at the given address, like 127.0.0.1:8080, to pause and resume the renderer, change
+    /// the levels it shows or its frame rate, or request an interrupt. Visit it to learn more.
+    #[argh(option)]
+    pub control: Option<String>,
+
     /// has not effect - use the NO_COLOR environment variable instead.
     #[argh(switch)]
     pub no_line_color: bool,
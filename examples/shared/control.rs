@@ -0,0 +1,77 @@
+use std::{
+    io::{self, BufRead, BufReader, Write},
+    net::{TcpListener, TcpStream},
+    thread,
+};
+
+use prodash::render::Control;
+
+/// Serve a tiny HTTP endpoint at `addr` to control the renderer through the returned handle, for instance with
+/// `curl localhost:8080/pause`.
+///
+/// * `/pause` and `/resume` stop and continue drawing frames.
+/// * `/levels/<first>/<last>` shows only tasks of these levels, and `/levels` shows all of them again.
+/// * `/fps/<frames per second>` changes the frame rate, and `/fps` restores the one the renderer was configured with.
+/// * `/interrupt` sets the interrupt signal of the control.
+/// * Anything else shows the current state.
+pub fn serve(addr: &str) -> io::Result<Control> {
+    let listener = TcpListener::bind(addr)?;
+    let control = Control::new();
+    thread::Builder::new().name("control endpoint".into()).spawn({
+        let control = control.clone();
+        move || {
+            for stream in listener.incoming().flatten() {
+                handle(stream, &control).ok();
+            }
+        }
+    })?;
+    Ok(control)
+}
+
+fn handle(mut stream: TcpStream, control: &Control) -> io::Result<()> {
+    let mut request_line = String::new();
+    BufReader::new(&stream).read_line(&mut request_line)?;
+    let path = request_line.split_whitespace().nth(1).unwrap_or("/");
+    let (status, body) = match apply(path, control) {
+        Ok(()) => ("200 OK", state(control)),
+        Err(err) => ("400 Bad Request", format!("{err}\n")),
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {status}\r\nContent-Type: text/plain\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    )
+}
+
+fn apply(path: &str, control: &Control) -> Result<(), String> {
+    let mut segments = path.trim_matches('/').split('/');
+    match (segments.next(), segments.next(), segments.next()) {
+        (Some("pause"), None, None) => control.pause(),
+        (Some("resume"), None, None) => control.resume(),
+        (Some("interrupt"), None, None) => control.request_interrupt(),
+        (Some("levels"), None, None) => control.set_level_filter(None),
+        (Some("levels"), Some(first), Some(last)) => {
+            let level = |level: &str| level.parse().map_err(|err| format!("invalid level '{level}': {err}"));
+            control.set_level_filter(Some(level(first)?..=level(last)?));
+        }
+        (Some("fps"), None, None) => control.set_frames_per_second(None),
+        (Some("fps"), Some(fps), None) => {
+            let fps: f32 = fps
+                .parse()
+                .map_err(|err| format!("invalid frame rate '{fps}': {err}"))?;
+            control.set_frames_per_second(Some(fps));
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+fn state(control: &Control) -> String {
+    format!(
+        "paused: {}\nlevels: {:?}\nframes per second: {:?}\ninterrupt requested: {}\n",
+        control.is_paused(),
+        control.level_filter(),
+        control.frames_per_second(),
+        control.is_interrupt_requested()
+    )
+}
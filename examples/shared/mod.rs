@@ -4,13 +4,15 @@ use futures_util::{future::FutureExt, stream::StreamExt};
 use prodash::{
     render::{
         line,
-        tui::{self, ticker, Event, Interrupt, Line},
+        tui::{self, Align, Event, Interrupt, Line},
     },
+    time::ticker,
     tree::Root as Tree,
 };
 use rand::{seq::SliceRandom, thread_rng, Rng};
 
 pub mod args;
+mod control;
 mod spawn;
 pub use spawn::{spawn, Task};
 
@@ -29,6 +31,7 @@ pub fn launch_ambient_gui(
 ) -> std::result::Result<Task<()>, std::io::Error> {
     let mut ticks: usize = 0;
     let mut interruptible = true;
+    let control = args.control.as_deref().map(control::serve).transpose()?;
     let render_fut = match renderer {
         "line" => async move {
             let mut handle = line::render(
@@ -45,6 +48,7 @@ pub fn launch_ambient_gui(
                     frames_per_second: args.fps,
                     keep_running_if_progress_is_empty: true,
                     throughput,
+                    control,
                     ..Default::default()
                 }
                 .auto_configure(line::StreamKind::Stderr),
@@ -66,6 +70,8 @@ pub fn launch_ambient_gui(
                         frames_per_second: args.fps,
                         recompute_column_width_every_nth_frame: args.recompute_column_width_every_nth_frame,
                         throughput,
+                        interrupt_signal: control.as_ref().map(|control| control.interrupt_signal()),
+                        control,
                         ..tui::Options::default()
                     },
                     futures_util::stream::select(
@@ -114,29 +120,36 @@ fn generate_statistics() -> Vec<Line> {
     ];
     lines.shuffle(&mut thread_rng());
     lines.insert(0, Line::Title("Hello World".into()));
+    lines.insert(1, Line::aligned("~ centered ~", Align::Center));
 
     lines.extend(vec![
         Line::Title("Statistics".into()),
-        Line::Text(format!(
-            "lines of unsafe code: {}",
-            thread_rng().gen_range(0usize..=1_000_000)
-        )),
-        Line::Text(format!(
-            "wasted space in crates: {} Kb",
-            thread_rng().gen_range(100usize..=1_000_000)
-        )),
-        Line::Text(format!(
-            "unused dependencies: {} crates",
-            thread_rng().gen_range(100usize..=1_000)
-        )),
-        Line::Text(format!(
-            "average #dependencies: {} crates",
-            thread_rng().gen_range(0usize..=500)
-        )),
-        Line::Text(format!(
-            "bloat in code: {} Kb",
-            thread_rng().gen_range(100usize..=5_000)
-        )),
+        Line::aligned(
+            format!("lines of unsafe code: {}", thread_rng().gen_range(0usize..=1_000_000)),
+            Align::Right,
+        ),
+        Line::aligned(
+            format!(
+                "wasted space in crates: {} Kb",
+                thread_rng().gen_range(100usize..=1_000_000)
+            ),
+            Align::Right,
+        ),
+        Line::aligned(
+            format!(
+                "unused dependencies: {} crates",
+                thread_rng().gen_range(100usize..=1_000)
+            ),
+            Align::Right,
+        ),
+        Line::aligned(
+            format!("average #dependencies: {} crates", thread_rng().gen_range(0usize..=500)),
+            Align::Right,
+        ),
+        Line::aligned(
+            format!("bloat in code: {} Kb", thread_rng().gen_range(100usize..=5_000)),
+            Align::Right,
+        ),
     ]);
     lines
 }
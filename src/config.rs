@@ -0,0 +1,44 @@
+use std::{io, path::Path};
+
+use serde::de::DeserializeOwned;
+
+/// Load options of type `T` from [TOML](https://toml.io), as found in the environment variable named `var` or in the file at `path`.
+///
+/// The environment variable takes precedence, and if neither it nor the file exist, `T::default()` is returned.
+/// Fields that aren't set fall back to their default value, so partial configuration is supported.
+///
+/// This works with [`tree::root::Options`][crate::tree::root::Options], [`render::line::Options`][crate::render::line::Options]
+/// and [`render::tui::Options`][crate::render::tui::Options], if the respective features are enabled.
+///
+/// ```rust
+/// let options: prodash::tree::root::Options = prodash::config::from_env_or_file("MYAPP_PROGRESS", "/etc/myapp/progress.toml")?;
+/// # Ok::<_, std::io::Error>(())
+/// ```
+pub fn from_env_or_file<T>(var: &str, path: impl AsRef<Path>) -> io::Result<T>
+where
+    T: DeserializeOwned + Default,
+{
+    let text = match std::env::var(var) {
+        Ok(text) => text,
+        Err(std::env::VarError::NotUnicode(_)) => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("environment variable {var} is not valid unicode"),
+            ))
+        }
+        Err(std::env::VarError::NotPresent) => match std::fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(T::default()),
+            Err(err) => return Err(err),
+        },
+    };
+    from_str(&text)
+}
+
+/// Parse options of type `T` from the given [TOML](https://toml.io) `text`.
+pub fn from_str<T>(text: &str) -> io::Result<T>
+where
+    T: DeserializeOwned,
+{
+    toml::from_str(text).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+}
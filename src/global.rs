@@ -0,0 +1,52 @@
+//! A process-wide default [`Root`][crate::tree::Root] for code which can't be handed a progress instance.
+//!
+//! Similar to the global logger of the `log` crate, deeply nested code can report progress through it, while the
+//! application attaches a renderer to it once.
+//!
+//! ```
+//! let progress = prodash::global::add_child("deeply nested work");
+//! progress.init(Some(10), None);
+//! progress.inc();
+//! assert_eq!(prodash::global::root().num_tasks(), 1);
+//! // Pass `prodash::global::downgrade()` to any renderer to show the progress of all code using the global root.
+//! ```
+use std::sync::{Arc, OnceLock, Weak};
+
+use crate::{
+    progress::Id,
+    tree::{self, Item},
+};
+
+static ROOT: OnceLock<Arc<tree::Root>> = OnceLock::new();
+
+/// Install `root` as the global root, which only works if no global root was set or used before.
+///
+/// Return `root` as error if there already is a global root.
+pub fn set_root(root: Arc<tree::Root>) -> Result<(), Arc<tree::Root>> {
+    ROOT.set(root)
+}
+
+/// Return the global root, which is created with the default configuration on first use unless it was
+/// [set][set_root()] before.
+pub fn root() -> Arc<tree::Root> {
+    Arc::clone(ROOT.get_or_init(tree::Root::new))
+}
+
+/// Return a weak reference to the global root, as needed by renderers.
+pub fn downgrade() -> Weak<tree::Root> {
+    Arc::downgrade(ROOT.get_or_init(tree::Root::new))
+}
+
+/// Add a new child task with the given `name` to the global root.
+///
+/// See [`tree::Root::add_child()`] for details.
+pub fn add_child(name: impl Into<String>) -> Item {
+    root().add_child(name)
+}
+
+/// Add a new child task with the given `name` and `id` to the global root.
+///
+/// See [`tree::Root::add_child_with_id()`] for details.
+pub fn add_child_with_id(name: impl Into<String>, id: Id) -> Item {
+    root().add_child_with_id(name, id)
+}
@@ -50,6 +50,10 @@ pub use log::warn;
 ///
 pub mod time;
 
+#[cfg(feature = "config")]
+///
+pub mod config;
+
 ///
 pub mod unit;
 #[doc(inline)]
@@ -66,8 +70,14 @@ pub use traits::{
     Progress, Root, WeakRoot,
 };
 
+#[cfg(feature = "testing")]
+pub mod testing;
+
+#[cfg(feature = "global")]
+pub mod global;
+
 mod throughput;
-pub use crate::throughput::Throughput;
+pub use crate::throughput::{Throughput, ThroughputSampler};
 
 #[cfg(not(feature = "progress-tree-log"))]
 mod log {
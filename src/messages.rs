@@ -1,7 +1,15 @@
-use std::time::SystemTime;
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt,
+    sync::atomic::{AtomicU64, Ordering},
+    time::{Duration, SystemTime},
+};
+
+use crate::progress;
 
 /// The severity of a message
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum MessageLevel {
     /// Rarely sent information related to the progress, not to be confused with the progress itself
     Info,
@@ -14,103 +22,332 @@ pub enum MessageLevel {
 /// A message to be stored along with the progress tree.
 ///
 /// It is created by [`Tree::message(…)`](./struct.Item.html#method.message).
+///
+/// It can't be constructed outside of this crate so that fields can be added without breaking downstream code.
 #[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
 pub struct Message {
-    /// The time at which the message was sent.
+    /// The time at which the message was sent, or last sent if identical messages were collapsed into it.
     pub time: SystemTime,
     /// The severity of the message
     pub level: MessageLevel,
-    /// The name of the task that created the `Message`
-    pub origin: String,
+    /// The task that created the `Message`
+    pub origin: Origin,
     /// The message itself
     pub message: String,
+    /// The amount of times this message was sent, which is more than one if identical messages were collapsed into it.
+    ///
+    /// See [`MessageRingBuffer::set_dedupe_window()`].
+    pub count: usize,
+    /// The position of the message among all messages stored in its buffer, starting at 0.
+    ///
+    /// It increases by one with each stored message, allowing consumers to tell which messages they have seen already.
+    /// A message into which an identical one is collapsed gets a new sequence number, so it's seen again with its new
+    /// count. Messages dropped due to a [rate limit][MessageRingBuffer::set_rate_limit()] don't get one.
+    pub seq: u64,
+}
+
+/// The task a [`Message`] originates from.
+///
+/// It displays as the name of the task.
+#[derive(Debug, Clone, Default, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Origin {
+    /// The key of the task in the progress tree.
+    pub key: progress::Key,
+    /// The stable identifier of the task, useful to filter messages.
+    pub id: progress::Id,
+    /// The name of the task at the time the message was created.
+    pub name: String,
+}
+
+impl fmt::Display for Origin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.name)
+    }
+}
+
+impl AsRef<str> for Origin {
+    fn as_ref(&self) -> &str {
+        &self.name
+    }
+}
+
+impl From<String> for Origin {
+    fn from(name: String) -> Self {
+        Origin {
+            name,
+            ..Default::default()
+        }
+    }
+}
+
+impl From<&str> for Origin {
+    fn from(name: &str) -> Self {
+        name.to_owned().into()
+    }
+}
+
+/// The maximum amount of messages each task may send within a period of time, as used with
+/// [`MessageRingBuffer::set_rate_limit()`].
+///
+/// [Failures][MessageLevel::Failure] are never limited.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RateLimit {
+    /// The amount of messages a task may send per `period`.
+    pub messages: usize,
+    /// The duration after which a task may send `messages` again, counting from its first message.
+    pub period: Duration,
 }
 
 /// A ring buffer for messages.
+///
+/// Optionally, [failure][MessageLevel::Failure] and [success][MessageLevel::Success] messages that would be overwritten
+/// are retained in a separate small buffer, so they aren't lost if many [info][MessageLevel::Info] messages are sent.
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct MessageRingBuffer {
     pub(crate) buf: Vec<Message>,
     cursor: usize,
-    total: usize,
+    /// The sequence number of the next message to be stored.
+    next_seq: u64,
+    retained: VecDeque<Message>,
+    retained_capacity: usize,
+    dedupe_window: Option<Duration>,
+    rate_limit: Option<RateLimit>,
+    /// The time each origin started its current period at, along with the amount of messages it sent since.
+    periods: HashMap<Origin, (SystemTime, usize)>,
+    suppressed: usize,
+    /// Identifies this buffer, to detect copy states obtained from another one.
+    generation: u64,
+}
+
+/// Return an identifier unlikely to be used by any other buffer, even in other processes.
+fn new_generation() -> u64 {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let nanos = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos() as u64;
+    nanos.wrapping_add(COUNTER.fetch_add(1, Ordering::Relaxed))
 }
 
 impl MessageRingBuffer {
     /// Create a new instance the ability to hold `capacity` amount of messages.
     pub fn with_capacity(capacity: usize) -> MessageRingBuffer {
+        Self::with_capacity_and_retention(capacity, 0)
+    }
+
+    /// Create a new instance the ability to hold `capacity` amount of messages, retaining up to `retained_capacity`
+    /// of the latest failure and success messages even after they were overwritten.
+    pub fn with_capacity_and_retention(capacity: usize, retained_capacity: usize) -> MessageRingBuffer {
         MessageRingBuffer {
             buf: Vec::with_capacity(capacity),
             cursor: 0,
-            total: 0,
+            next_seq: 0,
+            retained: VecDeque::with_capacity(retained_capacity),
+            retained_capacity,
+            dedupe_window: None,
+            rate_limit: None,
+            periods: HashMap::new(),
+            suppressed: 0,
+            generation: new_generation(),
         }
     }
 
+    /// If `window` is set, collapse messages into an identical one sent within `window` before them, counting them
+    /// instead of storing each of them.
+    ///
+    /// Messages are identical if their level, origin and text are the same. This prevents retry loops from flooding
+    /// the buffer with the same failure. The message they are collapsed into becomes the newest one, with a new
+    /// [sequence number][Message::seq], so consumers of [new messages][Self::copy_new()] learn about its count.
+    pub fn set_dedupe_window(&mut self, window: Option<Duration>) {
+        self.dedupe_window = window;
+    }
+
+    /// If `limit` is set, drop messages of each origin which sends more messages than allowed by it, keeping only the
+    /// amount of dropped messages.
+    ///
+    /// Failures are always kept, as are messages collapsed into an identical one as configured with
+    /// [`set_dedupe_window()`][Self::set_dedupe_window()], and neither counts towards the limit. Origins are distinct if
+    /// their key, id or name differ.
+    pub fn set_rate_limit(&mut self, limit: Option<RateLimit>) {
+        self.rate_limit = limit;
+        self.periods.clear();
+    }
+
+    /// Return the amount of messages dropped as their origin exceeded the [rate limit][Self::set_rate_limit()].
+    pub fn suppressed(&self) -> usize {
+        self.suppressed
+    }
+
     /// Push a `message` from `origin` at severity `level` into the buffer, possibly overwriting the last message added.
-    pub fn push_overwrite(&mut self, level: MessageLevel, origin: String, message: impl Into<String>) {
+    pub fn push_overwrite(&mut self, level: MessageLevel, origin: impl Into<Origin>, message: impl Into<String>) {
         let msg = Message {
             time: SystemTime::now(),
             level,
-            origin,
+            origin: origin.into(),
             message: message.into(),
+            count: 1,
+            seq: self.next_seq,
         };
+        if let Some(idx) = self.identical_within_window(&msg) {
+            let newest = self.move_to_newest(idx);
+            newest.count += 1;
+            newest.time = msg.time;
+            newest.seq = msg.seq;
+            self.next_seq += 1;
+            return;
+        }
+        if self.exceeds_rate_limit(&msg) {
+            self.suppressed = self.suppressed.wrapping_add(1);
+            return;
+        }
+        self.next_seq += 1;
         if self.has_capacity() {
             self.buf.push(msg)
+        } else if self.buf.is_empty() {
+            // Without any capacity, the message is overwritten right away.
+            self.retain(msg);
         } else {
-            self.buf[self.cursor] = msg;
+            let overwritten = std::mem::replace(&mut self.buf[self.cursor], msg);
+            self.retain(overwritten);
             self.cursor = (self.cursor + 1) % self.buf.len();
         }
-        self.total = self.total.wrapping_add(1);
     }
 
-    /// Copy all messages currently contained in the buffer to `out`.
+    /// Return the approximate amount of bytes used by this buffer and the messages it holds.
+    #[cfg(feature = "progress-tree")]
+    pub(crate) fn memory_estimate(&self) -> usize {
+        let message_size = std::mem::size_of::<Message>();
+        std::mem::size_of::<Self>()
+            + (self.buf.capacity() + self.retained.capacity()) * message_size
+            + self.periods.capacity() * std::mem::size_of::<(Origin, (SystemTime, usize))>()
+            + self.periods.keys().map(|origin| origin.name.capacity()).sum::<usize>()
+            + self
+                .buf
+                .iter()
+                .chain(self.retained.iter())
+                .map(|msg| msg.origin.name.capacity() + msg.message.capacity())
+                .sum::<usize>()
+    }
+
+    /// Copy all messages currently contained in the buffer to `out`, including retained ones, ordered from oldest to newest.
     pub fn copy_all(&self, out: &mut Vec<Message>) {
         out.clear();
-        if self.buf.is_empty() {
-            return;
-        }
-        out.extend_from_slice(&self.buf[self.cursor % self.buf.len()..]);
-        if self.cursor != self.buf.len() {
-            out.extend_from_slice(&self.buf[..self.cursor]);
-        }
+        out.extend(self.retained.iter().cloned());
+        let (newer, older) = self.buf.split_at(self.cursor);
+        out.extend_from_slice(older);
+        out.extend_from_slice(newer);
     }
 
     /// Copy all new messages into `out` that where received since the last time this method was called provided
-    /// its `previous` return value.
+    /// its `previous` return value, ordered from oldest to newest.
+    ///
+    /// Retained messages are copied as well if they are new, which happens if more messages were received than the
+    /// buffer can hold since then. If `previous` was obtained from another buffer, for instance one of a previous run
+    /// of the program, it's ignored and all messages are copied.
     pub fn copy_new(&self, out: &mut Vec<Message>, previous: Option<MessageCopyState>) -> MessageCopyState {
-        out.clear();
-        match previous {
-            Some(MessageCopyState { cursor, buf_len, total }) => {
-                if self.total.saturating_sub(total) >= self.buf.capacity() {
-                    self.copy_all(out);
-                } else {
-                    let new_elements_below_cap = self.buf.len().saturating_sub(buf_len);
-                    let cursor_ofs: isize = self.cursor as isize - cursor as isize;
-                    match cursor_ofs {
-                        // there was some capacity left without wrapping around
-                        0 => {
-                            out.extend_from_slice(&self.buf[self.buf.len() - new_elements_below_cap..]);
-                        }
-                        // cursor advanced
-                        c if c > 0 => {
-                            out.extend_from_slice(&self.buf[(cursor % self.buf.len())..self.cursor]);
-                        }
-                        // cursor wrapped around
-                        c if c < 0 => {
-                            out.extend_from_slice(&self.buf[(cursor % self.buf.len())..]);
-                            out.extend_from_slice(&self.buf[..self.cursor]);
-                        }
-                        _ => unreachable!("logic dictates that… yeah, you really shouldn't ever see this!"),
-                    }
-                }
+        match previous.filter(|previous| previous.generation == self.generation) {
+            Some(MessageCopyState {
+                next_seq,
+                generation: _,
+            }) => {
+                out.clear();
+                let (newer, older) = self.buf.split_at(self.cursor);
+                out.extend(
+                    self.retained
+                        .iter()
+                        .chain(older)
+                        .chain(newer)
+                        .filter(|msg| msg.seq >= next_seq)
+                        .cloned(),
+                );
             }
             None => self.copy_all(out),
         };
         MessageCopyState {
-            cursor: self.cursor,
-            buf_len: self.buf.len(),
-            total: self.total,
+            next_seq: self.next_seq,
+            generation: self.generation,
         }
     }
 
+    /// Returns the maximum amount of messages that can be held, including retained ones.
+    pub fn capacity(&self) -> usize {
+        self.buf.capacity() + self.retained_capacity
+    }
+
+    /// Return the index of the newest message identical to `msg` that was sent within the dedupe window before it, if there is one.
+    fn identical_within_window(&self, msg: &Message) -> Option<usize> {
+        let window = self.dedupe_window?;
+        let len = self.buf.len();
+        // The cursor is at the oldest message, or at the start if the buffer isn't full yet.
+        (1..=len)
+            .map(|offset| (self.cursor + len - offset) % len)
+            .take_while(|&idx| {
+                msg.time
+                    .duration_since(self.buf[idx].time)
+                    .map_or(true, |elapsed| elapsed <= window)
+            })
+            .find(|&idx| {
+                let candidate = &self.buf[idx];
+                candidate.level == msg.level && candidate.origin == msg.origin && candidate.message == msg.message
+            })
+    }
+
+    /// Move the message at `idx` behind all others, keeping their order, and return it.
+    fn move_to_newest(&mut self, idx: usize) -> &mut Message {
+        let len = self.buf.len();
+        let physical = |logical: usize| (self.cursor + logical) % len;
+        let mut logical = (idx + len - self.cursor) % len;
+        while logical + 1 < len {
+            self.buf.swap(physical(logical), physical(logical + 1));
+            logical += 1;
+        }
+        &mut self.buf[physical(logical)]
+    }
+
+    /// Return true if the origin of `msg` sent as many messages as the rate limit allows within its current period,
+    /// or count `msg` towards it otherwise.
+    fn exceeds_rate_limit(&mut self, msg: &Message) -> bool {
+        let Some(RateLimit { messages, period }) = self.rate_limit else {
+            return false;
+        };
+        if msg.level == MessageLevel::Failure {
+            return false;
+        }
+        let is_over = |started_at: SystemTime| {
+            msg.time
+                .duration_since(started_at)
+                .is_ok_and(|elapsed| elapsed > period)
+        };
+        if !self.periods.contains_key(&msg.origin) {
+            self.periods.retain(|_, (started_at, _)| !is_over(*started_at));
+            self.periods.insert(msg.origin.clone(), (msg.time, 0));
+        }
+        let (started_at, count) = self.periods.get_mut(&msg.origin).expect("just inserted");
+        if is_over(*started_at) {
+            *started_at = msg.time;
+            *count = 0;
+        }
+        if *count >= messages {
+            return true;
+        }
+        *count += 1;
+        false
+    }
+
+    fn retain(&mut self, msg: Message) {
+        if self.retained_capacity == 0 || msg.level == MessageLevel::Info {
+            return;
+        }
+        if self.retained.len() == self.retained_capacity {
+            self.retained.pop_front();
+        }
+        self.retained.push_back(msg);
+    }
+
     fn has_capacity(&self) -> bool {
         self.buf.len() < self.buf.capacity()
     }
@@ -119,8 +356,20 @@ impl MessageRingBuffer {
 /// State used to keep track of what's new since the last time message were copied.
 ///
 /// Note that due to the nature of a ring buffer, there is no guarantee that you see all messages.
+///
+/// With the `serde` feature toggle, it can be persisted to resume reading where a consumer stopped, for instance
+/// after reconnecting. Using it with a buffer other than the one it was obtained from copies all messages.
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MessageCopyState {
-    cursor: usize,
-    buf_len: usize,
-    total: usize,
+    /// The sequence number of the first message not yet seen.
+    next_seq: u64,
+    generation: u64,
+}
+
+impl MessageCopyState {
+    /// Return the [sequence number][Message::seq] of the first message that wasn't copied yet.
+    pub fn next_seq(&self) -> u64 {
+        self.next_seq
+    }
 }
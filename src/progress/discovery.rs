@@ -0,0 +1,83 @@
+use crate::{progress::Step, unit::Unit, Progress};
+
+/// Drive a progress which is unbounded while discovering the work to do, and bounded once its total is known.
+///
+/// The step is kept when the total becomes known, so work done during discovery counts towards it and renderers switch
+/// from the unbounded display to a bar that is filled accordingly, instead of starting from zero.
+///
+/// ```
+/// use prodash::progress::Discovery;
+///
+/// let root = prodash::tree::Root::new();
+/// let mut files = Discovery::new(root.add_child("index"), "files");
+/// files.inc_by(40);
+/// assert!(files.is_discovering());
+/// files.set_total(100);
+/// assert_eq!(files.inner().step(), Some(40));
+/// assert_eq!(files.inner().max(), Some(100));
+/// ```
+pub struct Discovery<P> {
+    progress: P,
+}
+
+impl<P: Progress> Discovery<P> {
+    /// Create a new instance which counts `unit` on `progress`, which is initialized to be unbounded.
+    pub fn new(mut progress: P, unit: impl Into<Unit>) -> Self {
+        progress.init(None, Some(unit.into()));
+        Discovery { progress }
+    }
+
+    /// Set the `total` amount of steps once it's known, without changing the current step.
+    ///
+    /// It can be called again if the total turns out to be different.
+    pub fn set_total(&mut self, total: Step) {
+        self.progress.set_max(Some(total));
+        if self.progress.max() != Some(total) {
+            // The progress wasn't initialized to take a maximum, like without units in `minimal` mode.
+            let step = self.progress.step();
+            let unit = self.progress.unit();
+            self.progress.init(Some(total), unit);
+            self.progress.set(step);
+        }
+    }
+
+    /// Return the total amount of steps, or `None` while discovering.
+    pub fn total(&self) -> Option<Step> {
+        self.progress.max()
+    }
+
+    /// Return `true` if the total amount of steps isn't known yet.
+    pub fn is_discovering(&self) -> bool {
+        self.total().is_none()
+    }
+
+    /// Set the current step to `step`.
+    pub fn set(&self, step: Step) {
+        self.progress.set(step)
+    }
+
+    /// Increment the current step by `step`.
+    pub fn inc_by(&self, step: Step) {
+        self.progress.inc_by(step)
+    }
+
+    /// Increment the current step by one.
+    pub fn inc(&self) {
+        self.inc_by(1)
+    }
+
+    /// Return the current step.
+    pub fn step(&self) -> Step {
+        self.progress.step()
+    }
+
+    /// Return the progress we advance.
+    pub fn inner(&self) -> &P {
+        &self.progress
+    }
+
+    /// Return the progress we advance, consuming this instance.
+    pub fn into_inner(self) -> P {
+        self.progress
+    }
+}
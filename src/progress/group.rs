@@ -0,0 +1,208 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{atomic::Ordering, Arc},
+};
+
+use crate::{
+    messages::{Message, MessageCopyState},
+    progress::{AtomicStep, Id, Key, Step, Task, Value, UNKNOWN},
+    Root, Unit, WeakRoot,
+};
+
+/// Move siblings in `tasks` sharing the same [`Id`] under a new organizational task if there are at least
+/// `min_group_size` of them, but at least two, to keep trees with many similar tasks readable.
+///
+/// `tasks` is expected to be in order of hierarchy, as produced by [`Root::sorted_snapshot()`]. Each new task is named
+/// after the id and the amount of tasks it groups, like `FILE (300)`, and shows their combined progress.
+/// Tasks without id are never grouped, and neither are those which would end up below the maximum nesting level.
+pub fn group_by_id(tasks: &mut Vec<(Key, Task)>, min_group_size: usize) {
+    let ungrouped = std::mem::take(tasks);
+    append_grouped(&ungrouped, min_group_size.max(2), tasks);
+}
+
+/// Split `siblings` into subtrees, each being a task followed by its descendants.
+fn subtrees(siblings: &[(Key, Task)]) -> Vec<&[(Key, Task)]> {
+    let level = match siblings.first() {
+        Some((key, _)) => key.level(),
+        None => return Vec::new(),
+    };
+    let mut subtrees = Vec::new();
+    let mut start = 0;
+    for (idx, (key, _)) in siblings.iter().enumerate().skip(1) {
+        if key.level() <= level {
+            subtrees.push(&siblings[start..idx]);
+            start = idx;
+        }
+    }
+    subtrees.push(&siblings[start..]);
+    subtrees
+}
+
+fn append_grouped(siblings: &[(Key, Task)], min_group_size: usize, out: &mut Vec<(Key, Task)>) {
+    let subtrees = subtrees(siblings);
+    let mut count_by_id = HashMap::<Id, usize>::new();
+    for subtree in &subtrees {
+        *count_by_id.entry(subtree[0].1.id).or_default() += 1;
+    }
+    let mut grouped = HashSet::new();
+    for subtree in &subtrees {
+        let (key, task) = &subtree[0];
+        let count = count_by_id[&task.id];
+        if task.id == UNKNOWN || count < min_group_size {
+            append_subtree(subtree, min_group_size, out);
+            continue;
+        }
+        if !grouped.insert(task.id) {
+            continue;
+        }
+        let members: Vec<_> = subtrees.iter().filter(|subtree| subtree[0].1.id == task.id).collect();
+        let mut descendants = Vec::new();
+        for member in &members {
+            append_subtree(member, min_group_size, &mut descendants);
+        }
+        let nested: Option<Vec<_>> = descendants
+            .iter()
+            .map(|(member_key, task)| member_key.nested_under(key).map(|nested| (nested, task.clone())))
+            .collect();
+        match nested {
+            Some(nested) => {
+                out.push((
+                    key.clone(),
+                    group_task(task.id, members.iter().map(|member| &member[0].1)),
+                ));
+                out.extend(nested);
+            }
+            None => out.extend(descendants),
+        }
+    }
+}
+
+fn append_subtree(subtree: &[(Key, Task)], min_group_size: usize, out: &mut Vec<(Key, Task)>) {
+    let (parent, descendants) = subtree.split_first().expect("subtrees are never empty");
+    out.push(parent.clone());
+    append_grouped(descendants, min_group_size, out);
+}
+
+/// Create the organizational task for `members`, which share `id`, with their combined progress.
+///
+/// It has no progress if none of its members has, and is only bounded if all members with progress are.
+fn group_task<'a>(id: Id, members: impl ExactSizeIterator<Item = &'a Task>) -> Task {
+    let count = members.len();
+    let mut combined: Option<(Step, Option<Step>, Option<Arc<Unit>>)> = None;
+    for progress in members.filter_map(|task| task.progress.as_ref()) {
+        let (step, max) = (progress.step.load(Ordering::SeqCst), progress.max());
+        combined = Some(match combined {
+            None => (step, max, progress.unit.clone()),
+            Some((sum, done_at, unit)) => (
+                sum + step,
+                done_at.zip(max).map(|(lhs, rhs)| lhs + rhs),
+                unit.or_else(|| progress.unit.clone()),
+            ),
+        });
+    }
+    Task {
+        name: format!("{} ({count})", id_name(id)),
+        id,
+        progress: combined.map(|(step, done_at, unit)| Value {
+            step: Arc::new(AtomicStep::new(step)),
+            done_at,
+            unit,
+            ..Default::default()
+        }),
+        ..Default::default()
+    }
+}
+
+/// Return `id` as ASCII if it's printable, or as hexadecimal otherwise.
+fn id_name(id: Id) -> String {
+    let name = std::str::from_utf8(&id).unwrap_or_default().trim_end_matches('\0');
+    if !name.is_empty() && name.bytes().all(|b| b.is_ascii_graphic()) {
+        name.to_owned()
+    } else {
+        id.iter().map(|b| format!("{b:02x}")).collect()
+    }
+}
+
+/// A [`Root`] or [`WeakRoot`] whose snapshots are [grouped by id][group_by_id()], which makes grouping available to
+/// all renderers.
+///
+/// ```
+/// use prodash::{progress::GroupById, Root};
+///
+/// let root = prodash::tree::Root::new();
+/// let progress = GroupById::new(root.downgrade(), 100);
+/// // Pass `progress` to any renderer instead of `root.downgrade()`.
+/// # drop(progress);
+/// ```
+#[derive(Clone)]
+pub struct GroupById<T> {
+    inner: T,
+    min_group_size: usize,
+}
+
+impl<T> GroupById<T> {
+    /// Group the tasks of `inner` if there are at least `min_group_size` siblings with the same id.
+    pub fn new(inner: T, min_group_size: usize) -> Self {
+        GroupById { inner, min_group_size }
+    }
+
+    /// Return the root whose tasks we group.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+impl<T: WeakRoot> WeakRoot for GroupById<T> {
+    type Root = GroupById<T::Root>;
+
+    fn upgrade(&self) -> Option<Self::Root> {
+        self.inner
+            .upgrade()
+            .map(|inner| GroupById::new(inner, self.min_group_size))
+    }
+}
+
+impl<T: Root> Root for GroupById<T> {
+    type WeakRoot = GroupById<T::WeakRoot>;
+
+    fn messages_capacity(&self) -> usize {
+        self.inner.messages_capacity()
+    }
+
+    fn num_tasks(&self) -> usize {
+        self.inner.num_tasks()
+    }
+
+    fn sorted_snapshot(&self, out: &mut Vec<(Key, Task)>) {
+        self.inner.sorted_snapshot(out);
+        group_by_id(out, self.min_group_size);
+    }
+
+    fn copy_messages(&self, out: &mut Vec<Message>) {
+        self.inner.copy_messages(out)
+    }
+
+    fn copy_new_messages(&self, out: &mut Vec<Message>, prev: Option<MessageCopyState>) -> MessageCopyState {
+        self.inner.copy_new_messages(out, prev)
+    }
+
+    fn downgrade(&self) -> Self::WeakRoot {
+        GroupById::new(self.inner.downgrade(), self.min_group_size)
+    }
+
+    fn is_clock_paused(&self) -> bool {
+        self.inner.is_clock_paused()
+    }
+
+    fn started_at(&self) -> Option<std::time::SystemTime> {
+        self.inner.started_at()
+    }
+
+    fn mutations(&self) -> Option<u64> {
+        self.inner.mutations()
+    }
+
+    fn suppressed_messages(&self) -> usize {
+        self.inner.suppressed_messages()
+    }
+}
@@ -1,18 +1,25 @@
 use std::ops::{Index, IndexMut};
 
+use smallvec::SmallVec;
+
 use crate::progress::Task;
 
-/// a level in the hierarchy of key components
+/// a level in the hierarchy of key components, with the root at level 0.
+///
+/// It's wide enough to never be exhausted, as a key at the [maximum level][Key::max_level()] would occupy gigabytes.
+pub type Level = u32;
+
+/// The identifier of a task among its siblings.
 ///
 /// _NOTE:_ This means we will show weird behaviour if there are more than 2^16 tasks at the same time on a level
 /// as multiple progress handles will manipulate the same state.
-pub type Level = u8;
-
 pub(crate) type Id = u16;
 
 /// A type identifying a spot in the hierarchy of `Tree` items.
-#[derive(Copy, Clone, Default, Hash, Eq, PartialEq, Ord, PartialOrd, Debug)]
-pub struct Key(Option<Id>, Option<Id>, Option<Id>, Option<Id>, Option<Id>, Option<Id>);
+///
+/// It holds one identifier per level, and keys of up to 8 levels don't allocate.
+#[derive(Clone, Default, Hash, Eq, PartialEq, Ord, PartialOrd, Debug)]
+pub struct Key(SmallVec<[Id; 8]>);
 
 /// Determines if a sibling is above or below in the given level of hierarchy
 #[derive(Default, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Debug)]
@@ -41,54 +48,66 @@ impl SiblingLocation {
     }
 }
 
-/// A type providing information about what's above and below `Tree` items.
-#[derive(Copy, Clone, Default, Eq, PartialEq, Ord, PartialOrd, Debug)]
-pub struct Adjacency(
-    pub SiblingLocation,
-    pub SiblingLocation,
-    pub SiblingLocation,
-    pub SiblingLocation,
-    pub SiblingLocation,
-    pub SiblingLocation,
-);
+/// A type providing information about what's above and below `Tree` items, with one [`SiblingLocation`] per level
+/// starting at level 1.
+///
+/// All levels past the last one with a sibling are [`NotFound`][SiblingLocation::NotFound].
+#[derive(Clone, Default, Debug)]
+pub struct Adjacency(SmallVec<[SiblingLocation; 8]>);
 
 impl Adjacency {
     /// Return the level at which this sibling is located in the hierarchy.
     pub fn level(&self) -> Level {
-        use SiblingLocation::*;
-        match self {
-            Adjacency(NotFound, NotFound, NotFound, NotFound, NotFound, NotFound) => 0,
-            Adjacency(_a, NotFound, NotFound, NotFound, NotFound, NotFound) => 1,
-            Adjacency(_a, _b, NotFound, NotFound, NotFound, NotFound) => 2,
-            Adjacency(_a, _b, _c, NotFound, NotFound, NotFound) => 3,
-            Adjacency(_a, _b, _c, _d, NotFound, NotFound) => 4,
-            Adjacency(_a, _b, _c, _d, _e, NotFound) => 5,
-            Adjacency(_a, _b, _c, _d, _e, _f) => 6,
-        }
+        self.0
+            .iter()
+            .rposition(|location| *location != SiblingLocation::NotFound)
+            .map_or(0, |idx| (idx + 1) as Level)
     }
-    /// Get a reference to the sibling location at `level`.
+    /// Get a reference to the sibling location at `level`, or `None` if `level` is 0.
     pub fn get(&self, level: Level) -> Option<&SiblingLocation> {
-        Some(match level {
-            1 => &self.0,
-            2 => &self.1,
-            3 => &self.2,
-            4 => &self.3,
-            5 => &self.4,
-            6 => &self.5,
-            _ => return None,
-        })
+        const NOT_FOUND: SiblingLocation = SiblingLocation::NotFound;
+        let idx = (level as usize).checked_sub(1)?;
+        Some(self.0.get(idx).unwrap_or(&NOT_FOUND))
     }
-    /// Get a mutable reference to the sibling location at `level`.
+    /// Get a mutable reference to the sibling location at `level`, or `None` if `level` is 0.
     pub fn get_mut(&mut self, level: Level) -> Option<&mut SiblingLocation> {
-        Some(match level {
-            1 => &mut self.0,
-            2 => &mut self.1,
-            3 => &mut self.2,
-            4 => &mut self.3,
-            5 => &mut self.4,
-            6 => &mut self.5,
-            _ => return None,
-        })
+        let idx = (level as usize).checked_sub(1)?;
+        if idx >= self.0.len() {
+            self.0.resize(idx + 1, SiblingLocation::NotFound);
+        }
+        self.0.get_mut(idx)
+    }
+
+    /// The sibling locations of all levels up to our [level][Adjacency::level()].
+    fn levels(&self) -> &[SiblingLocation] {
+        &self.0[..self.level() as usize]
+    }
+}
+
+impl<const N: usize> From<[SiblingLocation; N]> for Adjacency {
+    /// Create a new instance with the sibling locations of levels 1 to `N`.
+    fn from(levels: [SiblingLocation; N]) -> Self {
+        Adjacency(levels.into_iter().collect())
+    }
+}
+
+impl PartialEq for Adjacency {
+    fn eq(&self, other: &Self) -> bool {
+        self.levels() == other.levels()
+    }
+}
+
+impl Eq for Adjacency {}
+
+impl PartialOrd for Adjacency {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Adjacency {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.levels().cmp(other.levels())
     }
 }
 
@@ -107,62 +126,57 @@ impl IndexMut<Level> for Adjacency {
 
 impl Key {
     /// Return the key to the child identified by `child_id` located in a new nesting level below `self`.
-    pub fn add_child(self, child_id: Id) -> Key {
-        match self {
-            Key(None, None, None, None, None, None) => Key(Some(child_id), None, None, None, None, None),
-            Key(a, None, None, None, None, None) => Key(a, Some(child_id), None, None, None, None),
-            Key(a, b, None, None, None, None) => Key(a, b, Some(child_id), None, None, None),
-            Key(a, b, c, None, None, None) => Key(a, b, c, Some(child_id), None, None),
-            Key(a, b, c, d, None, None) => Key(a, b, c, d, Some(child_id), None),
-            Key(a, b, c, d, e, _f) => {
-                crate::warn!("Maximum nesting level reached. Adding tasks to current parent");
-                Key(a, b, c, d, e, Some(child_id))
-            }
-        }
+    pub fn add_child(&self, child_id: Id) -> Key {
+        let mut child = self.clone();
+        child.0.push(child_id);
+        child
+    }
+
+    /// Return the key of the parent of `self`, or `None` if `self` is the root at level 0.
+    pub fn parent(&self) -> Option<Key> {
+        let (_, parent) = self.0.split_last()?;
+        Some(Key(parent.into()))
+    }
+
+    /// Return the keys of all ancestors of `self`, starting with its parent and ending with the root at level 0.
+    pub fn ancestors(&self) -> impl Iterator<Item = Key> {
+        std::iter::successors(self.parent(), Key::parent)
+    }
+
+    /// Return true if `self` is the parent of `other`, or the parent of one of its ancestors.
+    pub fn is_ancestor_of(&self, other: &Key) -> bool {
+        self.level() < other.level() && self.shares_parent_with(other, self.level())
     }
 
     /// The level of hierarchy a node is placed in, i.e. the amount of path components
     pub fn level(&self) -> Level {
-        match self {
-            Key(None, None, None, None, None, None) => 0,
-            Key(Some(_), None, None, None, None, None) => 1,
-            Key(Some(_), Some(_), None, None, None, None) => 2,
-            Key(Some(_), Some(_), Some(_), None, None, None) => 3,
-            Key(Some(_), Some(_), Some(_), Some(_), None, None) => 4,
-            Key(Some(_), Some(_), Some(_), Some(_), Some(_), None) => 5,
-            Key(Some(_), Some(_), Some(_), Some(_), Some(_), Some(_)) => 6,
-            _ => unreachable!("This is a bug - Keys follow a certain pattern"),
-        }
+        self.0.len() as Level
     }
 
     /// Return the identifier for the item at `level`.
     fn get(&self, level: Level) -> Option<&Id> {
-        match level {
-            1 => self.0.as_ref(),
-            2 => self.1.as_ref(),
-            3 => self.2.as_ref(),
-            4 => self.3.as_ref(),
-            5 => self.4.as_ref(),
-            6 => self.5.as_ref(),
-            _ => None,
-        }
+        self.0.get((level as usize).checked_sub(1)?)
     }
 
     /// Return true if the item identified by `other` shares the parent at `parent_level`.
     pub fn shares_parent_with(&self, other: &Key, parent_level: Level) -> bool {
-        if parent_level < 1 {
-            return true;
+        let parent_level = parent_level as usize;
+        match (self.0.get(..parent_level), other.0.get(..parent_level)) {
+            (Some(lhs), Some(rhs)) => lhs == rhs,
+            _ => false,
         }
-        for level in 1..=parent_level {
-            if let (Some(lhs), Some(rhs)) = (self.get(level), other.get(level)) {
-                if lhs != rhs {
-                    return false;
-                }
-            } else {
-                return false;
-            }
+    }
+
+    /// Return the key `self` would have if its ancestor at the level of `group`, or `self` itself, was moved to be
+    /// a child of `group`, or `None` if that would exceed the maximum nesting level.
+    pub(crate) fn nested_under(&self, group: &Key) -> Option<Key> {
+        let level = group.level() as usize;
+        if level == 0 || level > self.level() as usize || self.level() == Key::max_level() {
+            return None;
         }
-        true
+        let mut nested = group.clone();
+        nested.0.extend_from_slice(&self.0[level - 1..]);
+        Some(nested)
     }
 
     /// Compute the adjacency map for the key in `sorted` at the given `index`.
@@ -243,7 +257,29 @@ impl Key {
 
     /// The maximum amount of path components we can represent.
     pub const fn max_level() -> Level {
-        6
+        Level::MAX
+    }
+}
+
+/// Serialize the identifiers of all levels, starting with the first one.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Key {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.as_slice().serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Key {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let ids = Vec::<Id>::deserialize(deserializer)?;
+        if ids.len() > Key::max_level() as usize {
+            return Err(serde::de::Error::invalid_length(
+                ids.len(),
+                &"at most as many identifiers as there are levels",
+            ));
+        }
+        Ok(Key(ids.into()))
     }
 }
 
@@ -59,7 +59,7 @@ impl Log {
 
 impl Log {
     fn maybe_log(&self) {
-        if self.current_level > self.max_level {
+        if self.current_level > self.max_level || crate::render::is_log_suppressed() {
             return;
         }
         let step = self.step();
@@ -130,6 +130,9 @@ impl Progress for Log {
     }
 
     fn message(&self, level: MessageLevel, message: String) {
+        if crate::render::is_log_suppressed() {
+            return;
+        }
         match level {
             MessageLevel::Info => log::info!("ℹ{} → {}", self.name, message),
             MessageLevel::Failure => log::error!("𐄂{} → {}", self.name, message),
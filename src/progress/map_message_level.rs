@@ -0,0 +1,168 @@
+use std::sync::Arc;
+
+use crate::{
+    messages::MessageLevel,
+    progress::{Id, Step, StepShared},
+    Count, NestedProgress, Progress, Unit,
+};
+
+/// A [`Progress`] which passes the level of each message through a function before sending it to the progress it
+/// wraps, as created by [`Progress::map_message_level()`].
+///
+/// This allows to tone down a noisy library which reports retries as failures, or to raise the level of messages
+/// that matter to the application, without changing the code producing them. Children are wrapped as well, sharing
+/// the same function. [`done()`][Progress::done()] and [`fail()`][Progress::fail()] finish the task with the
+/// outcome the mapped level indicates.
+///
+/// ```
+/// use prodash::{messages::MessageLevel, Progress};
+///
+/// let root = prodash::tree::Root::new();
+/// let progress = root.add_child("fetch").map_message_level(|level, message| match level {
+///     MessageLevel::Failure if message.contains("retrying") => MessageLevel::Info,
+///     level => level,
+/// });
+/// // This is shown as information.
+/// progress.fail("connection reset, retrying".into());
+/// ```
+pub struct MapMessageLevel<T, F> {
+    inner: T,
+    map: Arc<F>,
+}
+
+impl<T, F> MapMessageLevel<T, F>
+where
+    T: Progress,
+    F: Fn(MessageLevel, &str) -> MessageLevel + Send + Sync,
+{
+    /// Create a new instance to send the messages of `inner` with the level returned by `map`, which is called with
+    /// the original level and the message.
+    pub fn new(inner: T, map: F) -> Self {
+        MapMessageLevel {
+            inner,
+            map: Arc::new(map),
+        }
+    }
+
+    /// Finish with the outcome the mapped `level` indicates, or just send the message if it's informational.
+    fn finish(&self, level: MessageLevel, message: String) {
+        match (self.map)(level, &message) {
+            MessageLevel::Success => self.inner.done(message),
+            MessageLevel::Failure => self.inner.fail(message),
+            MessageLevel::Info => self.inner.info(message),
+        }
+    }
+
+    /// Return the progress we pass messages to.
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+
+    /// Return the progress we pass messages to, consuming this instance.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+impl<T, F> Count for MapMessageLevel<T, F>
+where
+    T: Progress,
+    F: Fn(MessageLevel, &str) -> MessageLevel + Send + Sync,
+{
+    fn set(&self, step: Step) {
+        self.inner.set(step)
+    }
+
+    fn step(&self) -> Step {
+        self.inner.step()
+    }
+
+    fn inc_by(&self, step: Step) {
+        self.inner.inc_by(step)
+    }
+
+    fn inc(&self) {
+        self.inner.inc()
+    }
+
+    fn counter(&self) -> StepShared {
+        self.inner.counter()
+    }
+}
+
+impl<T, F> Progress for MapMessageLevel<T, F>
+where
+    T: Progress,
+    F: Fn(MessageLevel, &str) -> MessageLevel + Send + Sync,
+{
+    fn init(&mut self, max: Option<Step>, unit: Option<Unit>) {
+        self.inner.init(max, unit)
+    }
+
+    fn unit(&self) -> Option<Unit> {
+        self.inner.unit()
+    }
+
+    fn max(&self) -> Option<Step> {
+        self.inner.max()
+    }
+
+    fn set_max(&mut self, max: Option<Step>) -> Option<Step> {
+        self.inner.set_max(max)
+    }
+
+    fn max_counter(&self) -> Option<StepShared> {
+        self.inner.max_counter()
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.inner.is_cancelled()
+    }
+
+    fn set_name(&mut self, name: String) {
+        self.inner.set_name(name)
+    }
+
+    fn name(&self) -> Option<String> {
+        self.inner.name()
+    }
+
+    fn id(&self) -> Id {
+        self.inner.id()
+    }
+
+    fn message(&self, level: MessageLevel, message: String) {
+        let level = (self.map)(level, &message);
+        self.inner.message(level, message)
+    }
+
+    fn done(&self, message: String) {
+        self.finish(MessageLevel::Success, message)
+    }
+
+    fn fail(&self, message: String) {
+        self.finish(MessageLevel::Failure, message)
+    }
+}
+
+impl<T, F> NestedProgress for MapMessageLevel<T, F>
+where
+    T: NestedProgress,
+    F: Fn(MessageLevel, &str) -> MessageLevel + Send + Sync,
+{
+    type SubProgress = MapMessageLevel<T::SubProgress, F>;
+
+    fn add_child(&mut self, name: impl Into<String>) -> Self::SubProgress {
+        MapMessageLevel {
+            inner: self.inner.add_child(name),
+            map: Arc::clone(&self.map),
+        }
+    }
+
+    fn add_child_with_id(&mut self, name: impl Into<String>, id: Id) -> Self::SubProgress {
+        MapMessageLevel {
+            inner: self.inner.add_child_with_id(name, id),
+            map: Arc::clone(&self.map),
+        }
+    }
+}
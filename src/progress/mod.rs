@@ -1,9 +1,9 @@
 use std::{
     sync::{
-        atomic::{AtomicUsize, Ordering},
+        atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
         Arc,
     },
-    time::SystemTime,
+    time::{Duration, SystemTime},
 };
 
 use crate::unit::Unit;
@@ -15,9 +15,27 @@ pub use key::Key;
 
 mod utils;
 
+mod phases;
+pub use phases::Phases;
+
+mod discovery;
+pub use discovery::Discovery;
+
+mod walk;
+pub use walk::Walk;
+
+mod scaled;
+pub use scaled::Scaled;
+
+mod map_message_level;
+pub use map_message_level::MapMessageLevel;
+
+mod group;
+pub use group::{group_by_id, GroupById};
+
 #[cfg(feature = "progress-log")]
 mod log;
-pub use utils::{Discard, DoOrDiscard, Either, ThroughputOnDrop};
+pub use utils::{Discard, DoOrDiscard, Either, Shared, ThroughputOnDrop};
 
 #[cfg(feature = "progress-log")]
 pub use self::log::Log;
@@ -43,22 +61,33 @@ pub type AtomicStep = AtomicUsize;
 /// As step, but shareable.
 pub type StepShared = Arc<AtomicStep>;
 
+/// The rate at which steps are made per second, stored as the bits of an `f64` to be shareable.
+///
+/// Use [`Value::rate()`] to read it conveniently.
+pub type RateShared = Arc<AtomicU64>;
+
 /// Indicate whether a progress can or cannot be made.
-#[derive(Default, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Debug, Hash)]
+///
+/// Reasons are usually string literals, but are owned when deserialized with the `serde` feature toggle.
+#[derive(Default, Clone, Eq, PartialEq, Ord, PartialOrd, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum State {
     /// Indicates a task is blocked and cannot indicate progress, optionally until the
     /// given time. The task cannot easily be interrupted.
-    Blocked(&'static str, Option<SystemTime>),
+    Blocked(std::borrow::Cow<'static, str>, Option<SystemTime>),
     /// Indicates a task cannot indicate progress, optionally until the
     /// given time. The task can be interrupted.
-    Halted(&'static str, Option<SystemTime>),
+    Halted(std::borrow::Cow<'static, str>, Option<SystemTime>),
     /// The task is running
     #[default]
     Running,
+    /// The task was finished, successfully unless `false`, and is kept in the tree even though its `tree::Item` was
+    /// dropped as configured with `tree::root::Options::retain_finished`.
+    Finished(bool),
 }
 
 /// Progress associated with some item in the progress tree.
-#[derive(Clone, Default, Debug)]
+#[derive(Default, Debug)]
 pub struct Value {
     /// The amount of progress currently made
     pub step: StepShared,
@@ -66,24 +95,107 @@ pub struct Value {
     ///
     /// If unset, the progress is unbounded.
     pub done_at: Option<Step>,
-    /// The unit associated with the progress.
-    pub unit: Option<Unit>,
+    /// The unit associated with the progress, shared with all tasks using the same unit.
+    pub unit: Option<Arc<Unit>>,
     /// Whether progress can be made or not
     pub state: State,
+    /// The amount of steps made per second, as last computed by a [`Throughput`][crate::Throughput] instance.
+    pub rate: RateShared,
+    /// The step at which no further progress has to be made if set, taking precedence over `done_at`,
+    /// with [`Step::MAX`] meaning the progress is unbounded.
+    ///
+    /// It's shared with everyone who obtained it through [`Progress::max_counter()`][crate::Progress::max_counter()],
+    /// and `done_at` is updated from it whenever this value is cloned, so snapshots see the latest bound.
+    pub shared_max: Option<StepShared>,
+    /// The amount of times work was done without counting it, as indicated by [`Item::tick()`][crate::tree::Item::tick()].
+    ///
+    /// Renderers use it to animate the task, independently of its step.
+    pub activity: StepShared,
+}
+
+impl Clone for Value {
+    fn clone(&self) -> Self {
+        Value {
+            step: Arc::clone(&self.step),
+            done_at: self.max(),
+            unit: self.unit.clone(),
+            state: self.state.clone(),
+            rate: Arc::clone(&self.rate),
+            shared_max: self.shared_max.clone(),
+            activity: Arc::clone(&self.activity),
+        }
+    }
 }
 
 impl std::hash::Hash for Value {
     fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
         let Self {
             step,
-            done_at,
+            done_at: _,
             unit,
             state: our_state,
+            rate: _,
+            shared_max: _,
+            activity,
         } = self;
-        done_at.hash(state);
+        self.max().hash(state);
         unit.hash(state);
         our_state.hash(state);
         step.load(Ordering::Relaxed).hash(state);
+        activity.load(Ordering::Relaxed).hash(state);
+    }
+}
+
+/// A `Value` as it is serialized, with the current state of all shared counters.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename = "Value")]
+struct SerializedValue {
+    step: Step,
+    done_at: Option<Step>,
+    unit: Option<Unit>,
+    state: State,
+    rate: f64,
+    activity: Step,
+}
+
+/// Serialize the current state of all shared counters, with the latest bound as `done_at`.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Value {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializedValue {
+            step: self.step.load(Ordering::Relaxed),
+            done_at: self.max(),
+            unit: self.unit.as_deref().cloned(),
+            state: self.state.clone(),
+            rate: self.rate(),
+            activity: self.activity.load(Ordering::Relaxed),
+        }
+        .serialize(serializer)
+    }
+}
+
+/// Deserialize into counters shared with nobody, which makes the value a snapshot.
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Value {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let SerializedValue {
+            step,
+            done_at,
+            unit,
+            state,
+            rate,
+            activity,
+        } = SerializedValue::deserialize(deserializer)?;
+        Ok(Value {
+            step: Arc::new(AtomicStep::new(step)),
+            done_at,
+            unit: unit.map(Unit::into_shared),
+            state,
+            rate: Arc::new(AtomicU64::new(rate.to_bits())),
+            shared_max: None,
+            activity: Arc::new(AtomicStep::new(activity)),
+        })
     }
 }
 
@@ -92,13 +204,103 @@ impl Value {
     ///
     /// A task half done would return `Some(0.5)`.
     pub fn fraction(&self) -> Option<f32> {
-        self.done_at
+        self.max()
             .map(|done_at| self.step.load(Ordering::SeqCst) as f32 / done_at as f32)
     }
+
+    /// Returns the step at which no further progress has to be made, or `None` if the progress is unbounded.
+    ///
+    /// It's read from [`shared_max`][Value::shared_max] if set, or `done_at` otherwise.
+    pub fn max(&self) -> Option<Step> {
+        match &self.shared_max {
+            Some(max) => Some(max.load(Ordering::SeqCst)).filter(|max| *max != Step::MAX),
+            None => self.done_at,
+        }
+    }
+
+    /// Returns the step to animate the task with, which changes whenever its step changes or it [ticks][crate::tree::Item::tick()].
+    pub fn animation_step(&self) -> Step {
+        self.step
+            .load(Ordering::Relaxed)
+            .wrapping_add(self.activity.load(Ordering::Relaxed))
+    }
+
+    /// Returns the amount of steps made per second, or `0.0` if it wasn't computed yet.
+    ///
+    /// Note that it is only updated if a renderer or another user of [`Throughput`][crate::Throughput] samples this value.
+    pub fn rate(&self) -> f64 {
+        f64::from_bits(self.rate.load(Ordering::Relaxed))
+    }
+}
+
+/// How the children of a task are ordered when displayed.
+#[derive(Default, Copy, Clone, Eq, PartialEq, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ChildOrder {
+    /// Show children in the order they were added.
+    #[default]
+    Creation,
+    /// Show children sorted by their name.
+    Name,
+    /// Show children sorted by their [id][Task::id].
+    Id,
+    /// Show children sorted by their [rank][Task::rank], lowest first.
+    Rank,
+}
+
+/// A point of interest within the progress of a task, like the boundary between two phases of work.
+#[derive(Clone, Debug, Hash, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Marker {
+    /// The step at which the marker was set.
+    pub step: Step,
+    /// The time at which the marker was set.
+    pub time: SystemTime,
+    /// A short description of what the marker stands for, like "headers done".
+    pub label: String,
+}
+
+/// The time spent in all measured scopes of a task with the same label, as recorded by `tree::MeasureScope::accumulate()`.
+#[derive(Clone, Debug, Hash, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Timing {
+    /// The label of the measured scopes, like "resolve deltas".
+    pub label: String,
+    /// The time spent in all of them.
+    pub total: Duration,
+    /// The amount of scopes that were measured.
+    pub count: usize,
+}
+
+/// A flag shared by a task and everyone holding a copy of it, like renderers, to request the task to stop.
+///
+/// Setting it has no effect unless the task checks it, for example with [`Progress::is_cancelled()`][crate::Progress::is_cancelled()].
+#[derive(Clone, Default, Debug)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    /// Request the task to stop.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    /// Return `true` if the task was requested to stop.
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+impl std::hash::Hash for CancelToken {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.is_cancelled().hash(state)
+    }
 }
 
 /// The value associated with a spot in the hierarchy.
+///
+/// With the `serde` feature toggle, it can be serialized as part of a snapshot, but its cancel token isn't.
 #[derive(Clone, Default, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Task {
     /// The name of the `Item` or task.
     pub name: String,
@@ -107,4 +309,90 @@ pub struct Task {
     pub id: Id,
     /// The progress itself, unless this value belongs to an `Item` serving as organizational unit.
     pub progress: Option<Value>,
+    /// A short and frequently changing note to display along with the progress, like the name of the file being processed.
+    pub note: Option<String>,
+    /// The order in which the children of this task are displayed.
+    pub child_order: ChildOrder,
+    /// The position of this task among its siblings if its parent orders its children by [rank][ChildOrder::Rank].
+    pub rank: i64,
+    /// Markers set on this task in the order they were set, as set by `tree::Item::mark()`.
+    pub markers: Vec<Marker>,
+    /// The total time spent in measured scopes of this task, per label and in the order they were first measured.
+    pub timings: Vec<Timing>,
+    /// A name set with `tree::Item::set_name()` while renames were debounced, along with the time at which it replaces
+    /// the current `name`.
+    pub pending_name: Option<(String, SystemTime)>,
+    /// The time at which `name` was last changed by `tree::Item::set_name()`, if renames are debounced.
+    pub renamed_at: Option<SystemTime>,
+    /// If true, a descendant of this task failed, which is only tracked if the tree escalates failures as configured with
+    /// `tree::root::Options::escalate_failures`.
+    pub failed_descendant: bool,
+    /// The duration this task is expected to take along with the time it was set, as set by `tree::Item::expect_duration()`.
+    pub expected_duration: Option<(Duration, SystemTime)>,
+    /// The time at which this task was added to the tree, which is unset for the task holding the name of the root.
+    pub created_at: Option<SystemTime>,
+    /// The time at which this task was finished by `tree::Item::done()` or `tree::Item::fail()`.
+    pub finished_at: Option<SystemTime>,
+    /// The amount of times this task was retried with `tree::Item::new_attempt()`, see [`attempt()`][Task::attempt()].
+    pub retries: u32,
+    /// The flag to request this task to stop, shared with the `tree::Item` it belongs to.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub cancel_token: CancelToken,
+}
+
+impl Task {
+    /// Return the approximate amount of bytes this task occupies on the heap, including what it shares with its `Item`.
+    #[cfg(feature = "progress-tree")]
+    pub(crate) fn heap_size(&self) -> usize {
+        use std::mem::size_of;
+        let arc_size = |value_size: usize| 2 * size_of::<usize>() + value_size;
+        let progress = self.progress.as_ref().map_or(0, |progress| {
+            arc_size(size_of::<AtomicStep>())
+                + arc_size(size_of::<AtomicU64>())
+                + progress
+                    .shared_max
+                    .as_ref()
+                    .map_or(0, |_| arc_size(size_of::<AtomicStep>()))
+                + progress
+                    .unit
+                    .as_ref()
+                    .map_or(0, |unit| arc_size(size_of::<Unit>()) + unit.heap_size())
+        });
+        self.name.capacity()
+            + self.note.as_ref().map_or(0, String::capacity)
+            + self.markers.capacity() * size_of::<Marker>()
+            + self.markers.iter().map(|marker| marker.label.capacity()).sum::<usize>()
+            + self.timings.capacity() * size_of::<Timing>()
+            + self.timings.iter().map(|timing| timing.label.capacity()).sum::<usize>()
+            + self.pending_name.as_ref().map_or(0, |(name, _)| name.capacity())
+            + arc_size(size_of::<AtomicBool>())
+            + progress
+    }
+
+    /// Return the number of the current attempt at this task, which is 1 unless it was [retried][Task::retries].
+    pub fn attempt(&self) -> u32 {
+        self.retries.saturating_add(1)
+    }
+
+    /// Return how long this task took until it was [finished][Task::finished_at], or how long it's running at `now`,
+    /// or `None` if its [creation time][Task::created_at] is unknown.
+    pub fn elapsed(&self, now: SystemTime) -> Option<Duration> {
+        let created_at = self.created_at?;
+        Some(
+            self.finished_at
+                .unwrap_or(now)
+                .duration_since(created_at)
+                .unwrap_or_default(),
+        )
+    }
+
+    /// Return by how much this task exceeded its [expected duration][Task::expected_duration] at `now`, or `None` if it
+    /// has none or is still within it.
+    pub fn overdue_by(&self, now: SystemTime) -> Option<Duration> {
+        let (expected, since) = self.expected_duration?;
+        now.duration_since(since)
+            .ok()?
+            .checked_sub(expected)
+            .filter(|overdue| !overdue.is_zero())
+    }
 }
@@ -0,0 +1,148 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::{progress::Step, Progress};
+
+struct Phase {
+    name: String,
+    /// The global step at which this phase starts.
+    start: Step,
+    /// The amount of global steps this phase covers.
+    len: Step,
+}
+
+/// Advance a single progress smoothly across a pipeline of named phases, each covering a part of it according to its weight.
+///
+/// Entering a phase maps its own steps from `0` to its maximum onto the range of the progress covered by the phase,
+/// so the progress doesn't jump back to zero for each phase, and it is named after the phase it's in.
+///
+/// ```
+/// use prodash::progress::Phases;
+///
+/// let root = prodash::tree::Root::new();
+/// let mut phases = Phases::new(root.add_child("clone"), [("resolve", 10), ("download", 70), ("checkout", 20)]);
+/// phases.enter("resolve", Some(5));
+/// phases.set(5);
+/// phases.enter("download", Some(200));
+/// phases.set(100);
+/// // 10% for resolving and half of the 70% for downloading.
+/// assert_eq!(phases.inner().step(), Some(4_500));
+/// ```
+pub struct Phases<P> {
+    progress: P,
+    name: String,
+    phases: Vec<Phase>,
+    current: Option<usize>,
+    max: Option<Step>,
+    step: AtomicUsize,
+}
+
+impl<P: Progress> Phases<P> {
+    /// The amount of steps of `progress` each unit of weight covers.
+    pub const STEPS_PER_WEIGHT: Step = 100;
+
+    /// Create a new instance which advances `progress` across `phases` in the given order, each being a name along with
+    /// its weight relative to all other phases.
+    ///
+    /// `progress` is initialized to cover [`STEPS_PER_WEIGHT`][Phases::STEPS_PER_WEIGHT] steps for each unit of weight.
+    pub fn new(mut progress: P, phases: impl IntoIterator<Item = (impl Into<String>, usize)>) -> Self {
+        let mut start = 0;
+        let phases: Vec<_> = phases
+            .into_iter()
+            .map(|(name, weight)| {
+                let len = weight.saturating_mul(Self::STEPS_PER_WEIGHT);
+                let phase = Phase {
+                    name: name.into(),
+                    start,
+                    len,
+                };
+                start = start.saturating_add(len);
+                phase
+            })
+            .collect();
+        progress.init(Some(start), None);
+        Phases {
+            name: progress.name().unwrap_or_default(),
+            progress,
+            phases,
+            current: None,
+            max: None,
+            step: AtomicUsize::new(0),
+        }
+    }
+
+    /// Enter the phase with the given `name`, which goes from step `0` to `max`, and return `true`.
+    ///
+    /// If `max` is `None`, the progress stays at the beginning of the phase until it's done.
+    /// Return `false` without changing anything if there is no phase with `name`.
+    pub fn enter(&mut self, name: &str, max: Option<Step>) -> bool {
+        let Some(idx) = self.phases.iter().position(|phase| phase.name == name) else {
+            return false;
+        };
+        self.current = Some(idx);
+        self.max = max;
+        self.step.store(0, Ordering::SeqCst);
+        self.progress.set_name(if self.name.is_empty() {
+            name.to_owned()
+        } else {
+            format!("{}: {name}", self.name)
+        });
+        self.update();
+        true
+    }
+
+    /// Return the name of the phase we are in, if one was entered.
+    pub fn current(&self) -> Option<&str> {
+        self.current.map(|idx| self.phases[idx].name.as_str())
+    }
+
+    /// Set the step of the current phase to `step`.
+    pub fn set(&self, step: Step) {
+        self.step.store(step, Ordering::SeqCst);
+        self.update();
+    }
+
+    /// Increment the step of the current phase by `step`.
+    pub fn inc_by(&self, step: Step) {
+        self.step.fetch_add(step, Ordering::SeqCst);
+        self.update();
+    }
+
+    /// Increment the step of the current phase by one.
+    pub fn inc(&self) {
+        self.inc_by(1)
+    }
+
+    /// Return the step of the current phase.
+    pub fn step(&self) -> Step {
+        self.step.load(Ordering::SeqCst)
+    }
+
+    /// Move the progress to the end of all phases, once the last one is done.
+    pub fn finish(&self) {
+        if let Some(last) = self.phases.last() {
+            self.progress.set(last.start + last.len);
+        }
+    }
+
+    /// Return the progress we advance.
+    pub fn inner(&self) -> &P {
+        &self.progress
+    }
+
+    /// Return the progress we advance, consuming this instance.
+    pub fn into_inner(self) -> P {
+        self.progress
+    }
+
+    fn update(&self) {
+        let Some(phase) = self.current.map(|idx| &self.phases[idx]) else {
+            return;
+        };
+        let offset = match self.max {
+            Some(0) => phase.len,
+            Some(max) => (self.step().min(max) as u128 * phase.len as u128 / max as u128) as Step,
+            None => 0,
+        };
+        self.progress.set(phase.start + offset);
+    }
+}
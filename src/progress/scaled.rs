@@ -0,0 +1,155 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::{
+    messages::MessageLevel,
+    progress::{Id, Step, StepShared},
+    Count, NestedProgress, Progress, Unit,
+};
+
+/// A [`Progress`] which multiplies all steps and maxima by `multiplier / divisor` before passing them on to the
+/// progress it wraps, and divides them when reading them back.
+///
+/// This allows a producer counting chunks to present bytes, or an application to rescale the units of a library
+/// without changing its code. Steps are tracked unscaled, so increments too small to show in the wrapped progress
+/// on their own still add up. Children are added to the wrapped progress and aren't scaled.
+///
+/// ```
+/// use prodash::{progress::Scaled, Count, Progress};
+///
+/// let root = prodash::tree::Root::new();
+/// // Each chunk is 64KB.
+/// let mut chunks = Scaled::new(root.add_child("download"), 64 * 1024, 1);
+/// chunks.init(Some(10), Some("bytes".into()));
+/// chunks.inc_by(2);
+/// assert_eq!(chunks.step(), 2);
+/// assert_eq!(chunks.inner().step(), Some(128 * 1024));
+/// assert_eq!(chunks.inner().max(), Some(640 * 1024));
+/// ```
+pub struct Scaled<T> {
+    inner: T,
+    multiplier: Step,
+    divisor: Step,
+    step: AtomicUsize,
+}
+
+impl<T: Progress> Scaled<T> {
+    /// Create a new instance to scale all steps of `inner` by `multiplier / divisor`.
+    ///
+    /// A `divisor` of `0` is treated as `1`.
+    pub fn new(inner: T, multiplier: Step, divisor: Step) -> Self {
+        Scaled {
+            step: AtomicUsize::new(0),
+            inner,
+            multiplier,
+            divisor: divisor.max(1),
+        }
+    }
+
+    /// Return the progress we pass scaled steps to.
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+
+    /// Return the progress we pass scaled steps to, consuming this instance.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    fn scale(&self, step: Step) -> Step {
+        (step as u128 * self.multiplier as u128 / self.divisor as u128).min(Step::MAX as u128) as Step
+    }
+
+    fn unscale(&self, step: Step) -> Step {
+        match self.multiplier {
+            0 => 0,
+            multiplier => (step as u128 * self.divisor as u128 / multiplier as u128).min(Step::MAX as u128) as Step,
+        }
+    }
+}
+
+impl<T: Progress> Count for Scaled<T> {
+    fn set(&self, step: Step) {
+        self.step.store(step, Ordering::SeqCst);
+        self.inner.set(self.scale(step))
+    }
+
+    fn step(&self) -> Step {
+        self.step.load(Ordering::SeqCst)
+    }
+
+    fn inc_by(&self, step: Step) {
+        let step = self.step.fetch_add(step, Ordering::SeqCst).saturating_add(step);
+        self.inner.set(self.scale(step))
+    }
+
+    /// Return the counter of the wrapped progress, which holds scaled steps.
+    fn counter(&self) -> StepShared {
+        self.inner.counter()
+    }
+}
+
+impl<T: Progress> Progress for Scaled<T> {
+    fn init(&mut self, max: Option<Step>, unit: Option<Unit>) {
+        self.step.store(0, Ordering::SeqCst);
+        let max = max.map(|max| self.scale(max));
+        self.inner.init(max, unit)
+    }
+
+    fn unit(&self) -> Option<Unit> {
+        self.inner.unit()
+    }
+
+    fn max(&self) -> Option<Step> {
+        self.inner.max().map(|max| self.unscale(max))
+    }
+
+    fn set_max(&mut self, max: Option<Step>) -> Option<Step> {
+        let max = max.map(|max| self.scale(max));
+        self.inner.set_max(max).map(|max| self.unscale(max))
+    }
+
+    /// Return the maximum of the wrapped progress, which holds scaled steps.
+    fn max_counter(&self) -> Option<StepShared> {
+        self.inner.max_counter()
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.inner.is_cancelled()
+    }
+
+    fn set_name(&mut self, name: String) {
+        self.inner.set_name(name)
+    }
+
+    fn name(&self) -> Option<String> {
+        self.inner.name()
+    }
+
+    fn id(&self) -> Id {
+        self.inner.id()
+    }
+
+    fn message(&self, level: MessageLevel, message: String) {
+        self.inner.message(level, message)
+    }
+
+    fn done(&self, message: String) {
+        self.inner.done(message)
+    }
+
+    fn fail(&self, message: String) {
+        self.inner.fail(message)
+    }
+}
+
+impl<T: NestedProgress> NestedProgress for Scaled<T> {
+    type SubProgress = T::SubProgress;
+
+    fn add_child(&mut self, name: impl Into<String>) -> Self::SubProgress {
+        self.inner.add_child(name)
+    }
+
+    fn add_child_with_id(&mut self, name: impl Into<String>, id: Id) -> Self::SubProgress {
+        self.inner.add_child_with_id(name, id)
+    }
+}
@@ -1,6 +1,6 @@
 use crate::{messages::MessageLevel, progress::Id, Count, NestedProgress, Progress, Unit};
 use std::sync::atomic::AtomicUsize;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
 
 /// An implementation of [`NestedProgress`] which discards all calls.
 pub struct Discard;
@@ -125,6 +125,20 @@ where
         }
     }
 
+    fn max_counter(&self) -> Option<StepShared> {
+        match self {
+            Either::Left(l) => l.max_counter(),
+            Either::Right(r) => r.max_counter(),
+        }
+    }
+
+    fn is_cancelled(&self) -> bool {
+        match self {
+            Either::Left(l) => l.is_cancelled(),
+            Either::Right(r) => r.is_cancelled(),
+        }
+    }
+
     fn set_name(&mut self, name: String) {
         match self {
             Either::Left(l) => l.set_name(name),
@@ -152,6 +166,20 @@ where
             Either::Right(r) => r.message(level, message),
         }
     }
+
+    fn done(&self, message: String) {
+        match self {
+            Either::Left(l) => l.done(message),
+            Either::Right(r) => r.done(message),
+        }
+    }
+
+    fn fail(&self, message: String) {
+        match self {
+            Either::Left(l) => l.fail(message),
+            Either::Right(r) => r.fail(message),
+        }
+    }
 }
 
 impl<L, R> NestedProgress for Either<L, R>
@@ -250,6 +278,14 @@ where
         self.0.set_max(max)
     }
 
+    fn max_counter(&self) -> Option<StepShared> {
+        self.0.max_counter()
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.0.is_cancelled()
+    }
+
     fn set_name(&mut self, name: String) {
         self.0.set_name(name);
     }
@@ -265,6 +301,14 @@ where
     fn message(&self, level: MessageLevel, message: String) {
         self.0.message(level, message)
     }
+
+    fn done(&self, message: String) {
+        self.0.done(message)
+    }
+
+    fn fail(&self, message: String) {
+        self.0.fail(message)
+    }
 }
 
 impl<T> NestedProgress for DoOrDiscard<T>
@@ -331,6 +375,14 @@ impl<T: NestedProgress> Progress for ThroughputOnDrop<T> {
         self.0.set_max(max)
     }
 
+    fn max_counter(&self) -> Option<StepShared> {
+        self.0.max_counter()
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.0.is_cancelled()
+    }
+
     fn set_name(&mut self, name: String) {
         self.0.set_name(name)
     }
@@ -346,6 +398,14 @@ impl<T: NestedProgress> Progress for ThroughputOnDrop<T> {
     fn message(&self, level: MessageLevel, message: String) {
         self.0.message(level, message)
     }
+
+    fn done(&self, message: String) {
+        self.0.done(message)
+    }
+
+    fn fail(&self, message: String) {
+        self.0.fail(message)
+    }
 }
 
 impl<T: NestedProgress> NestedProgress for ThroughputOnDrop<T> {
@@ -365,3 +425,123 @@ impl<T: NestedProgress> Drop for ThroughputOnDrop<T> {
         self.0.show_throughput(self.1)
     }
 }
+
+/// A [`NestedProgress`] implementation shared by multiple owners, like threads, by keeping it behind an `Arc<Mutex<T>>`.
+///
+/// Clones refer to the same progress. Each call locks the mutex, so prefer the [counter][Count::counter()] for
+/// frequent updates. A poisoned mutex is used as is, as progress can't be left in an inconsistent state.
+pub struct Shared<T>(Arc<Mutex<T>>);
+
+impl<T> Shared<T> {
+    /// Create a new instance to share `inner`.
+    pub fn new(inner: T) -> Self {
+        Shared(Arc::new(Mutex::new(inner)))
+    }
+
+    /// Lock the shared progress for direct access.
+    pub fn lock(&self) -> MutexGuard<'_, T> {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<T> Clone for Shared<T> {
+    fn clone(&self) -> Self {
+        Shared(Arc::clone(&self.0))
+    }
+}
+
+impl<T> From<Arc<Mutex<T>>> for Shared<T> {
+    fn from(inner: Arc<Mutex<T>>) -> Self {
+        Shared(inner)
+    }
+}
+
+impl<T> From<Shared<T>> for Arc<Mutex<T>> {
+    fn from(shared: Shared<T>) -> Self {
+        shared.0
+    }
+}
+
+impl<T: Count + Send> Count for Shared<T> {
+    fn set(&self, step: usize) {
+        self.lock().set(step)
+    }
+
+    fn step(&self) -> usize {
+        self.lock().step()
+    }
+
+    fn inc_by(&self, step: usize) {
+        self.lock().inc_by(step)
+    }
+
+    fn inc(&self) {
+        self.lock().inc()
+    }
+
+    fn counter(&self) -> StepShared {
+        self.lock().counter()
+    }
+}
+
+impl<T: Progress> Progress for Shared<T> {
+    fn init(&mut self, max: Option<usize>, unit: Option<Unit>) {
+        self.lock().init(max, unit)
+    }
+
+    fn unit(&self) -> Option<Unit> {
+        self.lock().unit()
+    }
+
+    fn max(&self) -> Option<usize> {
+        self.lock().max()
+    }
+
+    fn set_max(&mut self, max: Option<Step>) -> Option<Step> {
+        self.lock().set_max(max)
+    }
+
+    fn max_counter(&self) -> Option<StepShared> {
+        self.lock().max_counter()
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.lock().is_cancelled()
+    }
+
+    fn set_name(&mut self, name: String) {
+        self.lock().set_name(name)
+    }
+
+    fn name(&self) -> Option<String> {
+        self.lock().name()
+    }
+
+    fn id(&self) -> Id {
+        self.lock().id()
+    }
+
+    fn message(&self, level: MessageLevel, message: String) {
+        self.lock().message(level, message)
+    }
+
+    fn done(&self, message: String) {
+        self.lock().done(message)
+    }
+
+    fn fail(&self, message: String) {
+        self.lock().fail(message)
+    }
+}
+
+impl<T: NestedProgress> NestedProgress for Shared<T> {
+    type SubProgress = T::SubProgress;
+
+    fn add_child(&mut self, name: impl Into<String>) -> Self::SubProgress {
+        self.lock().add_child(name)
+    }
+
+    fn add_child_with_id(&mut self, name: impl Into<String>, id: Id) -> Self::SubProgress {
+        self.lock().add_child_with_id(name, id)
+    }
+}
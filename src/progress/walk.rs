@@ -0,0 +1,153 @@
+use std::{
+    fmt,
+    hash::Hasher,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+use crate::{
+    progress::{Discovery, Step},
+    unit::{self, display, DisplayValue},
+    Progress,
+};
+
+/// Track the progress of walking a tree of entries, like a directory, counting entries as they are discovered and as
+/// they are processed on one task.
+///
+/// While the walk is still discovering entries, the task is unbounded and shows both counts, as in
+/// `3/10 files (still scanning)`. Once discovery is finished, the task turns into a bar bounded by the amount of
+/// discovered entries, keeping the ones processed so far.
+///
+/// ```
+/// use prodash::progress::Walk;
+///
+/// let root = prodash::tree::Root::new();
+/// let mut walk = Walk::new(root.add_child("index"), "files");
+/// walk.discover_by(10);
+/// walk.inc_by(3);
+/// assert!(walk.is_discovering());
+/// walk.finish_discovery();
+/// assert_eq!(walk.inner().max(), Some(10));
+/// assert_eq!(walk.processed(), 3);
+/// ```
+pub struct Walk<P> {
+    discovery: Discovery<P>,
+    discovered: Arc<AtomicUsize>,
+    scanning: Arc<AtomicBool>,
+}
+
+impl<P: Progress> Walk<P> {
+    /// Create a new instance which counts entries called `unit` on `progress`, which is initialized to be unbounded.
+    pub fn new(progress: P, unit: &'static str) -> Self {
+        let discovered = Arc::new(AtomicUsize::new(0));
+        let scanning = Arc::new(AtomicBool::new(true));
+        let unit = unit::dynamic(WalkUnit {
+            label: unit,
+            discovered: Arc::clone(&discovered),
+            scanning: Arc::clone(&scanning),
+        });
+        Walk {
+            discovery: Discovery::new(progress, unit),
+            discovered,
+            scanning,
+        }
+    }
+
+    /// Count `amount` newly discovered entries.
+    pub fn discover_by(&self, amount: Step) {
+        self.discovered.fetch_add(amount, Ordering::Relaxed);
+    }
+
+    /// Count a newly discovered entry.
+    pub fn discover(&self) {
+        self.discover_by(1)
+    }
+
+    /// Return the amount of entries discovered so far.
+    pub fn discovered(&self) -> Step {
+        self.discovered.load(Ordering::Relaxed)
+    }
+
+    /// Count `amount` processed entries.
+    pub fn inc_by(&self, amount: Step) {
+        self.discovery.inc_by(amount)
+    }
+
+    /// Count a processed entry.
+    pub fn inc(&self) {
+        self.inc_by(1)
+    }
+
+    /// Return the amount of entries processed so far.
+    pub fn processed(&self) -> Step {
+        self.discovery.step()
+    }
+
+    /// Indicate that all entries were discovered, which bounds the task by their amount.
+    ///
+    /// It can be called again if more entries were discovered after all.
+    pub fn finish_discovery(&mut self) {
+        self.scanning.store(false, Ordering::Relaxed);
+        self.discovery.set_total(self.discovered());
+    }
+
+    /// Return `true` if [`finish_discovery()`][Walk::finish_discovery()] wasn't called yet.
+    pub fn is_discovering(&self) -> bool {
+        self.discovery.is_discovering()
+    }
+
+    /// Return the progress we advance.
+    pub fn inner(&self) -> &P {
+        self.discovery.inner()
+    }
+
+    /// Return the progress we advance, consuming this instance.
+    pub fn into_inner(self) -> P {
+        self.discovery.into_inner()
+    }
+}
+
+/// The unit of a [`Walk`], which shows the amount of discovered entries while still scanning.
+struct WalkUnit {
+    label: &'static str,
+    discovered: Arc<AtomicUsize>,
+    scanning: Arc<AtomicBool>,
+}
+
+impl WalkUnit {
+    fn is_scanning(&self) -> bool {
+        self.scanning.load(Ordering::Relaxed)
+    }
+}
+
+impl DisplayValue for WalkUnit {
+    fn display_current_value(&self, w: &mut dyn fmt::Write, value: Step, upper: Option<Step>) -> fmt::Result {
+        match upper {
+            None if self.is_scanning() => write!(w, "{value}/{}", self.discovered.load(Ordering::Relaxed)),
+            _ => write!(w, "{value}"),
+        }
+    }
+
+    fn display_throughput(&self, w: &mut dyn fmt::Write, throughput: &display::Throughput) -> fmt::Result {
+        // Rates are plain numbers, without the discovered entries.
+        self.label.display_throughput(w, throughput)
+    }
+
+    fn dyn_hash(&self, state: &mut dyn Hasher) {
+        self.label.dyn_hash(state);
+        state.write(b"walk");
+        // The displayed value changes with these, so renderers have to notice them even if the step stays the same.
+        state.write_usize(self.discovered.load(Ordering::Relaxed));
+        state.write_u8(self.is_scanning().into());
+    }
+
+    fn display_unit(&self, w: &mut dyn fmt::Write, _value: Step) -> fmt::Result {
+        w.write_str(self.label)?;
+        if self.is_scanning() {
+            w.write_str(" (still scanning)")?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,121 @@
+use std::{
+    sync::mpsc::{self, RecvTimeoutError},
+    time::Duration,
+};
+
+use crate::{
+    messages::{Message, MessageCopyState},
+    progress::{Key, Task},
+    Root, WeakRoot,
+};
+
+/// Configure the callback renderer.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "config", derive(serde::Deserialize), serde(default))]
+pub struct Options {
+    /// The amount of frames to pass to the callback per second. If below 1.0, it determines the amount of seconds between
+    /// the frames.
+    ///
+    /// *e.g.* 1.0/4.0 is one frame every 4 seconds.
+    pub frames_per_second: f32,
+    /// If true (default: true), we will keep running even if there is no progress to display.
+    ///
+    /// Otherwise the renderer stops after passing the first frame without any task to the callback.
+    pub keep_running_if_progress_is_empty: bool,
+    /// If true (default: true), we will stop running once the progress isn't available anymore (went out of scope).
+    pub stop_if_progress_missing: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            frames_per_second: 10.0,
+            keep_running_if_progress_is_empty: true,
+            stop_if_progress_missing: true,
+        }
+    }
+}
+
+/// A handle to the render thread, which when dropped will instruct it to stop after passing one last frame to the callback.
+pub struct JoinHandle {
+    inner: Option<std::thread::JoinHandle<()>>,
+    quit: Option<mpsc::Sender<()>>,
+}
+
+impl JoinHandle {
+    /// Wait for the thread to shutdown naturally, for example because there is no more progress to display
+    pub fn wait(mut self) {
+        self.inner.take().and_then(|h| h.join().ok());
+    }
+    /// Send the shutdown signal, which passes one last frame to the callback before stopping.
+    pub fn shutdown(&mut self) {
+        self.quit.take();
+    }
+    /// Send the signal to shutdown and wait for the thread to be shutdown.
+    pub fn shutdown_and_wait(mut self) {
+        self.shutdown();
+        self.wait();
+    }
+}
+
+impl Drop for JoinHandle {
+    fn drop(&mut self) {
+        self.shutdown();
+        self.inner.take().and_then(|h| h.join().ok());
+    }
+}
+
+/// Pass a snapshot of all tasks of `progress` along with the messages that arrived since the last frame to `on_frame`,
+/// for each frame drawn according to `options`, on a new thread.
+///
+/// It takes care of throttling snapshots and of keeping track of which messages were seen already, leaving the
+/// presentation entirely to `on_frame`, which makes it the simplest way to drive custom user interfaces.
+/// As `progress` is a weak handle, rendering stops once the application drops its root,
+/// unless [`Options::stop_if_progress_missing`] is unset.
+pub fn render(
+    progress: impl WeakRoot + Send + 'static,
+    Options {
+        frames_per_second,
+        keep_running_if_progress_is_empty,
+        stop_if_progress_missing,
+    }: Options,
+    mut on_frame: impl FnMut(&[(Key, Task)], &[Message]) + Send + 'static,
+) -> JoinHandle {
+    let duration_per_frame = Duration::from_secs_f32(1.0 / frames_per_second);
+    let (quit, quit_recv) = mpsc::channel::<()>();
+    let inner = std::thread::Builder::new()
+        .name("render-callback".into())
+        .spawn(move || {
+            let mut tasks = Vec::new();
+            let mut messages = Vec::new();
+            let mut copy_state: Option<MessageCopyState> = None;
+            let mut quit_requested = false;
+            loop {
+                match progress.upgrade() {
+                    Some(progress) => {
+                        progress.sorted_snapshot(&mut tasks);
+                        copy_state = Some(progress.copy_new_messages(&mut messages, copy_state.take()));
+                        on_frame(&tasks, &messages);
+                        if !keep_running_if_progress_is_empty && crate::render::has_no_tasks(&tasks) {
+                            break;
+                        }
+                    }
+                    None if stop_if_progress_missing => break,
+                    None => {}
+                }
+                if quit_requested {
+                    break;
+                }
+                // Stop after one last frame once the handle was dropped or shut down.
+                quit_requested = !matches!(
+                    quit_recv.recv_timeout(duration_per_frame),
+                    Err(RecvTimeoutError::Timeout)
+                );
+            }
+        })
+        .expect("starting a thread works");
+    JoinHandle {
+        inner: Some(inner),
+        quit: Some(quit),
+    }
+}
@@ -0,0 +1,92 @@
+use std::{
+    ops::RangeInclusive,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use crate::progress::key::Level;
+
+#[derive(Debug, Default)]
+struct State {
+    paused: AtomicBool,
+    level_filter: Mutex<Option<RangeInclusive<Level>>>,
+    frames_per_second: Mutex<Option<f32>>,
+    interrupt: Arc<AtomicBool>,
+}
+
+/// A handle to control renderers while they are running, for instance from a remote operator managing a long-running job.
+///
+/// Clones share the same state, so one clone can be handed to the renderer and another one to whatever receives the
+/// operator's commands, like a small HTTP server.
+#[derive(Debug, Default, Clone)]
+pub struct Control {
+    state: Arc<State>,
+}
+
+impl Control {
+    /// Create a new instance which doesn't pause rendering, doesn't filter levels, doesn't change the frame rate and doesn't
+    /// request interruption.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stop drawing frames until [`resume()`][Control::resume()] is called.
+    pub fn pause(&self) {
+        self.state.paused.store(true, Ordering::SeqCst);
+    }
+
+    /// Draw frames again after [`pause()`][Control::pause()] was called.
+    pub fn resume(&self) {
+        self.state.paused.store(false, Ordering::SeqCst);
+    }
+
+    /// Returns true if rendering is paused.
+    pub fn is_paused(&self) -> bool {
+        self.state.paused.load(Ordering::SeqCst)
+    }
+
+    /// Show only tasks whose level is within `levels`, or use the level filter the renderer was configured with if `None`.
+    pub fn set_level_filter(&self, levels: Option<RangeInclusive<Level>>) {
+        if let Ok(mut filter) = self.state.level_filter.lock() {
+            *filter = levels;
+        }
+    }
+
+    /// Returns the level filter set with [`set_level_filter()`][Control::set_level_filter()], if any.
+    pub fn level_filter(&self) -> Option<RangeInclusive<Level>> {
+        self.state.level_filter.lock().ok().and_then(|filter| filter.clone())
+    }
+
+    /// Draw `fps` frames per second, or use the frame rate the renderer was configured with if `None`.
+    ///
+    /// Values that aren't positive are treated like `None`. This is useful to lower the refresh rate over slow connections.
+    pub fn set_frames_per_second(&self, fps: Option<f32>) {
+        if let Ok(mut frames_per_second) = self.state.frames_per_second.lock() {
+            *frames_per_second = fps.filter(|fps| *fps > 0.0);
+        }
+    }
+
+    /// Returns the frame rate set with [`set_frames_per_second()`][Control::set_frames_per_second()], if any.
+    pub fn frames_per_second(&self) -> Option<f32> {
+        self.state.frames_per_second.lock().ok().and_then(|fps| *fps)
+    }
+
+    /// Ask the application to interrupt its work by setting the [interrupt signal][Control::interrupt_signal()].
+    pub fn request_interrupt(&self) {
+        self.state.interrupt.store(true, Ordering::SeqCst);
+    }
+
+    /// Returns true if an interrupt was requested.
+    pub fn is_interrupt_requested(&self) -> bool {
+        self.state.interrupt.load(Ordering::SeqCst)
+    }
+
+    /// Returns the flag that is set once an interrupt is requested, to be polled by the application as cancellation token.
+    ///
+    /// Pass it as `interrupt_signal` to the TUI renderer to also have interrupts requested by the user there set it.
+    pub fn interrupt_signal(&self) -> Arc<AtomicBool> {
+        Arc::clone(&self.state.interrupt)
+    }
+}
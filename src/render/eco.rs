@@ -0,0 +1,97 @@
+use std::time::{Duration, Instant};
+
+/// When renderers should save energy by drawing fewer frames, which keeps idle dashboards from waking up the CPU.
+///
+/// In eco mode, at most one frame per second is drawn, and frames are only drawn if tasks or messages changed.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "config", derive(serde::Deserialize), serde(rename_all = "kebab-case"))]
+pub enum EcoMode {
+    /// Always draw at the configured frame rate.
+    Never,
+    /// Always save energy.
+    Always,
+    /// Save energy while the system runs on battery, as determined by [`on_battery()`].
+    #[default]
+    OnBattery,
+}
+
+/// The least amount of time between frames in eco mode.
+const ECO_DURATION_PER_FRAME: Duration = Duration::from_secs(1);
+
+/// The time after which the power supply is checked again in [`EcoMode::OnBattery`].
+const POWER_SUPPLY_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Return true if the system runs on battery.
+///
+/// This is only known on Linux, where it's read from `/sys/class/power_supply`. Elsewhere, it's always false.
+pub fn on_battery() -> bool {
+    #[cfg(target_os = "linux")]
+    {
+        let Ok(supplies) = std::fs::read_dir("/sys/class/power_supply") else {
+            return false;
+        };
+        let mut discharging = false;
+        for supply in supplies.flatten() {
+            let path = supply.path();
+            let read = |name: &str| {
+                std::fs::read_to_string(path.join(name))
+                    .map(|value| value.trim().to_owned())
+                    .unwrap_or_default()
+            };
+            match read("type").as_str() {
+                "Mains" if read("online") == "1" => return false,
+                "Battery" => discharging |= read("status") == "Discharging",
+                _ => {}
+            }
+        }
+        discharging
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        false
+    }
+}
+
+/// Decide whether eco mode is active according to its [mode][EcoMode], checking the power supply only occasionally.
+pub(crate) struct Eco {
+    mode: EcoMode,
+    on_battery: bool,
+    checked_at: Option<Instant>,
+}
+
+impl Eco {
+    pub(crate) fn new(mode: EcoMode) -> Self {
+        Eco {
+            mode,
+            on_battery: false,
+            checked_at: None,
+        }
+    }
+
+    /// Return true if energy should be saved.
+    pub(crate) fn is_active(&mut self) -> bool {
+        match self.mode {
+            EcoMode::Never => false,
+            EcoMode::Always => true,
+            EcoMode::OnBattery => {
+                if self
+                    .checked_at
+                    .is_none_or(|checked_at| checked_at.elapsed() >= POWER_SUPPLY_CHECK_INTERVAL)
+                {
+                    self.on_battery = on_battery();
+                    self.checked_at = Some(Instant::now());
+                }
+                self.on_battery
+            }
+        }
+    }
+
+    /// Return the time to wait until the next frame if it's `duration_per_frame` without saving energy.
+    pub(crate) fn duration_per_frame(&mut self, duration_per_frame: Duration) -> Duration {
+        if self.is_active() {
+            duration_per_frame.max(ECO_DURATION_PER_FRAME)
+        } else {
+            duration_per_frame
+        }
+    }
+}
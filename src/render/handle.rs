@@ -0,0 +1,200 @@
+use std::{
+    future::{Future, IntoFuture},
+    io,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll, Waker},
+};
+
+#[cfg(feature = "render-line")]
+use crate::render::line;
+#[cfg(feature = "render-tui")]
+use crate::render::tui;
+use crate::{render::StreamKind, Root, WeakRoot};
+
+/// A flag which can be awaited, to learn that a renderer stopped or should stop.
+#[derive(Default)]
+pub(crate) struct Signal {
+    state: Mutex<SignalState>,
+}
+
+#[derive(Default)]
+struct SignalState {
+    is_set: bool,
+    wakers: Vec<Waker>,
+}
+
+impl Signal {
+    /// Set the flag and wake everyone waiting for it.
+    pub(crate) fn set(&self) {
+        let wakers = match self.state.lock() {
+            Ok(mut state) => {
+                state.is_set = true;
+                std::mem::take(&mut state.wakers)
+            }
+            Err(_) => return,
+        };
+        for waker in wakers {
+            waker.wake();
+        }
+    }
+
+    /// Return `Poll::Ready` if the flag is set, or arrange for the waker in `cx` to be woken once it is.
+    pub(crate) fn poll_set(&self, cx: &mut Context<'_>) -> Poll<()> {
+        let Ok(mut state) = self.state.lock() else {
+            return Poll::Ready(());
+        };
+        if state.is_set {
+            return Poll::Ready(());
+        }
+        if !state.wakers.iter().any(|waker| waker.will_wake(cx.waker())) {
+            state.wakers.push(cx.waker().clone());
+        }
+        Poll::Pending
+    }
+}
+
+/// A handle to any renderer running on its own thread, which allows to switch renderers at runtime behind one type.
+///
+/// Besides [waiting][RenderHandle::wait()] for it, it can be awaited to learn that the renderer stopped without blocking
+/// the executor. Dropping it shuts down the renderer and waits for it, just like dropping the handle it wraps.
+pub enum RenderHandle {
+    /// The handle to the [line renderer][line::render()].
+    #[cfg(feature = "render-line")]
+    Line(line::JoinHandle),
+    /// The handle to the [terminal user interface][tui::render_in_thread()].
+    #[cfg(feature = "render-tui")]
+    Tui(tui::JoinHandle),
+}
+
+impl RenderHandle {
+    /// Instruct the renderer to stop, without waiting for it.
+    pub fn shutdown(&mut self) {
+        match self {
+            #[cfg(feature = "render-line")]
+            RenderHandle::Line(handle) => handle.shutdown(),
+            #[cfg(feature = "render-tui")]
+            RenderHandle::Tui(handle) => handle.shutdown(),
+        }
+    }
+
+    /// Wait for the renderer to stop naturally, for example because there is no more progress to display.
+    pub fn wait(self) {
+        match self {
+            #[cfg(feature = "render-line")]
+            RenderHandle::Line(handle) => handle.wait(),
+            #[cfg(feature = "render-tui")]
+            RenderHandle::Tui(handle) => handle.wait(),
+        }
+    }
+
+    /// Instruct the renderer to stop and wait until it did.
+    pub fn shutdown_and_wait(mut self) {
+        self.shutdown();
+        self.wait();
+    }
+}
+
+#[cfg(feature = "render-line")]
+impl From<line::JoinHandle> for RenderHandle {
+    fn from(handle: line::JoinHandle) -> Self {
+        RenderHandle::Line(handle)
+    }
+}
+
+#[cfg(feature = "render-tui")]
+impl From<tui::JoinHandle> for RenderHandle {
+    fn from(handle: tui::JoinHandle) -> Self {
+        RenderHandle::Tui(handle)
+    }
+}
+
+/// A renderer along with its options, to continue rendering with on [`switch()`].
+pub enum RendererKind {
+    /// The [line renderer][line::render()], drawing to the stream set in its options, or stderr.
+    #[cfg(feature = "render-line")]
+    Line(line::Options),
+    /// The [terminal user interface][tui::render_in_thread()], drawing to the stream set in its options, or stdout.
+    #[cfg(feature = "render-tui")]
+    Tui(tui::Options),
+}
+
+/// Stop the renderer of `handle` and wait until it restored the terminal, to continue rendering `progress` with the
+/// renderer `to`.
+///
+/// This is useful to temporarily leave the full-screen terminal user interface, for instance to prompt the user.
+/// Messages shown by the previous renderer aren't shown again by the line renderer.
+/// Return the handle of the new renderer, or the error if it couldn't be started.
+pub fn switch(
+    handle: RenderHandle,
+    progress: impl WeakRoot + Send + 'static,
+    to: RendererKind,
+) -> io::Result<RenderHandle> {
+    #[cfg_attr(not(feature = "render-line"), allow(unused_variables))]
+    let messages_seen = progress
+        .upgrade()
+        .map(|root| root.copy_new_messages(&mut Vec::new(), None));
+    handle.shutdown_and_wait();
+    Ok(match to {
+        #[cfg(feature = "render-line")]
+        RendererKind::Line(options) => {
+            let options = line::Options {
+                message_copy_state: options.message_copy_state.or(messages_seen),
+                ..options
+            };
+            let mut handle = line::render(output(options.stream, StreamKind::Stderr), progress, options);
+            if let Some(err) = handle.take_error() {
+                return Err(err);
+            }
+            handle.into()
+        }
+        #[cfg(feature = "render-tui")]
+        RendererKind::Tui(options) => {
+            tui::render_in_thread(output(options.stream, StreamKind::Stdout), progress, options)?.into()
+        }
+    })
+}
+
+/// Return the output for `stream`, or for `default` if it's unset.
+fn output(stream: Option<StreamKind>, default: StreamKind) -> Box<dyn io::Write + Send> {
+    match stream.unwrap_or(default) {
+        StreamKind::Stdout => Box::new(io::stdout()),
+        StreamKind::Stderr => Box::new(io::stderr()),
+    }
+}
+
+impl IntoFuture for RenderHandle {
+    type Output = ();
+    type IntoFuture = Finished;
+
+    /// Wait for the renderer to stop on a new thread, and return a future which is ready once it did.
+    ///
+    /// Call [`shutdown()`][RenderHandle::shutdown()] before to make it stop.
+    fn into_future(self) -> Self::IntoFuture {
+        let finished = Arc::new(Signal::default());
+        std::thread::Builder::new()
+            .name("render-handle-wait".into())
+            .spawn({
+                let finished = Arc::clone(&finished);
+                move || {
+                    self.wait();
+                    finished.set();
+                }
+            })
+            .expect("starting a thread works");
+        Finished { finished }
+    }
+}
+
+/// A future which is ready once the renderer of an awaited [`RenderHandle`] stopped.
+pub struct Finished {
+    finished: Arc<Signal>,
+}
+
+impl Future for Finished {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.finished.poll_set(cx)
+    }
+}
@@ -1,9 +1,11 @@
 use std::{
-    collections::{hash_map::DefaultHasher, VecDeque},
+    borrow::Cow,
+    collections::{hash_map::DefaultHasher, HashMap, HashSet, VecDeque},
     hash::{Hash, Hasher},
     io,
     ops::RangeInclusive,
     sync::atomic::Ordering,
+    time::{Duration, Instant, SystemTime},
 };
 
 use crosstermion::{
@@ -15,6 +17,16 @@ use unicode_width::UnicodeWidthStr;
 use crate::{
     messages::{Message, MessageCopyState, MessageLevel},
     progress::{self, Value},
+    render::{
+        line::{
+            template::{Align, Field, Segment, Template},
+            AlertMode,
+        },
+        status_glyph,
+        theme::{ColorDepth, Quantized, Rgb, Theme},
+        SmoothFractions,
+    },
+    time::{TimestampFormat, Timezone},
     unit, Root, Throughput,
 };
 
@@ -23,7 +35,7 @@ pub struct State {
     tree: Vec<(progress::Key, progress::Task)>,
     tree_hash: u64,
     messages: Vec<Message>,
-    for_next_copy: Option<MessageCopyState>,
+    pub for_next_copy: Option<MessageCopyState>,
     /// The size of the message origin, tracking the terminal height so things potentially off screen don't influence width anymore.
     message_origin_size: VecDeque<usize>,
     /// The maximum progress midpoint (point till progress bar starts) seen at the last tick
@@ -31,11 +43,58 @@ pub struct State {
     /// The amount of blocks per line we have written last time.
     blocks_per_line: VecDeque<u16>,
     pub throughput: Option<Throughput>,
+    /// If true, time isn't accounted for when computing throughput.
+    clock_paused: bool,
+    /// The time of the first message we have seen, as reference for timestamps relative to it.
+    first_message_at: Option<SystemTime>,
+    /// The name of each task along with its width when drawn, to avoid computing it on each tick.
+    name_widths: HashMap<progress::Key, (String, u16)>,
+    /// The values and unit of each line drawn last, to reuse their allocations on each tick.
+    unit_texts: Vec<UnitText>,
+    /// The fractions of bars as shown, if bars are smoothed.
+    smooth_fractions: SmoothFractions,
+    /// The time the run started according to the progress, if it knows.
+    run_started_at: Option<SystemTime>,
+    /// The elapsed seconds shown last, to know when to draw them again.
+    elapsed_secs_drawn: u64,
+    /// The height of the terminal and the amount of rows at its bottom which are excluded from scrolling, if set up.
+    pinned_region: Option<(u16, u16)>,
+    /// If true, the terminal was flashed with inverted colors on the last tick, to be restored on this one.
+    flashing: bool,
+}
+
+/// The values and unit of a line along with the throughput they show, as written for the current tick.
+#[derive(Default)]
+struct UnitText {
+    values: String,
+    unit: String,
+    throughput: Option<unit::display::Throughput>,
+    /// The fraction the bar is drawn with, which lags behind the actual one if bars are smoothed.
+    fraction: Option<f32>,
+}
+
+impl UnitText {
+    /// Write the values and unit of `progress`, if it has a unit, reusing our buffers.
+    fn update(&mut self, progress: Option<&Value>) {
+        self.values.clear();
+        self.unit.clear();
+        if let Some((progress, unit)) = progress.and_then(|p| p.unit.as_ref().map(|unit| (p, unit))) {
+            let mut display = unit.display(
+                progress.step.load(Ordering::SeqCst),
+                progress.done_at,
+                self.throughput.clone(),
+            );
+            display.values().write_to(&mut self.values).ok();
+            display.unit().write_to(&mut self.unit).ok();
+        }
+    }
 }
 
 impl State {
     pub(crate) fn update_from_progress(&mut self, progress: &impl Root) -> bool {
         progress.sorted_snapshot(&mut self.tree);
+        self.clock_paused = progress.is_clock_paused();
+        self.run_started_at = progress.started_at();
         let mut hasher = DefaultHasher::new();
         self.tree.hash(&mut hasher);
         let cur_hash = hasher.finish();
@@ -45,10 +104,36 @@ impl State {
             .into();
         let changed = self.tree_hash != cur_hash;
         self.tree_hash = cur_hash;
+        if changed && self.name_widths.len() > self.tree.len() {
+            let keys: HashSet<_> = self.tree.iter().map(|(key, _)| key).collect();
+            self.name_widths.retain(|key, _| keys.contains(key));
+        }
         changed
     }
+    /// The tasks obtained by the last call to [`update_from_progress()`][State::update_from_progress()].
+    pub(crate) fn tasks(&self) -> &[(progress::Key, progress::Task)] {
+        &self.tree
+    }
+
+    /// Return true if smoothed bars didn't reach their actual fill level yet, so progress needs to be drawn again.
+    pub(crate) fn bars_are_moving(&self) -> bool {
+        self.smooth_fractions.is_moving()
+    }
+
+    /// Return true if the elapsed time is shown and changed since it was drawn last, so progress needs to be drawn again.
+    pub(crate) fn elapsed_changed(&self, config: &Options) -> bool {
+        config.show_elapsed && self.elapsed(config).as_secs() != self.elapsed_secs_drawn
+    }
+
+    fn elapsed(&self, config: &Options) -> Duration {
+        SystemTime::now()
+            .duration_since(self.run_started_at.unwrap_or(config.start))
+            .unwrap_or_default()
+    }
+
     pub(crate) fn clear(&mut self) {
         self.tree.clear();
+        self.name_widths.clear();
         self.messages.clear();
         self.for_next_copy.take();
     }
@@ -60,26 +145,135 @@ pub struct Options {
     pub keep_running_if_progress_is_empty: bool,
     pub output_is_terminal: bool,
     pub colored: bool,
+    /// If false, no escape codes are written and progress frames follow each other instead of being redrawn in place.
+    pub ansi: bool,
     pub timestamp: bool,
+    pub timestamp_format: TimestampFormat,
+    pub timezone: Timezone,
+    /// The time the renderer started, as reference for relative timestamps.
+    pub start: SystemTime,
     pub hide_cursor: bool,
+    pub ascii_only: bool,
+    pub template: Option<Template>,
+    pub alert_on_failure: Option<AlertMode>,
+    pub palette: Palette,
+    pub status_glyphs: bool,
+    pub smooth_bars: bool,
+    pub show_elapsed: bool,
+    pub pinned_rows: Option<u16>,
+}
+
+impl Options {
+    /// Return the amount of rows at the bottom of the terminal to draw progress into, if rows should be pinned and the
+    /// terminal leaves at least one row for scrolling.
+    fn pinned_rows(&self) -> Option<u16> {
+        self.pinned_rows
+            .filter(|_| self.ansi)
+            .map(|rows| rows.min(self.terminal_dimensions.1.saturating_sub(1)))
+            .filter(|rows| *rows > 0)
+    }
+}
+
+/// The colors to draw with, as the terminal can display them.
+pub struct Palette {
+    pub name: Color,
+    pub headline: Color,
+    pub overdue: Color,
+    pub progress: Color,
+    pub progress_almost_done: Color,
+    pub progress_unbounded: Color,
+    pub blocked: Color,
+    pub info: Color,
+    pub success: Color,
+    pub failure: Color,
+    pub timestamp_background: Color,
+}
+
+/// The colors of the terminal's own palette, used if there is no theme.
+impl Default for Palette {
+    fn default() -> Self {
+        Palette {
+            name: Color::Cyan,
+            headline: Color::White,
+            overdue: Color::Fixed(208),
+            progress: Color::Yellow,
+            progress_almost_done: Color::Green,
+            progress_unbounded: Color::White,
+            blocked: Color::Red,
+            info: Color::White,
+            success: Color::Green,
+            failure: Color::Red,
+            timestamp_background: Color::Yellow,
+        }
+    }
+}
+
+impl Palette {
+    /// Approximate the colors of `theme` with the ones available at `depth`.
+    pub fn new(theme: &Theme, depth: ColorDepth) -> Self {
+        let color = |rgb: Rgb| match rgb.quantize(depth) {
+            Quantized::Ansi16(idx) => [
+                Color::Black,
+                Color::Red,
+                Color::Green,
+                Color::Yellow,
+                Color::Blue,
+                Color::Purple,
+                Color::Cyan,
+                Color::White,
+            ]
+            .get(idx as usize)
+            .copied()
+            .unwrap_or(Color::Fixed(idx)),
+            Quantized::Ansi256(idx) => Color::Fixed(idx),
+            Quantized::Rgb(r, g, b) => Color::RGB(r, g, b),
+        };
+        Palette {
+            name: color(theme.name),
+            headline: color(theme.headline),
+            overdue: color(theme.overdue),
+            progress: color(theme.progress),
+            progress_almost_done: color(theme.progress_almost_done),
+            progress_unbounded: color(theme.progress_unbounded),
+            blocked: color(theme.blocked),
+            info: color(theme.info),
+            success: color(theme.success),
+            failure: color(theme.failure),
+            timestamp_background: color(theme.timestamp_background),
+        }
+    }
+}
+
+/// Replace all non-ASCII characters in `s` with `?` if `ascii_only` is set.
+fn ascii_if(ascii_only: bool, s: &str) -> Cow<'_, str> {
+    if ascii_only && !s.is_ascii() {
+        s.chars()
+            .map(|c| if c.is_ascii() { c } else { '?' })
+            .collect::<String>()
+            .into()
+    } else {
+        s.into()
+    }
 }
 
 fn messages(
     out: &mut impl io::Write,
     state: &mut State,
     colored: bool,
+    palette: &Palette,
     max_height: usize,
-    timestamp: bool,
+    format_time: Option<&dyn Fn(SystemTime) -> String>,
+    ascii_only: bool,
 ) -> io::Result<()> {
     let mut brush = color::Brush::new(colored);
-    fn to_color(level: MessageLevel) -> Color {
+    let to_color = |level: MessageLevel| {
         use crate::messages::MessageLevel::*;
         match level {
-            Info => Color::White,
-            Success => Color::Green,
-            Failure => Color::Red,
+            Info => palette.info,
+            Success => palette.success,
+            Failure => palette.failure,
         }
-    }
+    };
     let mut tokens: Vec<ANSIString<'_>> = Vec::with_capacity(6);
     let mut current_maximum = state.message_origin_size.iter().max().cloned().unwrap_or(0);
     for Message {
@@ -87,9 +281,12 @@ fn messages(
         level,
         origin,
         message,
+        count,
+        ..
     } in &state.messages
     {
         tokens.clear();
+        let (origin, message) = (ascii_if(ascii_only, &origin.name), ascii_if(ascii_only, message));
         let blocks_drawn_during_previous_tick = state.blocks_per_line.pop_front().unwrap_or(0);
         let message_block_len = origin.width();
         current_maximum = current_maximum.max(message_block_len);
@@ -100,11 +297,11 @@ fn messages(
 
         let color = to_color(*level);
         tokens.push(" ".into());
-        if timestamp {
+        if let Some(format_time) = format_time {
             tokens.push(
                 brush
-                    .style(color.dimmed().on(Color::Yellow))
-                    .paint(crate::time::format_time_for_messages(*time)),
+                    .style(color.dimmed().on(palette.timestamp_background))
+                    .paint(format_time(*time)),
             );
             tokens.push(Style::default().paint(" "));
         } else {
@@ -118,11 +315,18 @@ fn messages(
         )));
         tokens.push(" ".into());
         tokens.push(brush.style(color.bold()).paint(message));
-        let message_block_count = block_count_sans_ansi_codes(&tokens);
+        if *count > 1 {
+            tokens.push(
+                brush
+                    .style(Style::default().dimmed())
+                    .paint(format!(" {}{count}", if ascii_only { "x" } else { "×" })),
+            );
+        }
+        let message_block_count = block_count_sans_ansi_codes(&tokens, None);
         write!(out, "{}", ANSIStrings(tokens.as_slice()))?;
 
         if blocks_drawn_during_previous_tick > message_block_count {
-            newline_with_overdraw(out, &tokens, blocks_drawn_during_previous_tick)?;
+            newline_with_overdraw(out, message_block_count, blocks_drawn_during_previous_tick)?;
         } else {
             writeln!(out)?;
         }
@@ -130,68 +334,202 @@ fn messages(
     Ok(())
 }
 
-pub fn all(out: &mut impl io::Write, show_progress: bool, state: &mut State, config: &Options) -> io::Result<()> {
-    if !config.keep_running_if_progress_is_empty && state.tree.is_empty() {
-        return Err(io::Error::new(io::ErrorKind::Other, "stop as progress is empty"));
+/// Get the user's attention according to `mode` for each failure among the messages about to be drawn.
+///
+/// A flash inverts the colors of the terminal until the next tick, setting `flashing` to restore them then.
+fn alert(
+    out: &mut impl io::Write,
+    messages: &[Message],
+    mode: &AlertMode,
+    output_is_terminal: bool,
+    flashing: &mut bool,
+) -> io::Result<()> {
+    let mut failures = messages.iter().filter(|m| m.level == MessageLevel::Failure).peekable();
+    match mode {
+        AlertMode::Callback(cb) => {
+            for message in failures {
+                cb(message);
+            }
+        }
+        AlertMode::Bell if output_is_terminal && failures.peek().is_some() => {
+            write!(out, "\x07")?;
+        }
+        AlertMode::Flash if output_is_terminal && failures.peek().is_some() => {
+            write!(out, "\x1b[?5h")?;
+            *flashing = true;
+        }
+        AlertMode::Bell | AlertMode::Flash => {}
+    }
+    Ok(())
+}
+
+/// Draw all messages and progress, or return `false` without drawing if rendering should stop as progress is empty.
+pub fn all(out: &mut impl io::Write, show_progress: bool, state: &mut State, config: &Options) -> io::Result<bool> {
+    end_flash(out, state)?;
+    if !config.keep_running_if_progress_is_empty && crate::render::has_no_tasks(&state.tree) {
+        return Ok(false);
     }
+    if let Some(mode) = config.alert_on_failure.as_ref() {
+        alert(
+            out,
+            &state.messages,
+            mode,
+            config.output_is_terminal && config.ansi,
+            &mut state.flashing,
+        )?;
+    }
+    if state.first_message_at.is_none() {
+        state.first_message_at = state.messages.first().map(|message| message.time);
+    }
+    let start = config.timestamp_format.start(config.start, state.first_message_at);
+    let format_time = |time| config.timestamp_format.format(time, config.timezone, start);
     messages(
         out,
         state,
         config.colored,
+        &config.palette,
         config.terminal_dimensions.1 as usize,
-        config.timestamp,
+        config
+            .timestamp
+            .then_some(&format_time as &dyn Fn(SystemTime) -> String),
+        config.ascii_only,
     )?;
 
     if show_progress && config.output_is_terminal {
         if let Some(tp) = state.throughput.as_mut() {
-            tp.update_elapsed();
+            if state.clock_paused {
+                tp.skip_elapsed();
+            } else {
+                tp.update_elapsed();
+            }
         }
         let level_range = config
             .level_filter
             .clone()
             .unwrap_or(RangeInclusive::new(0, progress::key::Level::max_value()));
+        let pinned_rows = config.pinned_rows();
         let lines_to_be_drawn = state
             .tree
             .iter()
             .filter(|(k, _)| level_range.contains(&k.level()))
-            .count();
+            .count()
+            .min(pinned_rows.map_or(usize::MAX, usize::from));
+        let first_pinned_row = match pinned_rows {
+            Some(rows) => {
+                let height = config.terminal_dimensions.1;
+                if state.pinned_region != Some((height, rows)) {
+                    pin_rows(out, height, rows)?;
+                    state.pinned_region = Some((height, rows));
+                }
+                // Save the cursor in the scrolling region, to continue writing messages there.
+                write!(out, "\x1b7")?;
+                Some(height - rows + 1)
+            }
+            None => {
+                unpin_rows(out, state)?;
+                None
+            }
+        };
         if state.blocks_per_line.len() < lines_to_be_drawn {
             state.blocks_per_line.resize(lines_to_be_drawn, 0);
         }
+        // Write all values and units first, as the tokens of each line borrow them while drawing.
+        state.unit_texts.resize_with(lines_to_be_drawn, UnitText::default);
+        let now = Instant::now();
+        for ((key, value), text) in state
+            .tree
+            .iter()
+            .filter(|(k, _)| level_range.contains(&k.level()))
+            .zip(state.unit_texts.iter_mut())
+        {
+            text.throughput = state
+                .throughput
+                .as_mut()
+                .and_then(|tp| tp.update_and_get(key, value.progress.as_ref()));
+            text.update(value.progress.as_ref());
+            text.fraction = value.progress.as_ref().and_then(Value::fraction).map(|fraction| {
+                if config.smooth_bars {
+                    state.smooth_fractions.fraction(key, fraction, now)
+                } else {
+                    fraction
+                }
+            });
+        }
+        let elapsed = config.show_elapsed.then(|| {
+            let elapsed = state.elapsed(config);
+            state.elapsed_secs_drawn = elapsed.as_secs();
+            format!(
+                " {} {}",
+                if config.ascii_only { "elapsed" } else { "⏱" },
+                humantime::format_duration(Duration::from_secs(elapsed.as_secs()))
+            )
+        });
         let mut tokens: Vec<ANSIString<'_>> = Vec::with_capacity(4);
         let mut max_midpoint = 0;
-        for ((key, value), ref mut blocks_in_last_iteration) in state
+        for (line, ((entry, text), ref mut blocks_in_last_iteration)) in state
             .tree
             .iter()
             .filter(|(k, _)| level_range.contains(&k.level()))
+            .zip(state.unit_texts.iter())
             .zip(state.blocks_per_line.iter_mut())
+            .enumerate()
         {
-            max_midpoint = max_midpoint.max(
-                format_progress(
-                    key,
-                    value,
-                    config.terminal_dimensions.0,
-                    config.colored,
-                    state.last_progress_midpoint,
-                    state
-                        .throughput
-                        .as_mut()
-                        .and_then(|tp| tp.update_and_get(key, value.progress.as_ref())),
-                    &mut tokens,
-                )
-                .unwrap_or(0),
+            let (key, value) = entry;
+            let suffix = elapsed.as_deref().filter(|_| line == 0);
+            let suffix_width = suffix.map_or(0, |suffix| text_width(suffix) as u16);
+            let name_width = cached_name_width(&mut state.name_widths, key, &value.name, config.ascii_only);
+            let (midpoint, mut block_count) = format_progress(
+                entry,
+                config,
+                state.last_progress_midpoint,
+                text,
+                name_width,
+                suffix_width,
+                &mut tokens,
             );
+            if let Some(suffix) = suffix {
+                tokens.push(
+                    color::Brush::new(config.colored)
+                        .style(Style::new().dimmed())
+                        .paint(suffix),
+                );
+                block_count += suffix_width;
+            }
+            max_midpoint = max_midpoint.max(midpoint.unwrap_or(0));
+            if let Some(first_row) = first_pinned_row {
+                write!(out, "\x1b[{};1H\x1b[2K", first_row + line as u16)?;
+            }
             write!(out, "{}", ANSIStrings(tokens.as_slice()))?;
 
-            **blocks_in_last_iteration = newline_with_overdraw(out, &tokens, **blocks_in_last_iteration)?;
+            **blocks_in_last_iteration = if first_pinned_row.is_some() {
+                block_count
+            } else {
+                newline_with_overdraw(out, block_count, **blocks_in_last_iteration)?
+            };
         }
         if let Some(tp) = state.throughput.as_mut() {
             tp.reconcile(&state.tree);
         }
+        if config.smooth_bars {
+            state.smooth_fractions.reconcile(&state.tree);
+        }
         state.last_progress_midpoint = Some(max_midpoint);
         // overwrite remaining lines that we didn't touch naturally
         let lines_drawn = lines_to_be_drawn;
-        if state.blocks_per_line.len() > lines_drawn {
+        if let Some((first_row, rows)) = first_pinned_row.zip(pinned_rows) {
+            for line in lines_drawn as u16..rows {
+                write!(out, "\x1b[{};1H\x1b[2K", first_row + line)?;
+            }
+            write!(out, "\x1b8")?;
+            // Messages are written in their own rows, so there is nothing to overwrite.
+            state.blocks_per_line.clear();
+        } else if !config.ansi {
+            // Without moving the cursor there is nothing to overwrite, so just separate this frame from the next one.
+            state.blocks_per_line.clear();
+            if lines_drawn > 0 {
+                writeln!(out)?;
+            }
+        } else if state.blocks_per_line.len() > lines_drawn {
             for blocks_in_last_iteration in state.blocks_per_line.iter().skip(lines_drawn) {
                 writeln!(out, "{:>width$}", "", width = *blocks_in_last_iteration as usize)?;
             }
@@ -202,16 +540,43 @@ pub fn all(out: &mut impl io::Write, show_progress: bool, state: &mut State, con
             crosstermion::execute!(out, crosstermion::cursor::MoveUp(lines_drawn as u16))?;
         }
     }
+    Ok(true)
+}
+
+/// Exclude the bottom `rows` of a terminal that is `height` rows high from scrolling, without moving the cursor unless
+/// it is in one of these rows, in which case the rows above are scrolled up to make room.
+fn pin_rows(out: &mut impl io::Write, height: u16, rows: u16) -> io::Result<()> {
+    for _ in 0..rows {
+        writeln!(out)?;
+    }
+    crosstermion::execute!(out, crosstermion::cursor::MoveUp(rows))?;
+    // Setting the scrolling region moves the cursor to the top left, so we save and restore it.
+    write!(out, "\x1b7\x1b[1;{}r\x1b8", height - rows)
+}
+
+/// Restore the colors of the terminal if they were inverted to flash it on the last tick.
+pub(crate) fn end_flash(out: &mut impl io::Write, state: &mut State) -> io::Result<()> {
+    if std::mem::take(&mut state.flashing) {
+        write!(out, "\x1b[?5l")?;
+    }
     Ok(())
 }
 
-/// Must be called directly after `tokens` were drawn, without newline. Takes care of adding the newline.
+/// Let the whole terminal scroll again if rows were pinned to draw progress into.
+pub(crate) fn unpin_rows(out: &mut impl io::Write, state: &mut State) -> io::Result<()> {
+    if state.pinned_region.take().is_some() {
+        write!(out, "\x1b7\x1b[r\x1b8")?;
+    }
+    Ok(())
+}
+
+/// Must be called directly after tokens worth `current_block_count` blocks were drawn, without newline.
+/// Takes care of adding the newline.
 fn newline_with_overdraw(
     out: &mut impl io::Write,
-    tokens: &[ANSIString<'_>],
+    current_block_count: u16,
     blocks_in_last_iteration: u16,
 ) -> io::Result<u16> {
-    let current_block_count = block_count_sans_ansi_codes(tokens);
     if blocks_in_last_iteration > current_block_count {
         // fill to the end of line to overwrite what was previously there
         writeln!(
@@ -226,17 +591,60 @@ fn newline_with_overdraw(
     Ok(current_block_count)
 }
 
-fn block_count_sans_ansi_codes(strings: &[ANSIString<'_>]) -> u16 {
-    strings.iter().map(|s| s.width() as u16).sum()
+/// Return the amount of blocks `strings` take when drawn, using the width of the token at the index of `known_width`
+/// instead of computing it.
+fn block_count_sans_ansi_codes(strings: &[ANSIString<'_>], known_width: Option<(usize, u16)>) -> u16 {
+    strings
+        .iter()
+        .enumerate()
+        .map(|(idx, s)| match known_width {
+            Some((known_idx, width)) if known_idx == idx => width,
+            _ => text_width(s) as u16,
+        })
+        .sum()
 }
 
-fn draw_progress_bar(p: &Value, style: Style, mut blocks_available: u16, colored: bool, buf: &mut Vec<ANSIString<'_>>) {
+/// Return the width of `s`, quickly if it's ASCII only.
+fn text_width(s: &str) -> usize {
+    if s.is_ascii() {
+        s.len()
+    } else {
+        s.width()
+    }
+}
+
+/// Return the width of `name` of the task at `key` as it will be drawn, computing it only if the task is new or was renamed.
+fn cached_name_width(
+    cache: &mut HashMap<progress::Key, (String, u16)>,
+    key: &progress::Key,
+    name: &str,
+    ascii_only: bool,
+) -> u16 {
+    match cache.get(key) {
+        Some((cached_name, width)) if cached_name == name => *width,
+        _ => {
+            let width = text_width(&ascii_if(ascii_only, name)) as u16;
+            cache.insert(key.clone(), (name.to_owned(), width));
+            width
+        }
+    }
+}
+
+/// Draw the bar of `p` filled according to `fraction`, or as unbounded if it's `None`.
+fn draw_progress_bar(
+    p: &Value,
+    fraction: Option<f32>,
+    style: Style,
+    mut blocks_available: u16,
+    colored: bool,
+    buf: &mut Vec<ANSIString<'_>>,
+) {
     let mut brush = color::Brush::new(colored);
     let styled_brush = brush.style(style);
 
     blocks_available = blocks_available.saturating_sub(3); // account for…I don't really know it's magic
     buf.push(" [".into());
-    match p.fraction() {
+    match fraction {
         Some(mut fraction) => {
             fraction = fraction.min(1.0);
             blocks_available = blocks_available.saturating_sub(1); // account for '>' apparently
@@ -251,11 +659,11 @@ fn draw_progress_bar(p: &Value, style: Style, mut blocks_available: u16, colored
         }
         None => {
             const CHARS: [char; 6] = ['=', '=', '=', ' ', ' ', ' '];
+            let offset = p.animation_step();
             buf.push(
                 styled_brush.paint(
-                    (p.step.load(Ordering::SeqCst)..std::usize::MAX)
-                        .take(blocks_available as usize)
-                        .map(|idx| CHARS[idx % CHARS.len()])
+                    (0..blocks_available as usize)
+                        .map(|idx| CHARS[offset.wrapping_add(idx) % CHARS.len()])
                         .rev()
                         .collect::<String>(),
                 ),
@@ -265,51 +673,100 @@ fn draw_progress_bar(p: &Value, style: Style, mut blocks_available: u16, colored
     buf.push("]".into());
 }
 
-fn progress_style(p: &Value) -> Style {
+fn progress_style(p: &Value, palette: &Palette) -> Style {
     use crate::progress::State::*;
     match p.state {
         Running => if let Some(fraction) = p.fraction() {
             if fraction > 0.8 {
-                Color::Green
+                palette.progress_almost_done
             } else {
-                Color::Yellow
+                palette.progress
             }
         } else {
-            Color::White
+            palette.progress_unbounded
         }
         .normal(),
-        Halted(_, _) => Color::Red.dimmed(),
-        Blocked(_, _) => Color::Red.normal(),
+        Halted(_, _) => palette.blocked.dimmed(),
+        Blocked(_, _) => palette.blocked.normal(),
+        Finished(true) => palette.success.dimmed(),
+        Finished(false) => palette.failure.dimmed(),
+    }
+}
+
+fn name_style(overdue: Option<Duration>, palette: &Palette) -> Style {
+    if overdue.is_some() {
+        palette.overdue.bold()
+    } else {
+        palette.name.bold()
     }
 }
 
 fn format_progress<'a>(
-    key: &progress::Key,
-    value: &'a progress::Task,
-    column_count: u16,
-    colored: bool,
+    (key, value): &'a (progress::Key, progress::Task),
+    config: &Options,
     midpoint: Option<u16>,
-    throughput: Option<unit::display::Throughput>,
+    text: &'a UnitText,
+    name_width: u16,
+    reserved_width: u16,
     buf: &mut Vec<ANSIString<'a>>,
-) -> Option<u16> {
+) -> (Option<u16>, u16) {
+    let (column_count, colored, ascii_only) = (
+        config.terminal_dimensions.0.saturating_sub(reserved_width),
+        config.colored,
+        config.ascii_only,
+    );
     let mut brush = color::Brush::new(colored);
     buf.clear();
 
     buf.push(Style::new().paint(format!("{:>level$}", "", level = key.level() as usize)));
-    match value.progress.as_ref() {
+    if let (Some(progress), Some(template)) = (value.progress.as_ref(), config.template.as_ref()) {
+        format_with_template(template, progress, value, config, text, buf);
+        return (None, block_count_sans_ansi_codes(buf, None));
+    }
+    if config.status_glyphs {
+        let state = value.progress.as_ref().map(|progress| &progress.state);
+        let style = value
+            .progress
+            .as_ref()
+            .map_or_else(Style::new, |progress| progress_style(progress, &config.palette));
+        buf.push(brush.style(style).paint(status_glyph(state, ascii_only)));
+        buf.push(" ".into());
+    }
+    // The name is the next token.
+    let known_width = Some((buf.len(), name_width));
+    let midpoint = match value.progress.as_ref() {
         Some(progress) => {
-            let style = progress_style(progress);
-            buf.push(brush.style(Color::Cyan.bold()).paint(&value.name));
+            let style = progress_style(progress, &config.palette);
+            let overdue = value.overdue_by(SystemTime::now());
+            buf.push(
+                brush
+                    .style(name_style(overdue, &config.palette))
+                    .paint(ascii_if(ascii_only, &value.name)),
+            );
+            if value.retries > 0 {
+                buf.push(" ".into());
+                buf.push(
+                    brush
+                        .style(Style::new().dimmed())
+                        .paint(format!("(attempt {})", value.attempt())),
+                );
+            }
+            if let Some(overdue) = overdue {
+                buf.push(" ".into());
+                buf.push(brush.style(config.palette.overdue.normal()).paint(format!(
+                    "(overdue {})",
+                    humantime::format_duration(Duration::from_secs(overdue.as_secs()))
+                )));
+            }
             buf.push(" ".into());
 
             let pre_unit = buf.len();
             let values_brush = brush.style(Style::new().bold().dimmed());
             match progress.unit.as_ref() {
-                Some(unit) => {
-                    let mut display = unit.display(progress.step.load(Ordering::SeqCst), progress.done_at, throughput);
-                    buf.push(values_brush.paint(display.values().to_string()));
+                Some(_) => {
+                    buf.push(values_brush.paint(text.values.as_str()));
                     buf.push(" ".into());
-                    buf.push(display.unit().to_string().into());
+                    buf.push(text.unit.as_str().into());
                 }
                 None => {
                     buf.push(values_brush.paint(match progress.done_at {
@@ -318,26 +775,195 @@ fn format_progress<'a>(
                     }));
                 }
             }
-            let desired_midpoint = block_count_sans_ansi_codes(buf.as_slice());
+            let desired_midpoint = block_count_sans_ansi_codes(buf.as_slice(), known_width);
             let actual_midpoint = if let Some(midpoint) = midpoint {
                 let padding = midpoint.saturating_sub(desired_midpoint);
                 if padding > 0 {
                     buf.insert(pre_unit, " ".repeat(padding as usize).into());
                 }
-                block_count_sans_ansi_codes(buf.as_slice())
+                block_count_sans_ansi_codes(buf.as_slice(), known_width)
             } else {
                 desired_midpoint
             };
-            let blocks_left = column_count.saturating_sub(actual_midpoint);
+            let note = value.note.as_deref().map(|note| ascii_if(ascii_only, note));
+            let note_width = note.as_ref().map_or(0, |note| note.width() as u16 + 1);
+            let blocks_left = column_count.saturating_sub(actual_midpoint).saturating_sub(note_width);
             if blocks_left > 0 {
-                draw_progress_bar(progress, style, blocks_left, colored, buf);
+                draw_progress_bar(progress, text.fraction, style, blocks_left, colored, buf);
+            }
+            if let Some(note) = note {
+                buf.push(" ".into());
+                buf.push(brush.style(Style::new().dimmed()).paint(note));
             }
             Some(desired_midpoint)
         }
         None => {
             // headline only - FIXME: would have to truncate it if it is too long for the line…
-            buf.push(brush.style(Color::White.bold()).paint(&value.name));
+            buf.push(
+                brush
+                    .style(config.palette.headline.bold())
+                    .paint(ascii_if(ascii_only, &value.name)),
+            );
             None
         }
+    };
+    (midpoint, block_count_sans_ansi_codes(buf, known_width))
+}
+
+fn format_with_template<'a>(
+    template: &Template,
+    progress: &Value,
+    value: &'a progress::Task,
+    config: &Options,
+    text: &UnitText,
+    buf: &mut Vec<ANSIString<'a>>,
+) {
+    let mut brush = color::Brush::new(config.colored);
+    let step = progress.step.load(Ordering::SeqCst);
+    let values_style = Style::new().bold().dimmed();
+    let steps_per_second = text
+        .throughput
+        .clone()
+        .filter(|tp| !tp.timespan.is_zero())
+        .map(|tp| tp.value_change_in_timespan as f64 / tp.timespan.as_secs_f64());
+    let format_value = |value: progress::Step, upper: Option<progress::Step>, is_upper: bool| {
+        use std::fmt::Write;
+        let mut out = String::new();
+        match progress.unit.as_ref().map(|unit| unit.as_display_value()) {
+            Some(unit) if is_upper => unit.display_upper_bound(&mut out, value, step),
+            Some(unit) => unit.display_current_value(&mut out, value, upper),
+            None => write!(out, "{value}"),
+        }
+        .ok();
+        out
+    };
+
+    let mut bar_at = None;
+    for segment in template.segments() {
+        let (field, align, width) = match segment {
+            Segment::Literal(text) => {
+                buf.push(ascii_if(config.ascii_only, text).into_owned().into());
+                continue;
+            }
+            Segment::Field { field, align, width } => (*field, *align, *width),
+        };
+        let (text, style): (Cow<'a, str>, Style) = match field {
+            Field::Bar => {
+                bar_at = Some((buf.len(), width));
+                continue;
+            }
+            Field::Status => (
+                status_glyph(Some(&progress.state), config.ascii_only).into(),
+                progress_style(progress, &config.palette),
+            ),
+            Field::Name => (
+                ascii_if(config.ascii_only, &value.name),
+                name_style(value.overdue_by(SystemTime::now()), &config.palette),
+            ),
+            Field::Pos => (format_value(step, progress.done_at, false).into(), values_style),
+            Field::Len => (
+                progress
+                    .done_at
+                    .map_or_else(|| "?".into(), |done_at| format_value(done_at, None, true))
+                    .into(),
+                values_style,
+            ),
+            Field::Unit => (
+                progress
+                    .unit
+                    .as_ref()
+                    .map(|unit| unit.display(step, progress.done_at, None).unit().to_string())
+                    .unwrap_or_default()
+                    .into(),
+                Style::new(),
+            ),
+            Field::Percent => (
+                progress
+                    .fraction()
+                    .map(|fraction| format!("{}%", (fraction * 100.0).floor() as usize))
+                    .unwrap_or_default()
+                    .into(),
+                values_style,
+            ),
+            Field::Rate => (
+                steps_per_second
+                    .map(|rate| format!("{}/s", format_value(rate.round() as progress::Step, None, false)))
+                    .unwrap_or_default()
+                    .into(),
+                Style::new(),
+            ),
+            Field::Eta => (
+                progress
+                    .done_at
+                    .zip(steps_per_second)
+                    .filter(|(_, rate)| *rate > 0.0)
+                    .map(|(done_at, rate)| format_eta(done_at.saturating_sub(step) as f64 / rate))
+                    .unwrap_or_default()
+                    .into(),
+                Style::new(),
+            ),
+            Field::Note => (
+                value
+                    .note
+                    .as_deref()
+                    .map(|note| ascii_if(config.ascii_only, note))
+                    .unwrap_or_default(),
+                Style::new().dimmed(),
+            ),
+        };
+        buf.push(brush.style(style).paint(pad(text, align, width)));
+    }
+
+    if let Some((idx, width)) = bar_at {
+        let blocks = width.map_or_else(
+            || {
+                config
+                    .terminal_dimensions
+                    .0
+                    .saturating_sub(block_count_sans_ansi_codes(buf, None))
+            },
+            |width| width as u16,
+        );
+        let mut bar = Vec::new();
+        draw_progress_bar(
+            progress,
+            text.fraction,
+            progress_style(progress, &config.palette),
+            blocks.saturating_add(1),
+            config.colored,
+            &mut bar,
+        );
+        // The bar comes with a leading space, but spacing is up to the template.
+        bar[0] = "[".into();
+        buf.splice(idx..idx, bar);
+    }
+}
+
+/// Pad `text` to be at least `width` columns wide, aligned as specified.
+fn pad(text: Cow<'_, str>, align: Align, width: Option<usize>) -> Cow<'_, str> {
+    let fill = width.map_or(0, |width| width.saturating_sub(text.width()));
+    if fill == 0 {
+        return text;
+    }
+    match align {
+        Align::Left => format!("{text}{:fill$}", ""),
+        Align::Right => format!("{:fill$}{text}", ""),
+        Align::Center => format!(
+            "{:left$}{text}{:right$}",
+            "",
+            "",
+            left = fill / 2,
+            right = fill - fill / 2
+        ),
+    }
+    .into()
+}
+
+fn format_eta(secs: f64) -> String {
+    let secs = secs.ceil() as u64;
+    match (secs / 3600, secs / 60 % 60, secs % 60) {
+        (0, 0, s) => format!("{s}s"),
+        (0, m, s) => format!("{m}m{s:02}s"),
+        (h, m, _) => format!("{h}h{m:02}m"),
     }
 }
@@ -1,16 +1,65 @@
-#[cfg(feature = "signal-hook")]
-use std::sync::Arc;
+#[cfg(any(feature = "signal-hook", feature = "render-line-async"))]
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::{
     io,
     ops::RangeInclusive,
-    sync::atomic::{AtomicBool, Ordering},
-    time::Duration,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant, SystemTime},
 };
 
-use crate::{progress, render::line::draw, Throughput, WeakRoot};
+use crate::{
+    messages::{Message, MessageCopyState},
+    progress,
+    render::{
+        line::{draw, Template},
+        registry, ColorDepth, Control, Eco, EcoMode, OnConflict, Report, StreamKind, Theme,
+    },
+    time::{TimestampFormat, Timezone},
+    Throughput, WeakRoot,
+};
+
+/// How to get the user's attention when a [failure message][crate::messages::MessageLevel::Failure] is shown.
+#[derive(Clone)]
+#[cfg_attr(feature = "config", derive(serde::Deserialize), serde(rename_all = "kebab-case"))]
+pub enum AlertMode {
+    /// Ring the terminal bell.
+    Bell,
+    /// Briefly flash the terminal by inverting its colors until the next frame is drawn.
+    Flash,
+    /// Call the given function with each failure message, for instance to send a notification.
+    #[cfg_attr(feature = "config", serde(skip))]
+    Callback(Arc<dyn Fn(&Message) + Send + Sync>),
+}
+
+/// A function called with the error that makes the render thread stop, see [`Options::on_error`].
+pub type OnError = Arc<dyn Fn(&io::Error) + Send + Sync>;
+
+/// Whether to use ANSI escape codes for colors and for moving the cursor when drawing progress.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "config", derive(serde::Deserialize), serde(rename_all = "kebab-case"))]
+pub enum AnsiMode {
+    /// Use escape codes unless [`auto_configure()`][Options::auto_configure()] determined that the output isn't a terminal.
+    #[default]
+    Auto,
+    /// Always use escape codes, even if the output isn't a terminal.
+    Always,
+    /// Never use escape codes, and draw each frame of progress as plain text below the previous one.
+    ///
+    /// This keeps output captured in files or CI logs readable while still showing progress periodically,
+    /// if progress is forced on by setting [`output_is_terminal`][Options::output_is_terminal].
+    Never,
+}
+
+impl AnsiMode {
+    /// Return true if escape codes should be used.
+    pub fn is_enabled(&self) -> bool {
+        !matches!(self, AnsiMode::Never)
+    }
+}
 
 /// Options used for configuring a [line renderer][render()].
 #[derive(Clone)]
+#[cfg_attr(feature = "config", derive(serde::Deserialize), serde(default))]
 pub struct Options {
     /// If true, _(default true)_, we assume the output stream belongs to a terminal.
     ///
@@ -24,9 +73,21 @@ pub struct Options {
     /// this field to true.
     pub colored: bool,
 
+    /// Whether to use escape codes for colors and cursor movement _(default: [auto][AnsiMode::Auto])_.
+    ///
+    /// Without them, [`colored`][Options::colored] has no effect and progress frames are written one after another
+    /// instead of being redrawn in place.
+    pub ansi: AnsiMode,
+
     /// If true, _(default: false)_, a timestamp will be shown before each message.
     pub timestamp: bool,
 
+    /// The way timestamps are formatted _(default: hours, minutes and seconds)_, if [`timestamp`][Options::timestamp] is set.
+    pub timestamp_format: TimestampFormat,
+
+    /// The timezone of timestamps _(default: local time with the `local-time` feature toggle, UTC otherwise)_.
+    pub timezone: Timezone,
+
     /// The amount of columns and rows to use for drawing. Defaults to (80, 20).
     pub terminal_dimensions: (u16, u16),
 
@@ -57,21 +118,120 @@ pub struct Options {
     /// The amount of frames to draw per second. If below 1.0, it determines the amount of seconds between the frame.
     ///
     /// *e.g.* 1.0/4.0 is one frame every 4 seconds.
+    /// It can be changed while rendering with [`Control::set_frames_per_second()`] if [`control`][Options::control] is set.
     pub frames_per_second: f32,
 
+    /// If true, _(default: false)_, all non-ASCII characters of task names, notes and messages will be replaced with `?`.
+    ///
+    /// Use this for terminals without Unicode support, like some serial consoles, which would otherwise show garbled output.
+    /// It's set automatically by [`auto_configure()`][Options::auto_configure()] if the locale or terminal suggest that
+    /// Unicode isn't supported.
+    pub ascii_only: bool,
+
     /// If true (default: true), we will keep waiting for progress even after we encountered an empty list of drawable progress items.
     ///
     /// Please note that you should add at least one item to the `prodash::Tree` before launching the application or else
     /// risk a race causing nothing to be rendered at all.
     pub keep_running_if_progress_is_empty: bool,
-}
 
-/// The kind of stream to use for auto-configuration.
-pub enum StreamKind {
-    /// Standard output
-    Stdout,
-    /// Standard error
-    Stderr,
+    /// If true (default: true), we will stop rendering once the progress isn't available anymore, i.e. once the application
+    /// dropped its root.
+    ///
+    /// Otherwise the renderer keeps running without showing progress until it is shut down via its [`JoinHandle`].
+    pub stop_if_progress_missing: bool,
+
+    /// If set, each line of progress is drawn according to the given template instead of the default layout.
+    ///
+    /// This makes it possible to match the look of other tools, see [`Template`] for details.
+    pub template: Option<Template>,
+
+    /// If set, it will be called with the error that makes the render thread stop, like a broken pipe when writing to the output.
+    ///
+    /// Use it to react to failing output, for example by switching to logging instead. See also [`JoinHandle::take_error()`].
+    #[cfg_attr(feature = "config", serde(skip))]
+    pub on_error: Option<OnError>,
+
+    /// If set, _(default: None)_, get the user's attention as soon as a failure message is shown.
+    ///
+    /// This is useful for long unattended runs, which otherwise would reveal failures only once they are done.
+    /// The bell and flash are only used if [`output_is_terminal`][Options::output_is_terminal] is set.
+    pub alert_on_failure: Option<AlertMode>,
+
+    /// If set, it allows to pause and resume rendering, change the level filter or frame rate, or request an interrupt
+    /// while rendering.
+    ///
+    /// A level filter set through it takes precedence over [`level_filter`][Options::level_filter].
+    #[cfg_attr(feature = "config", serde(skip))]
+    pub control: Option<Control>,
+
+    /// If set, _(default: None)_, the stream `out` writes to, to prevent other renderers from drawing to it at the same time.
+    ///
+    /// It's set by [`auto_configure()`][Options::auto_configure()], and log messages of prodash are suppressed while
+    /// drawing to the stream set with [`set_log_stream()`][crate::render::set_log_stream()].
+    pub stream: Option<StreamKind>,
+
+    /// What to do if another renderer draws to [`stream`][Options::stream] already _(default: [fail][OnConflict::Fail])_.
+    ///
+    /// If it fails, no progress is drawn and the error is available through [`on_error`][Options::on_error] and
+    /// [`JoinHandle::take_error()`].
+    pub on_conflict: OnConflict,
+
+    /// If set, _(default: None)_, the colors to draw with instead of the colors of the terminal's palette.
+    ///
+    /// They are approximated by the closest colors available at the [`color_depth`][Options::color_depth].
+    pub theme: Option<Theme>,
+
+    /// The amount of colors the terminal can display _(default: [16 colors][ColorDepth::Ansi16])_, which is only used
+    /// with a [`theme`][Options::theme].
+    ///
+    /// It's set by [`auto_configure()`][Options::auto_configure()] from the `COLORTERM` and `TERM` environment variables.
+    pub color_depth: ColorDepth,
+
+    /// If set, _(default: None)_, only messages received after this state was obtained are shown, instead of all
+    /// messages the progress holds.
+    ///
+    /// It's set by [`switch()`][crate::render::switch()] so messages shown by the previous renderer aren't shown again.
+    #[cfg_attr(feature = "config", serde(skip))]
+    pub message_copy_state: Option<MessageCopyState>,
+
+    /// When to save energy by drawing at most one frame per second _(default: [on battery][EcoMode::OnBattery])_.
+    ///
+    /// Progress is only redrawn if it changed, in eco mode or not.
+    pub eco_mode: EcoMode,
+
+    /// If true, _(default: false)_, a glyph showing whether a task is running, halted or blocked is drawn before its name.
+    ///
+    /// This keeps the state visible if there is little space for the progress bar. Templates show it with `{status}`.
+    pub status_glyphs: bool,
+
+    /// If true, _(default: false)_, bars of bounded tasks move towards their actual fill level over several frames,
+    /// without ever exceeding it.
+    ///
+    /// This makes bars move smoothly even if progress is updated rarely, given a high enough `frames_per_second`.
+    pub smooth_bars: bool,
+
+    /// If true, _(default: false)_, the time elapsed since the run started is shown after the first line of progress,
+    /// like `⏱ 1m 3s`.
+    ///
+    /// The run started when the progress was created if it [knows that][crate::Root::started_at()], or when the
+    /// renderer was started otherwise.
+    pub show_elapsed: bool,
+
+    /// If set, _(default: None)_, progress is drawn into this many rows at the bottom of the terminal, which are
+    /// excluded from scrolling, like cargo does.
+    ///
+    /// Messages and anything else written to the terminal scroll in the rows above, instead of being interleaved with
+    /// progress. Tasks that don't fit into the rows aren't shown. At least one row is left for scrolling, and the rows
+    /// are released once rendering stops. It requires [escape codes][Options::ansi], without them it has no effect.
+    ///
+    /// Note that output written while a frame is drawn may end up in the rows of progress until they are drawn again.
+    pub pinned_rows: Option<u16>,
+
+    /// If set, _(default: None)_, a summary of the top-level tasks with their outcome and duration, followed by all
+    /// failures, is written to this stream once rendering stopped and the cursor was restored.
+    ///
+    /// Use it to keep the outcome of tasks visible, as finished tasks disappear from the progress as it's drawn.
+    pub exit_report: Option<StreamKind>,
 }
 
 /// Convenience
@@ -80,16 +240,30 @@ impl Options {
     ///
     /// * output_is_terminal
     /// * colored
+    /// * ansi, but only if it is [auto][AnsiMode::Auto]
     /// * terminal_dimensions
+    /// * ascii_only
     /// * hide-cursor (based on presence of 'signal-hook' feature.
+    /// * stream
+    /// * color_depth
     #[cfg(feature = "render-line-autoconfigure")]
     pub fn auto_configure(mut self, output: StreamKind) -> Self {
+        self.stream = Some(output);
         self.output_is_terminal = match output {
             StreamKind::Stdout => is_terminal::is_terminal(std::io::stdout()),
             StreamKind::Stderr => is_terminal::is_terminal(std::io::stderr()),
         };
+        if self.ansi == AnsiMode::Auto {
+            self.ansi = if self.output_is_terminal {
+                AnsiMode::Always
+            } else {
+                AnsiMode::Never
+            };
+        }
         self.colored = self.output_is_terminal && crosstermion::color::allowed();
         self.terminal_dimensions = crosstermion::terminal::size().unwrap_or((80, 20));
+        self.ascii_only = !unicode_is_supported();
+        self.color_depth = ColorDepth::detect();
         #[cfg(feature = "signal-hook")]
         self.auto_hide_cursor();
         self
@@ -105,12 +279,33 @@ impl Options {
     }
 }
 
+/// Return false if the terminal or the locale indicate that Unicode can't be displayed.
+///
+/// If nothing is known, we assume Unicode is supported.
+#[cfg(feature = "render-line-autoconfigure")]
+fn unicode_is_supported() -> bool {
+    if std::env::var_os("TERM").is_some_and(|term| term == "dumb") {
+        return false;
+    }
+    ["LC_ALL", "LC_CTYPE", "LANG"]
+        .iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|value| !value.is_empty())
+        .is_none_or(|locale| {
+            let locale = locale.to_ascii_lowercase();
+            locale.contains("utf-8") || locale.contains("utf8")
+        })
+}
+
 impl Default for Options {
     fn default() -> Self {
         Options {
             output_is_terminal: true,
             colored: true,
+            ansi: AnsiMode::Auto,
             timestamp: false,
+            timestamp_format: TimestampFormat::default(),
+            timezone: Timezone::default(),
             terminal_dimensions: (80, 20),
             hide_cursor: false,
             level_filter: None,
@@ -118,16 +313,34 @@ impl Default for Options {
             frames_per_second: 6.0,
             throughput: false,
             keep_running_if_progress_is_empty: true,
+            stop_if_progress_missing: true,
+            ascii_only: false,
+            template: None,
+            on_error: None,
+            alert_on_failure: None,
+            control: None,
+            stream: None,
+            on_conflict: OnConflict::default(),
+            theme: None,
+            color_depth: ColorDepth::default(),
+            message_copy_state: None,
+            eco_mode: EcoMode::default(),
+            status_glyphs: false,
+            smooth_bars: false,
+            show_elapsed: false,
+            pinned_rows: None,
+            exit_report: None,
         }
     }
 }
 
 /// A handle to the render thread, which when dropped will instruct it to stop showing progress.
 pub struct JoinHandle {
-    inner: Option<std::thread::JoinHandle<io::Result<()>>>,
+    inner: Option<std::thread::JoinHandle<()>>,
     connection: std::sync::mpsc::SyncSender<Event>,
     // If we disconnect before sending a Quit event, the selector continuously informs about the 'Disconnect' state
     disconnected: bool,
+    error: Arc<Mutex<Option<io::Error>>>,
 }
 
 impl JoinHandle {
@@ -162,6 +375,12 @@ impl JoinHandle {
         self.shutdown();
         self.wait();
     }
+    /// Return the error that made the render thread stop, like a broken pipe when writing to the output, if there was one.
+    ///
+    /// The error is returned only once. Use [`Options::on_error`] to learn about it as it happens.
+    pub fn take_error(&mut self) -> Option<io::Error> {
+        self.error.lock().ok().and_then(|mut error| error.take())
+    }
 }
 
 impl Drop for JoinHandle {
@@ -175,143 +394,129 @@ impl Drop for JoinHandle {
 enum Event {
     Tick,
     Quit,
-    #[cfg(feature = "signal-hook")]
-    Resize(u16, u16),
 }
 
 /// Write a line-based representation of `progress` to `out` which is assumed to be a terminal.
 ///
 /// Configure it with `config`, see the [`Options`] for details.
+/// As `progress` is a weak handle, rendering stops once the application drops its root,
+/// unless [`Options::stop_if_progress_missing`] is unset.
 pub fn render(
-    mut out: impl io::Write + Send + 'static,
+    out: impl io::Write + Send + 'static,
     progress: impl WeakRoot + Send + 'static,
-    Options {
-        output_is_terminal,
-        colored,
-        timestamp,
-        level_filter,
-        terminal_dimensions,
-        initial_delay,
-        frames_per_second,
-        keep_running_if_progress_is_empty,
-        hide_cursor,
-        throughput,
-    }: Options,
+    config: Options,
 ) -> JoinHandle {
-    #[cfg_attr(not(feature = "signal-hook"), allow(unused_mut))]
-    let mut config = draw::Options {
-        level_filter,
-        terminal_dimensions,
-        keep_running_if_progress_is_empty,
-        output_is_terminal,
-        colored,
-        timestamp,
-        hide_cursor,
-    };
+    let duration_per_frame = Duration::from_secs_f32(1.0 / config.frames_per_second);
+    let control = config.control.clone();
+    let mut eco = Eco::new(config.eco_mode);
+    let ticks = std::iter::once(Instant::now()).chain(std::iter::repeat_with(move || {
+        std::thread::sleep(eco.duration_per_frame(adjusted_duration_per_frame(duration_per_frame, control.as_ref())));
+        Instant::now()
+    }));
+    render_with_ticks(out, progress, config, ticks)
+}
 
-    let (event_send, event_recv) = std::sync::mpsc::sync_channel::<Event>(1);
-    let show_cursor = possibly_hide_cursor(&mut out, hide_cursor && output_is_terminal);
-    static SHOW_PROGRESS: AtomicBool = AtomicBool::new(false);
-    #[cfg(feature = "signal-hook")]
-    let term_signal_received: Arc<AtomicBool> = Arc::new(AtomicBool::new(false));
-    #[cfg(feature = "signal-hook")]
-    let terminal_resized: Arc<AtomicBool> = Arc::new(AtomicBool::new(false));
-    #[cfg(feature = "signal-hook")]
-    {
-        for sig in signal_hook::consts::TERM_SIGNALS {
-            signal_hook::flag::register(*sig, term_signal_received.clone()).ok();
+/// Like [`render()`], but returns a future which draws a frame whenever it's due while it is polled, instead of
+/// starting threads.
+///
+/// This is useful for fully asynchronous applications, which can run it as a task on their executor.
+/// It completes once rendering stops naturally, or with the error that made it stop, like a broken pipe when writing
+/// to the output. Dropping the future stops rendering after drawing one last frame and restoring the cursor.
+#[cfg(feature = "render-line-async")]
+pub async fn render_async(out: impl io::Write, progress: impl WeakRoot, config: Options) -> io::Result<()> {
+    let duration_per_frame = Duration::from_secs_f32(1.0 / config.frames_per_second);
+    let mut eco = Eco::new(config.eco_mode);
+    let replaced = Arc::new(AtomicBool::new(false));
+    let mut renderer = Renderer::new(out, progress, config, {
+        let replaced = Arc::clone(&replaced);
+        move || replaced.store(true, Ordering::SeqCst)
+    })?;
+    loop {
+        let event = if replaced.load(Ordering::SeqCst) {
+            Event::Quit
+        } else {
+            Event::Tick
+        };
+        if !renderer.handle(event)? {
+            return Ok(());
         }
-
-        #[cfg(unix)]
-        signal_hook::flag::register(signal_hook::consts::SIGWINCH, terminal_resized.clone()).ok();
+        async_io::Timer::after(eco.duration_per_frame(adjusted_duration_per_frame(
+            duration_per_frame,
+            renderer.control.as_ref(),
+        )))
+        .await;
     }
+}
 
+/// Return the time between frames as set through `control`, or `duration_per_frame` if it doesn't say.
+fn adjusted_duration_per_frame(duration_per_frame: Duration, control: Option<&Control>) -> Duration {
+    control
+        .and_then(Control::frames_per_second)
+        .and_then(|fps| Duration::try_from_secs_f32(1.0 / fps).ok())
+        .unwrap_or(duration_per_frame)
+}
+
+/// Like [`render()`], but a frame is drawn for each item produced by `ticks` instead of in regular intervals.
+///
+/// This makes drawing frames controllable, which is useful for testing or for driving the renderer from custom schedulers,
+/// for instance by passing the receiving end of a channel as `ticks`.
+/// [`Options::frames_per_second`] and [`Options::eco_mode`] are ignored, and once `ticks` is depleted no more frames
+/// will be drawn until shutdown.
+pub fn render_with_ticks(
+    out: impl io::Write + Send + 'static,
+    progress: impl WeakRoot + Send + 'static,
+    config: Options,
+    ticks: impl Iterator<Item = Instant> + Send + 'static,
+) -> JoinHandle {
+    let (event_send, event_recv) = std::sync::mpsc::sync_channel::<Event>(1);
+    let renderer = Renderer::new(out, progress, config, {
+        let connection = event_send.clone();
+        move || {
+            connection.send(Event::Quit).ok();
+        }
+    });
+    let mut renderer = match renderer {
+        Ok(renderer) => renderer,
+        Err(err) => {
+            return JoinHandle {
+                inner: None,
+                connection: event_send,
+                disconnected: true,
+                error: Arc::new(Mutex::new(Some(err))),
+            };
+        }
+    };
+
+    let error = Arc::new(Mutex::new(None));
     let handle = std::thread::Builder::new()
         .name("render-line-eventloop".into())
         .spawn({
             let tick_send = event_send.clone();
+            let error = Arc::clone(&error);
             move || {
-                {
-                    let initial_delay = initial_delay.unwrap_or_default();
-                    SHOW_PROGRESS.store(initial_delay == Duration::default(), Ordering::Relaxed);
-                    if !SHOW_PROGRESS.load(Ordering::Relaxed) {
-                        std::thread::Builder::new()
-                            .name("render-line-progress-delay".into())
-                            .spawn(move || {
-                                std::thread::sleep(initial_delay);
-                                SHOW_PROGRESS.store(true, Ordering::Relaxed);
-                            })
-                            .ok();
-                    }
-                }
-
-                let mut state = draw::State::default();
-                if throughput {
-                    state.throughput = Some(Throughput::default());
-                }
-                let secs = 1.0 / frames_per_second;
                 let _ticker = std::thread::Builder::new()
                     .name("render-line-ticker".into())
-                    .spawn(move || loop {
-                        #[cfg(feature = "signal-hook")]
-                        {
-                            if term_signal_received.load(Ordering::SeqCst) {
-                                tick_send.send(Event::Quit).ok();
+                    .spawn(move || {
+                        for _tick in ticks {
+                            if tick_send.send(Event::Tick).is_err() {
                                 break;
                             }
-                            if terminal_resized.load(Ordering::SeqCst) {
-                                terminal_resized.store(false, Ordering::SeqCst);
-                                if let Ok((x, y)) = crosstermion::terminal::size() {
-                                    tick_send.send(Event::Resize(x, y)).ok();
-                                }
-                            }
-                        }
-                        if tick_send.send(Event::Tick).is_err() {
-                            break;
                         }
-                        std::thread::sleep(Duration::from_secs_f32(secs));
                     })
                     .expect("starting a thread works");
 
                 for event in event_recv {
-                    match event {
-                        #[cfg(feature = "signal-hook")]
-                        Event::Resize(x, y) => {
-                            config.terminal_dimensions = (x, y);
-                            draw::all(&mut out, SHOW_PROGRESS.load(Ordering::Relaxed), &mut state, &config)?;
-                        }
-                        Event::Tick => match progress.upgrade() {
-                            Some(progress) => {
-                                let has_changed = state.update_from_progress(&progress);
-                                draw::all(
-                                    &mut out,
-                                    SHOW_PROGRESS.load(Ordering::Relaxed) && has_changed,
-                                    &mut state,
-                                    &config,
-                                )?;
+                    match renderer.handle(event) {
+                        Ok(true) => {}
+                        Ok(false) => break,
+                        Err(err) => {
+                            if let Ok(mut error) = error.lock() {
+                                *error = Some(err);
                             }
-                            None => {
-                                state.clear();
-                                draw::all(&mut out, SHOW_PROGRESS.load(Ordering::Relaxed), &mut state, &config)?;
-                                break;
-                            }
-                        },
-                        Event::Quit => {
-                            state.clear();
-                            draw::all(&mut out, SHOW_PROGRESS.load(Ordering::Relaxed), &mut state, &config)?;
                             break;
                         }
                     }
                 }
-
-                if show_cursor {
-                    crosstermion::execute!(out, crosstermion::cursor::Show).ok();
-                }
-
-                // One day we might try this out on windows, but let's not risk it now.
-                #[cfg(unix)]
-                write!(out, "\x1b[2K\r").ok(); // clear the last line.
-                Ok(())
             }
         })
         .expect("starting a thread works");
@@ -320,9 +525,253 @@ pub fn render(
         inner: Some(handle),
         connection: event_send,
         disconnected: false,
+        error,
     }
 }
 
+/// Everything needed to draw frames, independently of what decides when to draw them.
+///
+/// Dropping it draws a last frame unless rendering stopped already, and restores the cursor.
+struct Renderer<W: io::Write, P: WeakRoot> {
+    out: W,
+    progress: P,
+    state: draw::State,
+    config: draw::Options,
+    level_filter: Option<RangeInclusive<progress::key::Level>>,
+    control: Option<Control>,
+    stop_if_progress_missing: bool,
+    on_error: Option<OnError>,
+    show_progress_at: Instant,
+    show_cursor: bool,
+    /// If true, no more frames are drawn.
+    stopped: bool,
+    #[cfg(feature = "signal-hook")]
+    term_signal_received: Arc<AtomicBool>,
+    #[cfg(feature = "signal-hook")]
+    terminal_resized: Arc<AtomicBool>,
+    _registration: Option<registry::Registration>,
+    /// Written once dropped, which happens after the last frame was drawn and the cursor was restored.
+    report: Option<Report>,
+}
+
+impl<W: io::Write, P: WeakRoot> Renderer<W, P> {
+    /// Prepare drawing to `out`, calling `on_replaced()` if another renderer takes over the stream we draw to.
+    ///
+    /// On error, it was already passed to [`on_error`][Options::on_error].
+    fn new(
+        mut out: W,
+        progress: P,
+        Options {
+            output_is_terminal,
+            colored,
+            ansi,
+            timestamp,
+            timestamp_format,
+            timezone,
+            level_filter,
+            terminal_dimensions,
+            initial_delay,
+            frames_per_second: _,
+            keep_running_if_progress_is_empty,
+            hide_cursor,
+            throughput,
+            ascii_only,
+            stop_if_progress_missing,
+            template,
+            on_error,
+            alert_on_failure,
+            control,
+            stream,
+            on_conflict,
+            theme,
+            color_depth,
+            message_copy_state,
+            eco_mode: _,
+            status_glyphs,
+            smooth_bars,
+            show_elapsed,
+            pinned_rows,
+            exit_report,
+        }: Options,
+        on_replaced: impl Fn() + Send + 'static,
+    ) -> io::Result<Self> {
+        let ansi = ansi.is_enabled();
+        let config = draw::Options {
+            level_filter: level_filter.clone(),
+            terminal_dimensions,
+            keep_running_if_progress_is_empty,
+            output_is_terminal,
+            colored: colored && ansi,
+            ansi,
+            timestamp,
+            timestamp_format,
+            timezone,
+            start: SystemTime::now(),
+            hide_cursor,
+            ascii_only,
+            template,
+            alert_on_failure,
+            status_glyphs,
+            smooth_bars,
+            show_elapsed,
+            pinned_rows,
+            palette: theme.map_or_else(draw::Palette::default, |theme| draw::Palette::new(&theme, color_depth)),
+        };
+
+        let registration = stream
+            .map(|stream| registry::register(stream, on_conflict, on_replaced))
+            .transpose();
+        let registration = match registration {
+            Ok(registration) => registration,
+            Err(err) => {
+                if let Some(on_error) = on_error.as_ref() {
+                    on_error(&err);
+                }
+                return Err(err);
+            }
+        };
+        let show_cursor = possibly_hide_cursor(&mut out, hide_cursor && output_is_terminal && ansi);
+        #[cfg(feature = "signal-hook")]
+        let term_signal_received: Arc<AtomicBool> = Arc::new(AtomicBool::new(false));
+        #[cfg(feature = "signal-hook")]
+        let terminal_resized: Arc<AtomicBool> = Arc::new(AtomicBool::new(false));
+        #[cfg(feature = "signal-hook")]
+        {
+            for sig in signal_hook::consts::TERM_SIGNALS {
+                signal_hook::flag::register(*sig, term_signal_received.clone()).ok();
+            }
+
+            #[cfg(unix)]
+            signal_hook::flag::register(signal_hook::consts::SIGWINCH, terminal_resized.clone()).ok();
+        }
+
+        let mut state = draw::State::default();
+        state.for_next_copy = message_copy_state;
+        if throughput {
+            state.throughput = Some(Throughput::default());
+        }
+        Ok(Renderer {
+            out,
+            progress,
+            state,
+            config,
+            level_filter,
+            control,
+            stop_if_progress_missing,
+            on_error,
+            show_progress_at: Instant::now() + initial_delay.unwrap_or_default(),
+            show_cursor,
+            stopped: false,
+            #[cfg(feature = "signal-hook")]
+            term_signal_received,
+            #[cfg(feature = "signal-hook")]
+            terminal_resized,
+            _registration: registration,
+            report: exit_report.map(Report::new),
+        })
+    }
+
+    /// Draw a frame for `event` and return `true` if rendering should continue.
+    ///
+    /// Errors are passed to [`on_error`][Options::on_error] before they are returned.
+    fn handle(&mut self, event: Event) -> io::Result<bool> {
+        let res = self.draw(event);
+        self.stopped = !matches!(res, Ok(true));
+        if let (Err(err), Some(on_error)) = (res.as_ref(), self.on_error.as_ref()) {
+            on_error(err);
+        }
+        res
+    }
+
+    fn draw(&mut self, event: Event) -> io::Result<bool> {
+        #[cfg(feature = "signal-hook")]
+        let event = if self.term_signal_received.load(Ordering::SeqCst) {
+            Event::Quit
+        } else {
+            if self.terminal_resized.swap(false, Ordering::SeqCst) {
+                if let Ok(dimensions) = crosstermion::terminal::size() {
+                    self.config.terminal_dimensions = dimensions;
+                }
+            }
+            event
+        };
+        let show_progress = Instant::now() >= self.show_progress_at;
+        Ok(match event {
+            Event::Tick if self.control.as_ref().is_some_and(Control::is_paused) => {
+                draw::end_flash(&mut self.out, &mut self.state)?;
+                true
+            }
+            Event::Tick => match self.progress.upgrade() {
+                Some(progress) => {
+                    let mut has_changed = self.state.update_from_progress(&progress)
+                        | self.state.bars_are_moving()
+                        | self.state.elapsed_changed(&self.config);
+                    if let Some(report) = self.report.as_mut() {
+                        report.update(self.state.tasks(), &progress);
+                    }
+                    if let Some(control) = self.control.as_ref() {
+                        let filter = control.level_filter().or_else(|| self.level_filter.clone());
+                        has_changed |= filter != self.config.level_filter;
+                        self.config.level_filter = filter;
+                    }
+                    draw::all(
+                        &mut self.out,
+                        show_progress && has_changed,
+                        &mut self.state,
+                        &self.config,
+                    )?
+                }
+                None => {
+                    self.state.clear();
+                    draw::all(&mut self.out, show_progress, &mut self.state, &self.config)?
+                        && !self.stop_if_progress_missing
+                }
+            },
+            Event::Quit => {
+                self.state.clear();
+                draw::all(&mut self.out, show_progress, &mut self.state, &self.config)?;
+                false
+            }
+        })
+    }
+}
+
+impl<W: io::Write, P: WeakRoot> Drop for Renderer<W, P> {
+    fn drop(&mut self) {
+        if !self.stopped && matches!(self.handle(Event::Tick), Ok(true)) {
+            self.handle(Event::Quit).ok();
+        }
+        draw::end_flash(&mut self.out, &mut self.state).ok();
+        draw::unpin_rows(&mut self.out, &mut self.state).ok();
+        if self.show_cursor {
+            crosstermion::execute!(self.out, crosstermion::cursor::Show).ok();
+        }
+
+        // One day we might try this out on windows, but let's not risk it now.
+        #[cfg(unix)]
+        if self.config.ansi {
+            write!(self.out, "\x1b[2K\r").ok(); // clear the last line.
+        }
+    }
+}
+
+/// Like [`render()`], but runs `f` on the current thread while progress is being rendered, returning its result.
+///
+/// The renderer is shut down and waited for once `f` returns, which also happens if `f` panics. That way the last frame
+/// is always drawn and the cursor is restored, without having to manage the [`JoinHandle`] manually.
+pub fn render_scoped<T>(
+    out: impl io::Write + Send + 'static,
+    progress: impl WeakRoot + Send + 'static,
+    options: Options,
+    f: impl FnOnce() -> T,
+) -> T {
+    // Dropping the handle during unwinding shuts down the render thread and waits for it.
+    let handle = render(out, progress, options);
+    let res = f();
+    handle.shutdown_and_wait();
+    res
+}
+
 // Not all configurations actually need it to be mut, but those with the 'signal-hook' feature do
 #[allow(unused_mut)]
 fn possibly_hide_cursor(out: &mut impl io::Write, mut hide_cursor: bool) -> bool {
@@ -6,5 +6,11 @@ compile_error!("Please choose either one of these features: 'render-line-crosste
 
 mod draw;
 mod engine;
+///
+pub mod template;
 
-pub use engine::{render, JoinHandle, Options, StreamKind};
+pub use crate::render::StreamKind;
+#[cfg(feature = "render-line-async")]
+pub use engine::render_async;
+pub use engine::{render, render_scoped, render_with_ticks, AlertMode, AnsiMode, JoinHandle, OnError, Options};
+pub use template::Template;
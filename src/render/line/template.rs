@@ -0,0 +1,168 @@
+use std::{io, str::FromStr};
+
+/// A property of a task that can be drawn as part of a [`Template`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Field {
+    /// `{name}` - the name of the task.
+    Name,
+    /// `{status}` - a glyph showing whether the task is running, halted or blocked.
+    Status,
+    /// `{bar}` - the progress bar, filling all remaining space unless a width is given.
+    Bar,
+    /// `{pos}` - the current step, formatted by the unit if there is one.
+    Pos,
+    /// `{len}` - the step at which the task is done, or `?` if it is unbounded.
+    Len,
+    /// `{unit}` - the label of the unit, if there is one.
+    Unit,
+    /// `{percent}` - the percentage of completion, if the task is bounded.
+    Percent,
+    /// `{rate}` - the amount of steps per second, if throughput is enabled.
+    Rate,
+    /// `{eta}` - the estimated time until the task is done, if it is bounded and throughput is enabled.
+    Eta,
+    /// `{note}` - the note of the task, if set.
+    Note,
+}
+
+impl FromStr for Field {
+    type Err = io::Error;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        Ok(match name {
+            "name" => Field::Name,
+            "status" => Field::Status,
+            "bar" => Field::Bar,
+            "pos" => Field::Pos,
+            "len" => Field::Len,
+            "unit" => Field::Unit,
+            "percent" => Field::Percent,
+            "rate" => Field::Rate,
+            "eta" => Field::Eta,
+            "note" => Field::Note,
+            _ => return Err(invalid(format!("Unknown field '{name}'"))),
+        })
+    }
+}
+
+/// How to align a field within its width.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub enum Align {
+    /// `<`, fill the space after the field.
+    #[default]
+    Left,
+    /// `>`, fill the space before the field.
+    Right,
+    /// `^`, fill the space before and after the field equally.
+    Center,
+}
+
+/// A piece of a parsed [`Template`].
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Segment {
+    /// Text to be drawn as is.
+    Literal(String),
+    /// A property of the task, drawn with at least `width` columns if set.
+    Field {
+        /// The property to draw.
+        field: Field,
+        /// How to align the property if it's shorter than `width`.
+        align: Align,
+        /// The minimal amount of columns to use, or the exact amount for the progress bar.
+        width: Option<usize>,
+    },
+}
+
+/// A format string defining how each line of progress is drawn, like `"{name:<30} {bar:40} {pos}/{len} {unit} {rate} {eta}"`.
+///
+/// Fields are written as `{field}` or `{field:<width}`, with `<`, `>` and `^` to align left, right or centered respectively.
+/// Use `{{` and `}}` to draw braces. See [`Field`] for all available fields.
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "config", derive(serde::Deserialize), serde(try_from = "String"))]
+pub struct Template {
+    segments: Vec<Segment>,
+}
+
+impl Template {
+    /// Parse `input` into a template, or fail with [`InvalidInput`][io::ErrorKind::InvalidInput] if it is malformed.
+    pub fn parse(input: &str) -> io::Result<Self> {
+        let mut segments = Vec::new();
+        let mut literal = String::new();
+        let mut chars = input.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.as_str().starts_with('{') => {
+                    chars.next();
+                    literal.push('{');
+                }
+                '}' if chars.as_str().starts_with('}') => {
+                    chars.next();
+                    literal.push('}');
+                }
+                '{' => {
+                    let rest = chars.as_str();
+                    let end = rest
+                        .find('}')
+                        .ok_or_else(|| invalid(format!("Unclosed '{{' in template '{input}'")))?;
+                    if !literal.is_empty() {
+                        segments.push(Segment::Literal(std::mem::take(&mut literal)));
+                    }
+                    segments.push(parse_field(&rest[..end])?);
+                    chars = rest[end + 1..].chars();
+                }
+                '}' => return Err(invalid(format!("Unmatched '}}' in template '{input}'"))),
+                c => literal.push(c),
+            }
+        }
+        if !literal.is_empty() {
+            segments.push(Segment::Literal(literal));
+        }
+        Ok(Template { segments })
+    }
+
+    /// Return all segments of the template in order.
+    pub fn segments(&self) -> &[Segment] {
+        &self.segments
+    }
+}
+
+impl FromStr for Template {
+    type Err = io::Error;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        Template::parse(input)
+    }
+}
+
+impl TryFrom<String> for Template {
+    type Error = io::Error;
+
+    fn try_from(input: String) -> Result<Self, Self::Error> {
+        Template::parse(&input)
+    }
+}
+
+fn parse_field(spec: &str) -> io::Result<Segment> {
+    let (name, format) = spec.split_once(':').unwrap_or((spec, ""));
+    let field = name.trim().parse()?;
+    let (align, width) = match format.chars().next() {
+        Some('<') => (Align::Left, &format[1..]),
+        Some('>') => (Align::Right, &format[1..]),
+        Some('^') => (Align::Center, &format[1..]),
+        _ => (Align::default(), format),
+    };
+    let width = if width.is_empty() {
+        None
+    } else {
+        Some(
+            width
+                .parse()
+                .map_err(|_| invalid(format!("Invalid width '{width}' of field '{name}'")))?,
+        )
+    };
+    Ok(Segment::Field { field, align, width })
+}
+
+fn invalid(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, message)
+}
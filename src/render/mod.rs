@@ -9,3 +9,69 @@ pub use self::tui::render as tui;
 pub mod line;
 #[cfg(feature = "render-line")]
 pub use self::line::render as line;
+
+#[cfg(feature = "render-callback")]
+///
+pub mod callback;
+
+mod control;
+pub use control::Control;
+
+/// Return true if `tasks` of a snapshot are empty, not counting the name of the root set with `Root::set_name()`
+/// as it isn't a task.
+#[cfg(any(feature = "render-line", feature = "render-tui", feature = "render-callback"))]
+pub(crate) fn has_no_tasks(tasks: &[(crate::progress::Key, crate::progress::Task)]) -> bool {
+    tasks.iter().all(|(key, _)| key.level() == 0)
+}
+
+#[cfg(any(feature = "render-line", feature = "render-tui"))]
+///
+pub mod theme;
+#[cfg(any(feature = "render-line", feature = "render-tui"))]
+pub use theme::{ColorDepth, Theme};
+
+#[cfg(any(feature = "render-line", feature = "render-tui"))]
+mod status;
+#[cfg(any(feature = "render-line", feature = "render-tui"))]
+pub(crate) use status::status_glyph;
+
+#[cfg(any(feature = "render-line", feature = "render-tui"))]
+mod report;
+#[cfg(any(feature = "render-line", feature = "render-tui"))]
+pub(crate) use report::Report;
+
+#[cfg(any(feature = "render-line", feature = "render-tui"))]
+mod smooth;
+#[cfg(any(feature = "render-line", feature = "render-tui"))]
+pub(crate) use smooth::SmoothFractions;
+
+#[cfg(any(feature = "render-line", feature = "render-tui"))]
+mod eco;
+#[cfg(any(feature = "render-line", feature = "render-tui"))]
+pub(crate) use eco::Eco;
+#[cfg(any(feature = "render-line", feature = "render-tui"))]
+pub use eco::{on_battery, EcoMode};
+
+#[cfg(any(feature = "render-line", feature = "render-tui"))]
+pub(crate) mod registry;
+#[cfg(any(feature = "render-line", feature = "render-tui"))]
+pub use registry::{set_log_stream, OnConflict, StreamKind};
+
+/// Without terminal renderers, there is nothing to garble log messages.
+#[cfg(all(
+    any(feature = "progress-tree-log", feature = "progress-log"),
+    not(any(feature = "render-line", feature = "render-tui"))
+))]
+pub(crate) fn is_log_suppressed() -> bool {
+    false
+}
+#[cfg(all(
+    any(feature = "progress-tree-log", feature = "progress-log"),
+    any(feature = "render-line", feature = "render-tui")
+))]
+pub(crate) use registry::is_log_suppressed;
+
+#[cfg(any(feature = "render-line", feature = "render-tui"))]
+mod handle;
+#[cfg(any(feature = "render-line", feature = "render-tui"))]
+pub use handle::{switch, Finished, RenderHandle, RendererKind};
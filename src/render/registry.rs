@@ -0,0 +1,113 @@
+use std::{
+    io,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex, MutexGuard, PoisonError,
+    },
+};
+
+/// The kind of stream a renderer draws to, used for auto-configuration and to keep renderers from drawing to the same one.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "config", derive(serde::Deserialize), serde(rename_all = "kebab-case"))]
+pub enum StreamKind {
+    /// Standard output
+    Stdout,
+    /// Standard error
+    Stderr,
+}
+
+impl StreamKind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            StreamKind::Stdout => "stdout",
+            StreamKind::Stderr => "stderr",
+        }
+    }
+}
+
+/// What to do if a renderer is started on a stream another renderer is drawing to already.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "config", derive(serde::Deserialize), serde(rename_all = "kebab-case"))]
+pub enum OnConflict {
+    /// Fail to start the new renderer with an error of kind [`AlreadyExists`][io::ErrorKind::AlreadyExists].
+    #[default]
+    Fail,
+    /// Instruct the previous renderer to stop, and start the new one right away.
+    Replace,
+}
+
+struct Entry {
+    id: u64,
+    stream: StreamKind,
+    shutdown: Box<dyn Fn() + Send>,
+}
+
+static RENDERERS: Mutex<Vec<Entry>> = Mutex::new(Vec::new());
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+static LOG_STREAM: Mutex<Option<StreamKind>> = Mutex::new(Some(StreamKind::Stderr));
+
+fn renderers() -> MutexGuard<'static, Vec<Entry>> {
+    RENDERERS.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Set the stream the logger of the application writes to _(default: [stderr][StreamKind::Stderr])_, or `None` to never
+/// suppress log messages.
+///
+/// Messages logged by prodash with the `progress-tree-log` or `progress-log` feature toggles are suppressed while a renderer
+/// draws to this stream, as they would be garbled by it otherwise.
+pub fn set_log_stream(stream: Option<StreamKind>) {
+    *LOG_STREAM.lock().unwrap_or_else(PoisonError::into_inner) = stream;
+}
+
+/// Return true if log messages shouldn't be emitted as a renderer draws to the stream the logger writes to.
+#[cfg(any(feature = "progress-tree-log", feature = "progress-log"))]
+pub(crate) fn is_log_suppressed() -> bool {
+    let Some(log_stream) = *LOG_STREAM.lock().unwrap_or_else(PoisonError::into_inner) else {
+        return false;
+    };
+    renderers().iter().any(|entry| entry.stream == log_stream)
+}
+
+/// Removes a renderer from the registry once dropped.
+pub(crate) struct Registration {
+    id: u64,
+}
+
+impl Drop for Registration {
+    fn drop(&mut self) {
+        renderers().retain(|entry| entry.id != self.id);
+    }
+}
+
+/// Register a renderer drawing to `stream`, which can be stopped by calling `shutdown()` if it's replaced by another one.
+///
+/// If there is a renderer on `stream` already, fail or shut it down according to `on_conflict`.
+pub(crate) fn register(
+    stream: StreamKind,
+    on_conflict: OnConflict,
+    shutdown: impl Fn() + Send + 'static,
+) -> io::Result<Registration> {
+    let mut renderers = renderers();
+    let previous = match (renderers.iter().position(|entry| entry.stream == stream), on_conflict) {
+        (Some(_), OnConflict::Fail) => {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!("another renderer is drawing to {} already", stream.as_str()),
+            ))
+        }
+        (Some(idx), OnConflict::Replace) => Some(renderers.remove(idx)),
+        (None, _) => None,
+    };
+    let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+    renderers.push(Entry {
+        id,
+        stream,
+        shutdown: Box::new(shutdown),
+    });
+    drop(renderers);
+
+    if let Some(previous) = previous {
+        (previous.shutdown)();
+    }
+    Ok(Registration { id })
+}
@@ -0,0 +1,153 @@
+use std::{
+    io::{self, Write},
+    time::{Duration, SystemTime},
+};
+
+use crate::{
+    messages::{Message, MessageCopyState, MessageLevel},
+    progress::{Key, State, Task},
+    render::StreamKind,
+    Root,
+};
+
+/// A top-level task as seen by the renderer.
+struct Entry {
+    key: Key,
+    name: String,
+    state: Option<State>,
+    started_at: SystemTime,
+    /// The time the task finished, or was last seen if it's gone.
+    ended_at: Option<SystemTime>,
+    gone: bool,
+}
+
+/// A concise summary of the top-level tasks and failures seen by a renderer, written to `stream` once dropped.
+///
+/// Renderers drop it after restoring the terminal, so the summary stays visible after they stopped.
+pub(crate) struct Report {
+    stream: StreamKind,
+    tasks: Vec<Entry>,
+    failures: Vec<Message>,
+    messages: Vec<Message>,
+    copy_state: Option<MessageCopyState>,
+}
+
+impl Report {
+    pub(crate) fn new(stream: StreamKind) -> Self {
+        Report {
+            stream,
+            tasks: Vec::new(),
+            failures: Vec::new(),
+            messages: Vec::new(),
+            copy_state: None,
+        }
+    }
+
+    /// Learn about the top-level tasks among `entries`, as obtained from `progress`, along with its new failures.
+    ///
+    /// Tasks that were seen before but aren't among `entries` anymore are considered finished.
+    pub(crate) fn update(&mut self, entries: &[(Key, Task)], progress: &impl Root) {
+        let now = SystemTime::now();
+        for entry in self.tasks.iter_mut().filter(|entry| !entry.gone) {
+            entry.gone = true;
+            entry.ended_at.get_or_insert(now);
+        }
+        for (key, task) in entries.iter().filter(|(key, _)| key.level() == 1) {
+            let state = task.progress.as_ref().map(|p| p.state.clone());
+            match self.tasks.iter_mut().find(|entry| entry.key == *key) {
+                Some(entry) => {
+                    entry.name.clone_from(&task.name);
+                    entry.state.clone_from(&state);
+                    entry.ended_at = task.finished_at;
+                    entry.gone = false;
+                }
+                None => self.tasks.push(Entry {
+                    key: key.clone(),
+                    name: task.name.clone(),
+                    state,
+                    started_at: task.created_at.unwrap_or(now),
+                    ended_at: task.finished_at,
+                    gone: false,
+                }),
+            }
+        }
+
+        self.copy_state = Some(progress.copy_new_messages(&mut self.messages, self.copy_state.take()));
+        self.failures.extend(
+            self.messages
+                .drain(..)
+                .filter(|message| message.level == MessageLevel::Failure),
+        );
+    }
+
+    /// Return how `entry` ended, which is only known for sure if it reached the [finished][State::Finished] state.
+    fn outcome(&self, entry: &Entry) -> &'static str {
+        match entry.state {
+            Some(State::Finished(true)) => "done",
+            Some(State::Finished(false)) => "failed",
+            _ if entry.gone => {
+                let failed = self
+                    .failures
+                    .iter()
+                    .any(|message| message.origin.key == entry.key || entry.key.is_ancestor_of(&message.origin.key));
+                if failed {
+                    "failed"
+                } else {
+                    "done"
+                }
+            }
+            Some(State::Blocked(..)) => "blocked",
+            Some(State::Halted(..)) => "halted",
+            Some(State::Running) | None => "unfinished",
+        }
+    }
+
+    /// Write one line for each top-level task with its outcome and duration, followed by all failures, to `out`.
+    fn write(&self, out: &mut impl Write, now: SystemTime) -> io::Result<()> {
+        let name_width = self
+            .tasks
+            .iter()
+            .map(|entry| entry.name.chars().count())
+            .max()
+            .unwrap_or_default();
+        for entry in &self.tasks {
+            let duration = entry
+                .ended_at
+                .unwrap_or(now)
+                .duration_since(entry.started_at)
+                .unwrap_or_default();
+            writeln!(
+                out,
+                "{:>10} {:name_width$} {}",
+                self.outcome(entry),
+                entry.name,
+                humantime::format_duration(Duration::from_millis(duration.as_millis() as u64)),
+            )?;
+        }
+        if !self.failures.is_empty() {
+            writeln!(out, "{} failures:", self.failures.len())?;
+            for message in &self.failures {
+                writeln!(out, "  {}: {}", message.origin, message.message)?;
+            }
+        }
+        out.flush()
+    }
+}
+
+impl Drop for Report {
+    fn drop(&mut self) {
+        if self.tasks.is_empty() && self.failures.is_empty() {
+            return;
+        }
+        let now = SystemTime::now();
+        let res = match self.stream {
+            StreamKind::Stdout => self.write(&mut io::stdout().lock(), now),
+            StreamKind::Stderr => self.write(&mut io::stderr().lock(), now),
+        };
+        res.ok();
+    }
+}
+
+/// Tests inspecting the report, which needs the progress tree and the messages that don't exist with the minimal feature.
+#[cfg(all(test, feature = "progress-tree", not(feature = "minimal")))]
+mod tests;
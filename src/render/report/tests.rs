@@ -0,0 +1,85 @@
+use std::time::SystemTime;
+
+use crate::render::{Report, StreamKind};
+
+/// Return the lines written by `report`, without the durations of tasks.
+fn lines(report: &Report) -> Vec<String> {
+    let mut out = Vec::new();
+    report.write(&mut out, SystemTime::now()).unwrap();
+    String::from_utf8(out)
+        .unwrap()
+        .lines()
+        .enumerate()
+        .map(|(idx, line)| {
+            if idx < report.tasks.len() {
+                line.split_whitespace().take(2).collect::<Vec<_>>().join(" ")
+            } else {
+                line.to_owned()
+            }
+        })
+        .collect()
+}
+
+#[test]
+fn top_level_tasks_are_listed_with_their_outcome_followed_by_all_failures() {
+    let root = crate::tree::Root::new();
+    let fetch = root.add_child("fetch");
+    let mut build = root.add_child("build");
+    let mut compile = build.add_child("compile");
+    let mut report = Report::new(StreamKind::Stderr);
+    let mut entries = Vec::new();
+    let mut update = |report: &mut Report| {
+        root.sorted_snapshot(&mut entries);
+        report.update(&entries, &root);
+    };
+
+    update(&mut report);
+    assert_eq!(lines(&report), ["unfinished fetch", "unfinished build"]);
+
+    compile.fail("linker error");
+    drop((fetch, compile, build));
+    update(&mut report);
+    assert_eq!(
+        lines(&report),
+        ["done fetch", "failed build", "1 failures:", "  compile: linker error"],
+        "tasks that are gone failed if they or their children sent a failure"
+    );
+
+    report.tasks.clear();
+    report.failures.clear();
+    drop(report);
+}
+
+#[test]
+fn tasks_finished_through_the_progress_trait_are_listed_with_their_outcome() {
+    use crate::Progress;
+
+    let root: std::sync::Arc<crate::tree::Root> = crate::tree::root::Options {
+        retain_finished: true,
+        ..Default::default()
+    }
+    .into();
+    let done = root.add_child("done");
+    done.init(Some(1), None);
+    let failed = root.add_child("failed");
+    failed.init(Some(1), None);
+    let mut report = Report::new(StreamKind::Stderr);
+    let mut entries = Vec::new();
+
+    (&done as &dyn Progress).done("finished".into());
+    (&failed as &dyn Progress).fail("broken".into());
+    drop((done, failed));
+    root.sorted_snapshot(&mut entries);
+    report.update(&entries, &root);
+    assert_eq!(
+        lines(&report),
+        ["done done", "failed failed", "1 failures:", "  failed: broken"]
+    );
+    assert!(
+        report.tasks.iter().all(|entry| entry.ended_at.is_some()),
+        "the durations end when the tasks finished"
+    );
+
+    report.tasks.clear();
+    report.failures.clear();
+}
@@ -0,0 +1,48 @@
+use std::{
+    collections::{HashMap, HashSet},
+    time::{Duration, Instant},
+};
+
+use crate::progress::{Key, Task};
+
+/// The time it takes a bar to cover about two thirds of the distance to the actual fraction.
+const TIME_CONSTANT: Duration = Duration::from_millis(300);
+
+/// Bars closer than this to their actual fraction are considered to have arrived.
+const EPSILON: f32 = 0.001;
+
+/// The fractions of bars as shown, eased towards the actual fractions on each frame so bars move smoothly even if
+/// their progress is updated rarely.
+#[derive(Default)]
+pub(crate) struct SmoothFractions {
+    /// The fraction shown and the actual one, along with the time they were computed.
+    by_key: HashMap<Key, (f32, f32, Instant)>,
+}
+
+impl SmoothFractions {
+    /// Return the fraction to show for the task at `key` `now`, which approaches `actual` but never exceeds it.
+    pub(crate) fn fraction(&mut self, key: &Key, actual: f32, now: Instant) -> f32 {
+        let (shown, last_actual, shown_at) = self.by_key.entry(key.clone()).or_insert((actual, actual, now));
+        *last_actual = actual;
+        if actual - *shown <= EPSILON {
+            *shown = actual;
+        } else {
+            let elapsed = now.saturating_duration_since(*shown_at).as_secs_f32();
+            *shown += (actual - *shown) * (1.0 - (-elapsed / TIME_CONSTANT.as_secs_f32()).exp());
+        }
+        *shown_at = now;
+        *shown
+    }
+
+    /// Return true if any bar didn't reach its actual fraction yet, so it should be drawn again even if no task changed.
+    #[cfg(feature = "render-line")]
+    pub(crate) fn is_moving(&self) -> bool {
+        self.by_key.values().any(|(shown, actual, _)| actual - shown > EPSILON)
+    }
+
+    /// Forget about all tasks that aren't in `tasks` anymore.
+    pub(crate) fn reconcile(&mut self, tasks: &[(Key, Task)]) {
+        let keys: HashSet<_> = tasks.iter().map(|(key, _)| key).collect();
+        self.by_key.retain(|key, _| keys.contains(key));
+    }
+}
@@ -0,0 +1,20 @@
+use crate::progress::State;
+
+/// Return a glyph showing `state`, or blanks for tasks without progress if `None`, which is two columns wide when drawn.
+///
+/// With `ascii_only`, glyphs made of ASCII characters are used instead.
+pub(crate) fn status_glyph(state: Option<&State>, ascii_only: bool) -> &'static str {
+    match (state, ascii_only) {
+        (None, _) => "  ",
+        (Some(State::Running), false) => "▶ ",
+        (Some(State::Halted(..)), false) => "⏸ ",
+        (Some(State::Blocked(..)), false) => "⛔",
+        (Some(State::Finished(true)), false) => "✔ ",
+        (Some(State::Finished(false)), false) => "✘ ",
+        (Some(State::Running), true) => "> ",
+        (Some(State::Halted(..)), true) => "||",
+        (Some(State::Blocked(..)), true) => "! ",
+        (Some(State::Finished(true)), true) => "+ ",
+        (Some(State::Finished(false)), true) => "x ",
+    }
+}
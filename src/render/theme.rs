@@ -0,0 +1,176 @@
+/// The amount of colors a terminal can display.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "config", derive(serde::Deserialize), serde(rename_all = "kebab-case"))]
+pub enum ColorDepth {
+    /// The 16 colors of the terminal's palette, which every color terminal supports.
+    #[default]
+    Ansi16,
+    /// The 256 colors of the extended palette, as supported by terminals like `xterm-256color`.
+    Ansi256,
+    /// Any color given as red, green and blue.
+    TrueColor,
+}
+
+impl ColorDepth {
+    /// Detect the color depth of the terminal from the `COLORTERM` and `TERM` environment variables.
+    pub fn detect() -> Self {
+        Self::from_env(
+            std::env::var("COLORTERM").ok().as_deref(),
+            std::env::var("TERM").ok().as_deref(),
+        )
+    }
+
+    /// Determine the color depth from the values of the `COLORTERM` and `TERM` environment variables, if set.
+    ///
+    /// `TERM` is expected to be the name of a terminfo entry, like `xterm-256color` or `xterm-direct`.
+    pub fn from_env(colorterm: Option<&str>, term: Option<&str>) -> Self {
+        if matches!(colorterm, Some("truecolor" | "24bit")) {
+            return ColorDepth::TrueColor;
+        }
+        match term {
+            Some(term) if ["direct", "truecolor", "24bit"].iter().any(|name| term.contains(name)) => {
+                ColorDepth::TrueColor
+            }
+            Some(term) if term.contains("256color") => ColorDepth::Ansi256,
+            _ => ColorDepth::Ansi16,
+        }
+    }
+}
+
+/// A color as it can be displayed at a particular [`ColorDepth`], as returned by [`Rgb::quantize()`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum Quantized {
+    /// The index into the 16 colors of the terminal's palette, with `0` to `7` being the normal colors and `8` to `15`
+    /// their bright variants.
+    Ansi16(u8),
+    /// The index into the 256 colors of the extended palette.
+    Ansi256(u8),
+    /// The color as red, green and blue.
+    Rgb(u8, u8, u8),
+}
+
+/// A color given by its red, green and blue components, which is approximated on terminals that can't display it.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "config", derive(serde::Deserialize))]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+/// The colors of the 16 color palette as used by `xterm`, which others resemble.
+const ANSI16: [Rgb; 16] = [
+    Rgb(0, 0, 0),
+    Rgb(205, 0, 0),
+    Rgb(0, 205, 0),
+    Rgb(205, 205, 0),
+    Rgb(0, 0, 238),
+    Rgb(205, 0, 205),
+    Rgb(0, 205, 205),
+    Rgb(229, 229, 229),
+    Rgb(127, 127, 127),
+    Rgb(255, 0, 0),
+    Rgb(0, 255, 0),
+    Rgb(255, 255, 0),
+    Rgb(92, 92, 255),
+    Rgb(255, 0, 255),
+    Rgb(0, 255, 255),
+    Rgb(255, 255, 255),
+];
+
+/// The intensities of each component in the 6x6x6 color cube of the 256 color palette.
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+impl Rgb {
+    /// Return this color as it can be displayed at `depth`, which is the closest color of the palette unless it's
+    /// [true color][ColorDepth::TrueColor].
+    pub fn quantize(self, depth: ColorDepth) -> Quantized {
+        match depth {
+            ColorDepth::TrueColor => Quantized::Rgb(self.0, self.1, self.2),
+            ColorDepth::Ansi256 => Quantized::Ansi256(self.closest_ansi256()),
+            ColorDepth::Ansi16 => Quantized::Ansi16(self.closest_ansi16()),
+        }
+    }
+
+    fn distance(self, other: Rgb) -> u32 {
+        let d = |a: u8, b: u8| (a as i32 - b as i32).unsigned_abs().pow(2);
+        d(self.0, other.0) + d(self.1, other.1) + d(self.2, other.2)
+    }
+
+    fn closest_ansi16(self) -> u8 {
+        (0..ANSI16.len())
+            .min_by_key(|idx| self.distance(ANSI16[*idx]))
+            .expect("palette isn't empty") as u8
+    }
+
+    fn closest_ansi256(self) -> u8 {
+        let level = |c: u8| {
+            (0..CUBE_LEVELS.len())
+                .min_by_key(|idx| (CUBE_LEVELS[*idx] as i32 - c as i32).abs())
+                .expect("levels aren't empty") as u8
+        };
+        let (r, g, b) = (level(self.0), level(self.1), level(self.2));
+        let cube = Rgb(
+            CUBE_LEVELS[r as usize],
+            CUBE_LEVELS[g as usize],
+            CUBE_LEVELS[b as usize],
+        );
+
+        let average = (self.0 as u32 + self.1 as u32 + self.2 as u32) / 3;
+        let gray_idx = (average.saturating_sub(3) / 10).min(23) as u8;
+        let gray_level = 8 + gray_idx * 10;
+        let gray = Rgb(gray_level, gray_level, gray_level);
+
+        if self.distance(gray) < self.distance(cube) {
+            232 + gray_idx
+        } else {
+            16 + 36 * r + 6 * g + b
+        }
+    }
+}
+
+/// The colors renderers use for the different parts of the progress display, which are approximated according to the
+/// [`ColorDepth`] of the terminal.
+///
+/// The default resembles the colors of the 16 color palette used if no theme is set.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "config", derive(serde::Deserialize), serde(default))]
+pub struct Theme {
+    /// The names of tasks with progress.
+    pub name: Rgb,
+    /// The names of tasks without progress, serving as headline for their children.
+    pub headline: Rgb,
+    /// The names of tasks taking longer than expected.
+    pub overdue: Rgb,
+    /// The progress of running tasks.
+    pub progress: Rgb,
+    /// The progress of running tasks which are more than 80% done.
+    pub progress_almost_done: Rgb,
+    /// The progress of running tasks without upper bound.
+    pub progress_unbounded: Rgb,
+    /// The progress of blocked or halted tasks.
+    pub blocked: Rgb,
+    /// Informational messages.
+    pub info: Rgb,
+    /// Messages about success.
+    pub success: Rgb,
+    /// Messages about failure.
+    pub failure: Rgb,
+    /// The background of message timestamps.
+    pub timestamp_background: Rgb,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        let [_black, red, green, yellow, _blue, _magenta, cyan, white, ..] = ANSI16;
+        Theme {
+            name: cyan,
+            headline: white,
+            overdue: Rgb(255, 135, 0),
+            progress: yellow,
+            progress_almost_done: green,
+            progress_unbounded: white,
+            blocked: red,
+            info: white,
+            success: green,
+            failure: red,
+            timestamp_background: yellow,
+        }
+    }
+}
@@ -1,9 +1,13 @@
-use std::time::Duration;
+use std::{
+    collections::HashMap,
+    time::{Duration, SystemTime},
+};
 
+use humantime::format_duration;
 use tui::{
     buffer::Buffer,
     layout::Rect,
-    style::{Modifier, Style},
+    style::{Color, Modifier, Style},
     text::Span,
     widgets::{Block, Borders, Widget},
 };
@@ -11,11 +15,15 @@ use tui::{
 use crate::{
     messages::Message,
     progress::{Key, Task},
-    render::tui::{
-        draw,
-        utils::{block_width, rect},
-        InterruptDrawInfo, Line,
+    render::{
+        tui::{
+            draw,
+            utils::{block_width, draw_text_with_ellipsis_nowrap, rect, task_matches},
+            Columns, InterruptDrawInfo, Line,
+        },
+        SmoothFractions,
     },
+    time::{TimestampFormat, Timezone},
     Throughput,
 };
 
@@ -23,7 +31,10 @@ use crate::{
 pub struct State {
     pub title: String,
     pub task_offset: u16,
+    /// The amount of the newest messages scrolled past.
     pub message_offset: u16,
+    /// If true, messages are shown in chronological order, with the newest one at the bottom.
+    pub messages_oldest_first: bool,
     pub hide_messages: bool,
     pub messages_fullscreen: bool,
     pub user_provided_window_size: Option<Rect>,
@@ -34,19 +45,72 @@ pub struct State {
     pub last_tree_column_width: Option<u16>,
     pub next_tree_column_width: Option<u16>,
     pub throughput: Option<Throughput>,
+    /// If true, time isn't accounted for when computing throughput.
+    pub clock_paused: bool,
+    /// The ETA of blocked or halted tasks along with the time it was first seen, to draw a countdown until the ETA.
+    pub blocked_since: HashMap<Key, (SystemTime, SystemTime)>,
+    /// The columns to show for each task.
+    pub columns: Columns,
+    /// The time each task was first seen, to show the time elapsed since then.
+    pub first_seen: HashMap<Key, SystemTime>,
+    /// The ETA last shown for each task along with the time it was computed, to count down from it.
+    pub etas: HashMap<Key, (Duration, SystemTime)>,
+    /// The fractions of bars as shown, if bars are smoothed.
+    pub smooth_fractions: Option<SmoothFractions>,
+    pub compact_layout_width: Option<u16>,
+    /// The text to search for in tasks, if a search was started.
+    pub search: Option<String>,
+    /// If true, key presses are used to edit the `search` query.
+    pub search_input_active: bool,
+    /// If true, the id of each task is shown after its name.
+    pub show_ids: bool,
+    /// If true, a row with details about the selected task, the one at the top of the list, is shown below it.
+    pub show_details: bool,
+    pub hide_output: bool,
+    /// The amount of the latest output lines to skip, to scroll back in the output.
+    pub output_offset: u16,
+    /// The outcome of the last user action to show in the title bar until the next key press, either a success or a failure.
+    pub notice: Option<Result<String, String>>,
+    pub timestamp_format: TimestampFormat,
+    pub timezone: Timezone,
+    /// The time the dashboard was created, as reference for relative timestamps.
+    pub started_at: Option<SystemTime>,
+    /// The time of the first message we have seen, as reference for timestamps relative to it.
+    pub first_message_at: Option<SystemTime>,
+    /// The time the run started according to the progress, as reference for the elapsed time shown in the headline.
+    pub run_started_at: Option<SystemTime>,
+}
+
+impl State {
+    /// Scroll the message pane down by `lines`, or up if negative, which reveals older messages unless they are shown
+    /// oldest first.
+    pub fn scroll_messages_down(&mut self, lines: i16) {
+        let towards_older = if self.messages_oldest_first { -lines } else { lines };
+        self.message_offset = self.message_offset.saturating_add_signed(towards_older);
+    }
 }
 
+/// The width and height of the smallest window the dashboard can be drawn into without its panes overlapping.
+pub(crate) const MIN_WINDOW_SIZE: (u16, u16) = (40, 10);
+
 pub(crate) fn all(
     state: &mut State,
     interrupt_mode: InterruptDrawInfo,
     entries: &[(Key, Task)],
     messages: &[Message],
+    output: &[String],
     bound: Rect,
     buf: &mut Buffer,
 ) {
+    if bound.width < MIN_WINDOW_SIZE.0 || bound.height < MIN_WINDOW_SIZE.1 {
+        window_too_small(bound, buf);
+        return;
+    }
+    let compact = state.compact_layout_width.is_some_and(|width| bound.width <= width);
+    let information: Vec<_> = state.information.iter().cloned().chain(marker_lines(entries)).collect();
     let (bound, info_pane) = compute_info_bound(
         bound,
-        if state.hide_info { &[] } else { &state.information },
+        if state.hide_info || compact { &[] } else { &information },
         state.maximize_info,
     );
     let bold = Style::default().add_modifier(Modifier::BOLD);
@@ -55,15 +119,18 @@ pub(crate) fn all(
         .borders(Borders::ALL);
     let inner_area = window.inner(bound);
     window.render(bound, buf);
-    if bound.width < 4 || bound.height < 4 {
-        return;
-    }
 
     let border_width = 1;
+    let elapsed = state
+        .run_started_at
+        .or(state.started_at)
+        .and_then(|start| SystemTime::now().duration_since(start).ok())
+        .unwrap_or_default();
     draw::progress::headline(
         entries,
         interrupt_mode,
         state.duration_per_frame,
+        elapsed,
         buf,
         rect::offset_x(
             Rect {
@@ -75,14 +142,72 @@ pub(crate) fn all(
         ),
     );
 
+    if let Some(query) = state.search.as_ref() {
+        let num_matches = entries
+            .iter()
+            .filter(|(_, task)| !query.is_empty() && task_matches(task, query))
+            .count();
+        let text = format!(
+            " /{}{} ({} found) ",
+            query,
+            if state.search_input_active { "_" } else { "" },
+            num_matches
+        );
+        draw_text_with_ellipsis_nowrap(
+            rect::offset_x(
+                Rect {
+                    height: 1,
+                    width: bound.width.saturating_sub(border_width),
+                    ..bound
+                },
+                block_width(&state.title) + (border_width * 2) + 1,
+            ),
+            buf,
+            text,
+            Style::default().fg(Color::Black).bg(Color::Yellow),
+        );
+    }
+
+    if let Some(notice) = state.notice.as_ref().filter(|_| state.search.is_none()) {
+        let (text, bg) = match notice {
+            Ok(text) => (text, Color::Green),
+            Err(text) => (text, Color::Red),
+        };
+        draw_text_with_ellipsis_nowrap(
+            rect::offset_x(
+                Rect {
+                    height: 1,
+                    width: bound.width.saturating_sub(border_width),
+                    ..bound
+                },
+                block_width(&state.title) + (border_width * 2) + 1,
+            ),
+            buf,
+            format!(" {text} "),
+            Style::default().fg(Color::Black).bg(bg),
+        );
+    }
+
+    let (inner_area, output_pane) = compute_output_bound(output, inner_area);
     let (progress_pane, messages_pane) = compute_pane_bounds(
         if state.hide_messages { &[] } else { messages },
         inner_area,
         state.messages_fullscreen,
     );
 
-    draw::progress::pane(entries, progress_pane, buf, state);
+    if compact {
+        draw::progress::compact_pane(entries, progress_pane, buf, state);
+    } else {
+        draw::progress::pane(entries, messages, progress_pane, buf, state);
+    }
     if let Some(messages_pane) = messages_pane {
+        if state.first_message_at.is_none() {
+            state.first_message_at = messages.first().map(|message| message.time);
+        }
+        let start = state
+            .timestamp_format
+            .start(state.started_at.unwrap_or_else(SystemTime::now), state.first_message_at);
+        let format_time = |time| state.timestamp_format.format(time, state.timezone, start);
         draw::messages::pane(
             messages,
             messages_pane,
@@ -91,13 +216,87 @@ pub(crate) fn all(
                 ..rect::line_bound(bound, bound.height.saturating_sub(1) as usize)
             },
             &mut state.message_offset,
+            state.messages_oldest_first,
+            &format_time,
             buf,
         );
     }
 
+    if let Some(output_pane) = output_pane {
+        draw::output::pane(output, output_pane, &mut state.output_offset, buf);
+    }
+
     if let Some(info_pane) = info_pane {
-        draw::information::pane(&state.information, info_pane, buf);
+        draw::information::pane(&information, info_pane, buf);
+    }
+}
+
+/// Draw a single centered line stating the minimum window size into `bound`, instead of the dashboard which wouldn't fit.
+fn window_too_small(bound: Rect, buf: &mut Buffer) {
+    if bound.width == 0 || bound.height == 0 {
+        return;
+    }
+    let (min_width, min_height) = MIN_WINDOW_SIZE;
+    let text = format!("terminal too small: need {min_width}x{min_height}");
+    let width = block_width(&text).min(bound.width);
+    draw_text_with_ellipsis_nowrap(
+        Rect {
+            x: bound.x + (bound.width - width) / 2,
+            y: bound.y + bound.height / 2,
+            width,
+            height: 1,
+        },
+        buf,
+        text,
+        Style::default().add_modifier(Modifier::BOLD),
+    );
+}
+
+/// List the markers of all tasks that have some, below a title with the name of the task.
+fn marker_lines(entries: &[(Key, Task)]) -> impl Iterator<Item = Line> + '_ {
+    let now = SystemTime::now();
+    entries
+        .iter()
+        .filter(|(_, task)| !task.markers.is_empty())
+        .flat_map(move |(_, task)| {
+            let done_at = task.progress.as_ref().and_then(|p| p.done_at);
+            std::iter::once(Line::Title(task.name.clone())).chain(task.markers.iter().map(move |marker| {
+                let elapsed = now.duration_since(marker.time).unwrap_or_default();
+                let elapsed = Duration::from_secs(elapsed.as_secs());
+                Line::Text(match done_at {
+                    Some(done_at) => format!(
+                        "{}/{} {} ({} ago)",
+                        marker.step,
+                        done_at,
+                        marker.label,
+                        format_duration(elapsed)
+                    ),
+                    None => format!("{} {} ({} ago)", marker.step, marker.label, format_duration(elapsed)),
+                })
+            }))
+        })
+}
+
+/// Split the bottom of `inner` off for the `output` pane, if there is output to show.
+fn compute_output_bound(output: &[String], inner: Rect) -> (Rect, Option<Rect>) {
+    let title = 1u16;
+    let num_lines = output.len().min(u16::MAX as usize) as u16;
+    let output_height = ((inner.height as f32 * 0.3).floor() as u16).min(num_lines.saturating_add(title));
+    if output.is_empty() || output_height < 2 {
+        return (inner, None);
     }
+    let remaining_height = inner.height - output_height;
+    (
+        Rect {
+            height: remaining_height,
+            ..inner
+        },
+        Some(Rect {
+            y: inner.y + remaining_height,
+            height: output_height,
+            ..inner
+        }),
+    )
 }
 
 fn compute_pane_bounds(messages: &[Message], inner: Rect, messages_fullscreen: bool) -> (Rect, Option<Rect>) {
@@ -140,7 +339,7 @@ fn compute_info_bound(bound: Rect, info: &[Line], maximize: bool) -> (Rect, Opti
     let max_line_width = info.iter().fold(0, |state, l| {
         state.max(
             block_width(match l {
-                Line::Text(s) | Line::Title(s) => s,
+                Line::Text(s) | Line::Aligned { text: s, .. } | Line::Title(s) => s,
             }) + margin * 2,
         )
     });
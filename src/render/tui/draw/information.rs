@@ -8,7 +8,7 @@ use tui::{
 
 use crate::render::tui::{
     utils::{block_width, draw_text_with_ellipsis_nowrap, rect},
-    Line,
+    Align, Line,
 };
 
 pub fn pane(lines: &[Line], bound: Rect, buf: &mut Buffer) {
@@ -43,8 +43,8 @@ pub fn pane(lines: &[Line], bound: Rect, buf: &mut Buffer) {
                 }
                 offset += 1;
             }
-            Line::Text(text) => {
-                draw_text_with_ellipsis_nowrap(rect::offset_x(line_bound, 1), buf, text, None);
+            Line::Text(text) | Line::Aligned { text, .. } => {
+                draw_text_with_ellipsis_nowrap(aligned(rect::offset_x(line_bound, 1), info), buf, text, None);
             }
         };
         if let Line::Title(_) = next_info {
@@ -52,10 +52,27 @@ pub fn pane(lines: &[Line], bound: Rect, buf: &mut Buffer) {
         }
     }
 
-    if let Some(Line::Text(text)) = lines.last() {
+    if let Some(last @ (Line::Text(text) | Line::Aligned { text, .. })) = lines.last() {
         let line = lines.len().saturating_sub(1) + offset;
         if line < bound.height as usize {
-            draw_text_with_ellipsis_nowrap(rect::offset_x(rect::line_bound(bound, line), 1), buf, text, bold);
+            let line_bound = rect::offset_x(rect::line_bound(bound, line), 1);
+            draw_text_with_ellipsis_nowrap(aligned(line_bound, last), buf, text, bold);
         }
     }
 }
+
+/// Return the part of `bound` to draw the text of `line` into so it's aligned as configured.
+///
+/// Text which doesn't fit is left-aligned, to be truncated with an ellipsis.
+fn aligned(bound: Rect, line: &Line) -> Rect {
+    let Line::Aligned { text, align } = line else {
+        return bound;
+    };
+    let free = bound.width.saturating_sub(block_width(text));
+    let offset = match align {
+        Align::Left => 0,
+        Align::Center => free / 2,
+        Align::Right => free,
+    };
+    rect::offset_x(bound, offset)
+}
@@ -12,10 +12,17 @@ use unicode_width::UnicodeWidthStr;
 use crate::{
     messages::{Message, MessageLevel},
     render::tui::utils::{block_width, draw_text_with_ellipsis_nowrap, rect, sanitize_offset, VERTICAL_LINE},
-    time::{format_time_for_messages, DATE_TIME_HMS},
 };
 
-pub fn pane(messages: &[Message], bound: Rect, overflow_bound: Rect, offset: &mut u16, buf: &mut Buffer) {
+pub fn pane(
+    messages: &[Message],
+    bound: Rect,
+    overflow_bound: Rect,
+    offset: &mut u16,
+    oldest_first: bool,
+    format_time: &dyn Fn(SystemTime) -> String,
+    buf: &mut Buffer,
+) {
     let bold = Style::default().add_modifier(Modifier::BOLD);
     let block = Block::default()
         .title(Span::styled("Messages", bold))
@@ -27,31 +34,39 @@ pub fn pane(messages: &[Message], bound: Rect, overflow_bound: Rect, offset: &mu
 
     let bound = inner_bound;
     *offset = sanitize_offset(*offset, messages.len(), bound.height);
-    let max_origin_width = messages
+    let mut visible: Vec<_> = messages
         .iter()
         .rev()
         .skip(*offset as usize)
         .take(bound.height as usize)
-        .fold(0, |state, message| state.max(block_width(&message.origin)));
+        .collect();
+    if oldest_first {
+        visible.reverse();
+    }
+    let max_origin_width = visible
+        .iter()
+        .fold(0, |state, message| state.max(block_width(&message.origin.name)));
+    let times: Vec<_> = visible.iter().map(|message| format_time(message.time)).collect();
+    let max_time_width = times.iter().fold(0, |state, time| state.max(block_width(time)));
     for (
         line,
-        Message {
+        (
             time,
-            message,
-            level,
-            origin,
-        },
-    ) in messages
-        .iter()
-        .rev()
-        .skip(*offset as usize)
-        .take(bound.height as usize)
-        .enumerate()
+            Message {
+                message,
+                level,
+                origin,
+                count,
+                ..
+            },
+        ),
+    ) in times.iter().zip(visible).enumerate()
     {
         let line_bound = rect::line_bound(bound, line);
-        let (time_bound, level_bound, origin_bound, message_bound) = compute_bounds(line_bound, max_origin_width);
+        let (time_bound, level_bound, origin_bound, message_bound) =
+            compute_bounds(line_bound, max_time_width, max_origin_width);
         if let Some(time_bound) = time_bound {
-            draw_text_with_ellipsis_nowrap(time_bound, buf, format_time_column(time), None);
+            draw_text_with_ellipsis_nowrap(time_bound, buf, format!("{time}{VERTICAL_LINE}"), None);
         }
         if let Some(level_bound) = level_bound {
             draw_text_with_ellipsis_nowrap(
@@ -63,10 +78,19 @@ pub fn pane(messages: &[Message], bound: Rect, overflow_bound: Rect, offset: &mu
             draw_text_with_ellipsis_nowrap(rect::offset_x(level_bound, LEVEL_TEXT_WIDTH), buf, VERTICAL_LINE, None);
         }
         if let Some(origin_bound) = origin_bound {
-            draw_text_with_ellipsis_nowrap(origin_bound, buf, origin, None);
+            draw_text_with_ellipsis_nowrap(origin_bound, buf, &origin.name, None);
             draw_text_with_ellipsis_nowrap(rect::offset_x(origin_bound, max_origin_width), buf, "→", None);
         }
-        draw_text_with_ellipsis_nowrap(message_bound, buf, message, None);
+        let message_width = draw_text_with_ellipsis_nowrap(message_bound, buf, message, None);
+        if *count > 1 {
+            let dim = Style::default().add_modifier(Modifier::DIM);
+            draw_text_with_ellipsis_nowrap(
+                rect::offset_x(message_bound, message_width),
+                buf,
+                format!(" ×{count}"),
+                dim,
+            );
+        }
     }
 
     if (bound.height as usize) < messages.len().saturating_sub(*offset as usize)
@@ -76,13 +100,18 @@ pub fn pane(messages: &[Message], bound: Rect, overflow_bound: Rect, offset: &mu
             .len()
             .saturating_sub(bound.height.saturating_add(*offset) as usize);
         let messages_skipped = (*offset).min(messages.len() as u16);
-        draw_text_with_ellipsis_nowrap(
-            rect::offset_x(overflow_bound, 1),
-            buf,
-            format!("… {} skipped and {} more", messages_skipped, messages_below),
-            bold,
-        );
-        let help_text = " ⇊ = D|↓ = J|⇈ = U|↑ = K ┘";
+        let (overflow_text, help_text) = if oldest_first {
+            (
+                format!("… {} more and {} skipped", messages_below, messages_skipped),
+                " ⇊ = D|↓ = J|⇈ = U|↑ = K|↥ = r ┘",
+            )
+        } else {
+            (
+                format!("… {} skipped and {} more", messages_skipped, messages_below),
+                " ⇊ = D|↓ = J|⇈ = U|↑ = K|↧ = r ┘",
+            )
+        };
+        draw_text_with_ellipsis_nowrap(rect::offset_x(overflow_bound, 1), buf, overflow_text, bold);
         draw_text_with_ellipsis_nowrap(
             rect::snap_to_right(overflow_bound, block_width(help_text)),
             buf,
@@ -114,16 +143,16 @@ fn level_to_style(level: MessageLevel) -> Style {
         })
 }
 
-fn format_time_column(time: &SystemTime) -> String {
-    format!("{}{}", format_time_for_messages(*time), VERTICAL_LINE)
-}
-
-fn compute_bounds(line: Rect, max_origin_width: u16) -> (Option<Rect>, Option<Rect>, Option<Rect>, Rect) {
+fn compute_bounds(
+    line: Rect,
+    max_time_width: u16,
+    max_origin_width: u16,
+) -> (Option<Rect>, Option<Rect>, Option<Rect>, Rect) {
     let vertical_line_width = VERTICAL_LINE.width() as u16;
     let mythical_offset_we_should_not_need = 1;
 
     let time_bound = Rect {
-        width: DATE_TIME_HMS as u16 + vertical_line_width,
+        width: max_time_width + vertical_line_width,
         ..line
     };
 
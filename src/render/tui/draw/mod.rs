@@ -1,6 +1,7 @@
 mod all;
 mod information;
 mod messages;
+mod output;
 mod progress;
 
 pub(crate) use all::{all, State};
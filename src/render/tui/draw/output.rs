@@ -0,0 +1,40 @@
+use tui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::Span,
+    widgets::{Block, Borders, Widget},
+};
+
+use crate::render::tui::utils::{block_width, draw_text_with_ellipsis_nowrap, rect};
+
+/// Draw the latest `lines` into `bound`, skipping the `offset` latest ones to scroll back.
+pub fn pane(lines: &[String], bound: Rect, offset: &mut u16, buf: &mut Buffer) {
+    let bold = Style::default().add_modifier(Modifier::BOLD);
+    let block = Block::default()
+        .title(Span::styled("Output", bold))
+        .borders(Borders::TOP);
+    let inner_bound = block.inner(bound);
+    block.render(bound, buf);
+
+    let max_offset = lines.len().saturating_sub(inner_bound.height as usize);
+    *offset = (*offset).min(max_offset.min(u16::MAX as usize) as u16);
+    let end = lines.len() - *offset as usize;
+    let start = end.saturating_sub(inner_bound.height as usize);
+
+    let help_text = if *offset == 0 {
+        " ⨯ = o|↑ = ( ".to_string()
+    } else {
+        format!(" {} newer | ⨯ = o|↑ = (|↓ = ) ", *offset)
+    };
+    draw_text_with_ellipsis_nowrap(
+        rect::snap_to_right(bound, block_width(&help_text)),
+        buf,
+        help_text,
+        bold,
+    );
+
+    for (line, text) in lines[start..end].iter().enumerate() {
+        draw_text_with_ellipsis_nowrap(rect::line_bound(inner_bound, line), buf, text, None);
+    }
+}
@@ -1,7 +1,8 @@
 use std::{
+    collections::{HashMap, HashSet},
     fmt,
     sync::atomic::Ordering,
-    time::{Duration, SystemTime},
+    time::{Duration, Instant, SystemTime},
 };
 
 use humantime::format_duration;
@@ -13,45 +14,100 @@ use tui::{
 use tui_react::fill_background;
 
 use crate::{
+    messages::Message,
     progress::{self, Key, Step, Task, Value},
-    render::tui::{
-        draw::State,
-        utils::{
-            block_width, draw_text_nowrap_fn, draw_text_with_ellipsis_nowrap, rect, sanitize_offset,
-            GraphemeCountWriter, VERTICAL_LINE,
+    render::{
+        status_glyph,
+        tui::{
+            draw::State,
+            utils::{
+                block_width, draw_text_nowrap_fn, draw_text_with_ellipsis_nowrap, format_id, rect, sanitize_offset,
+                task_matches, GraphemeCountWriter, VERTICAL_LINE,
+            },
+            InterruptDrawInfo,
         },
-        InterruptDrawInfo,
+        SmoothFractions,
     },
     time::format_now_datetime_seconds,
-    unit, Throughput,
+    unit,
 };
 
 const MIN_TREE_WIDTH: u16 = 20;
+/// The width of the ETA and elapsed time columns, which fit durations like `~99h59m` with some spacing.
+const TIME_COLUMN_WIDTH: u16 = 9;
+/// The ETA and elapsed time columns are hidden if the progress column is narrower than this.
+const MIN_PROGRESS_WIDTH_FOR_TIME_COLUMNS: u16 = 40;
 
-pub fn pane(entries: &[(Key, progress::Task)], mut bound: Rect, buf: &mut Buffer, state: &mut State) {
+pub fn pane(
+    entries: &[(Key, progress::Task)],
+    messages: &[Message],
+    mut bound: Rect,
+    buf: &mut Buffer,
+    state: &mut State,
+) {
     state.task_offset = sanitize_offset(state.task_offset, entries.len(), bound.height);
-    let needs_overflow_line =
-        if entries.len() > bound.height as usize || (state.task_offset).min(entries.len() as u16) > 0 {
-            bound.height = bound.height.saturating_sub(1);
-            true
-        } else {
-            false
-        };
+    let detail_rows = u16::from(state.show_details && !entries.is_empty() && bound.height > 3);
+    let needs_overflow_line = if entries.len() + detail_rows as usize > bound.height as usize
+        || (state.task_offset).min(entries.len() as u16) > 0
+    {
+        bound.height = bound.height.saturating_sub(1);
+        true
+    } else {
+        false
+    };
     state.task_offset = sanitize_offset(state.task_offset, entries.len(), bound.height);
 
     if entries.is_empty() {
         return;
     }
 
-    let initial_column_width = bound.width / 3;
-    let desired_max_tree_draw_width = *state.next_tree_column_width.as_ref().unwrap_or(&initial_column_width);
+    // The tasks are drawn in segments of (first line, amount of lines, task offset), leaving room for the detail row
+    // below the selected task if needed.
+    let segments = if detail_rows == 0 {
+        vec![(0, bound.height, state.task_offset)]
+    } else {
+        vec![
+            (0, 1, state.task_offset),
+            (2, bound.height - 2, state.task_offset.saturating_add(1)),
+        ]
+    };
+    let segment_bound = |bound: Rect, first_line: u16, height: u16| Rect {
+        y: bound.y + first_line,
+        height,
+        ..bound
+    };
+
+    let columns = state.columns;
+    let initial_column_width = (u32::from(bound.width) * u32::from(columns.tree_width_percent.min(100)) / 100) as u16;
+    let desired_max_tree_draw_width = if columns.tree {
+        *state.next_tree_column_width.as_ref().unwrap_or(&initial_column_width)
+    } else {
+        0
+    };
     {
-        if initial_column_width >= MIN_TREE_WIDTH {
+        if !columns.tree {
+            // Start over with the initial width once the tree is shown again.
+            state.last_tree_column_width = None;
+        } else if initial_column_width >= MIN_TREE_WIDTH {
             let tree_bound = Rect {
                 width: desired_max_tree_draw_width,
                 ..bound
             };
-            let computed = draw_tree(entries, buf, tree_bound, state.task_offset);
+            let computed = segments
+                .iter()
+                .map(|&(first_line, height, offset)| {
+                    draw_tree(
+                        entries,
+                        buf,
+                        segment_bound(tree_bound, first_line, height),
+                        offset,
+                        search_query(state),
+                        state.show_ids,
+                        columns.status_glyph,
+                    )
+                })
+                .max()
+                .unwrap_or_default();
             state.last_tree_column_width = Some(computed);
         } else {
             state.last_tree_column_width = Some(0);
@@ -60,23 +116,54 @@ pub fn pane(entries: &[(Key, progress::Task)], mut bound: Rect, buf: &mut Buffer
 
     {
         if let Some(tp) = state.throughput.as_mut() {
-            tp.update_elapsed();
+            if state.clock_paused {
+                tp.skip_elapsed();
+            } else {
+                tp.update_elapsed();
+            }
         }
+        update_blocked_since(entries, &mut state.blocked_since);
+        update_first_seen(entries, &mut state.first_seen);
 
         let progress_area = rect::offset_x(bound, desired_max_tree_draw_width);
-        draw_progress(
-            entries,
-            buf,
-            progress_area,
-            state.task_offset,
-            state.throughput.as_mut(),
-        );
+        let show_time_columns = progress_area.width >= MIN_PROGRESS_WIDTH_FOR_TIME_COLUMNS;
+        if columns.eta && show_time_columns {
+            state.etas.retain(|key, _| state.first_seen.contains_key(key));
+        } else {
+            state.etas.clear();
+        }
+        for &(first_line, height, offset) in &segments {
+            draw_progress(
+                entries,
+                buf,
+                segment_bound(progress_area, first_line, height),
+                offset,
+                state,
+                show_time_columns,
+            );
+        }
+        if let Some(smooth_fractions) = state.smooth_fractions.as_mut() {
+            smooth_fractions.reconcile(entries);
+        }
 
         if let Some(tp) = state.throughput.as_mut() {
             tp.reconcile(entries);
         }
     }
 
+    if detail_rows != 0 {
+        if let Some((key, task)) = entries.get(state.task_offset as usize) {
+            let line_bound = rect::line_bound(bound, 1);
+            let indent = block_width(&level_prefix(entries, state.task_offset as usize)) + 1;
+            draw_text_with_ellipsis_nowrap(
+                rect::offset_x(line_bound, indent),
+                buf,
+                detail_text(key, task, messages),
+                Style::default().add_modifier(Modifier::DIM),
+            );
+        }
+    }
+
     if needs_overflow_line {
         let overflow_rect = Rect {
             y: bound.height + 1,
@@ -88,32 +175,224 @@ pub fn pane(entries: &[(Key, progress::Task)], mut bound: Rect, buf: &mut Buffer
             buf,
             overflow_rect,
             desired_max_tree_draw_width,
-            bound.height,
+            bound.height - detail_rows,
             state.task_offset,
         );
     }
 }
 
+/// Return the text of the detail row shown below the selected `task`, with its progress in full, its note, the time
+/// spent in its measured scopes and the last message it sent.
+fn detail_text(key: &Key, task: &Task, messages: &[Message]) -> String {
+    use std::fmt::Write;
+    let mut out = String::new();
+    if let Some(progress) = task.progress.as_ref() {
+        let step = progress.step.load(Ordering::SeqCst);
+        let rate = progress.rate();
+        match progress.unit.as_ref() {
+            Some(unit) => unit.write_to(&mut out, step, progress.done_at, None),
+            None => match progress.done_at {
+                Some(done_at) => write!(out, "{step}/{done_at}"),
+                None => write!(out, "{step}"),
+            },
+        }
+        .ok();
+        if let Some(fraction) = progress.fraction() {
+            write!(out, " {:.0}%", (fraction * 100.0).floor()).ok();
+        }
+        if rate > 0.0 {
+            out.push(' ');
+            let throughput = unit::display::Throughput::new(rate as Step, Duration::from_secs(1));
+            match progress.unit.as_ref() {
+                Some(unit) => unit.as_display_value().display_throughput(&mut out, &throughput),
+                None => write!(out, "{rate:.0}/s"),
+            }
+            .ok();
+            if let Some(remaining) = progress.done_at.map(|done_at| done_at.saturating_sub(step)) {
+                let eta = Duration::from_secs((remaining as f64 / rate) as u64);
+                write!(out, " eta {}", format_duration(eta)).ok();
+            }
+        }
+    }
+    if let Some(note) = task.note.as_deref() {
+        if !out.is_empty() {
+            out.push_str(" · ");
+        }
+        out.push_str(note);
+    }
+    for timing in &task.timings {
+        if !out.is_empty() {
+            out.push_str(" · ");
+        }
+        write!(out, "{} {:.2?}", timing.label, timing.total).ok();
+        if timing.count > 1 {
+            write!(out, " ({}×)", timing.count).ok();
+        }
+    }
+    if let Some(message) = messages.iter().rev().find(|message| message.origin.key == *key) {
+        if !out.is_empty() {
+            out.push_str(" · ");
+        }
+        write!(out, "→ {}", message.message).ok();
+    }
+    out
+}
+
+/// Draw each task on a single line, with its name followed by its progress, for use in narrow windows.
+pub fn compact_pane(entries: &[(Key, progress::Task)], mut bound: Rect, buf: &mut Buffer, state: &mut State) {
+    state.task_offset = sanitize_offset(state.task_offset, entries.len(), bound.height);
+    let needs_overflow_line =
+        if entries.len() > bound.height as usize || (state.task_offset).min(entries.len() as u16) > 0 {
+            bound.height = bound.height.saturating_sub(1);
+            true
+        } else {
+            false
+        };
+    state.task_offset = sanitize_offset(state.task_offset, entries.len(), bound.height);
+    state.last_tree_column_width = Some(0);
+
+    if entries.is_empty() {
+        return;
+    }
+
+    let bold = Style::default().add_modifier(Modifier::BOLD);
+    // Borrow the query alone, as smoothing bars changes the state.
+    let search = state.search.as_deref().filter(|query| !query.is_empty());
+    let now = SystemTime::now();
+    let frame_time = Instant::now();
+    for (line, (entry_index, (key, task @ Task { progress, name, .. }))) in entries
+        .iter()
+        .enumerate()
+        .skip(state.task_offset as usize)
+        .take(bound.height as usize)
+        .enumerate()
+    {
+        let line_bound = rect::line_bound(bound, line);
+        let overdue = overdue_suffix(task, now);
+        let label = format!(
+            "{} {}{}{}{}{} ",
+            level_prefix(entries, entry_index),
+            status_prefix(task, state.columns.status_glyph),
+            name,
+            id_suffix(task.id, state.show_ids),
+            attempt_suffix(task),
+            overdue.as_deref().unwrap_or_default()
+        );
+        let is_match = search.is_some_and(|query| task_matches(task, query));
+        let progress = match progress {
+            Some(progress) => progress,
+            None => {
+                let style = overdue_style(overdue.is_some(), Some(bold)).unwrap_or(bold);
+                let style = if is_match {
+                    search_match_style(Some(style))
+                } else {
+                    style
+                };
+                let label_width = draw_text_with_ellipsis_nowrap(line_bound, buf, label, style);
+                draw_failed_descendant_marker(rect::offset_x(line_bound, label_width), buf, task);
+                continue;
+            }
+        };
+        let style = overdue_style(overdue.is_some(), None);
+        let style = if is_match {
+            Some(search_match_style(style))
+        } else {
+            style
+        };
+        let label_width = draw_text_with_ellipsis_nowrap(line_bound, buf, label, style);
+        let label_width =
+            label_width + draw_failed_descendant_marker(rect::offset_x(line_bound, label_width), buf, task);
+        let progress_rect = rect::offset_x(line_bound, label_width);
+        let step = progress.step.load(Ordering::SeqCst);
+        match progress.fraction() {
+            Some(fraction) => {
+                let percent = format!("{:>3}% ", (fraction.min(1.0) * 100.0).floor() as usize);
+                let percent_width = draw_text_with_ellipsis_nowrap(progress_rect, buf, percent, None);
+                let bar_rect = rect::offset_x(progress_rect, percent_width);
+                if bar_rect.width > 2 {
+                    draw_text_with_ellipsis_nowrap(bar_rect, buf, "[", None);
+                    draw_text_with_ellipsis_nowrap(rect::snap_to_right(bar_rect, 1), buf, "]", None);
+                    draw_progress_bar_fn(
+                        buf,
+                        Rect {
+                            x: bar_rect.x + 1,
+                            width: bar_rect.width - 2,
+                            ..bar_rect
+                        },
+                        bar_fraction(state.smooth_fractions.as_mut(), key, fraction, frame_time),
+                        |fraction| progress_color(&progress.state, fraction),
+                    );
+                }
+            }
+            None => {
+                let step_width = draw_text_with_ellipsis_nowrap(progress_rect, buf, format!("{} ", step), None);
+                draw_spinner(
+                    buf,
+                    rect::offset_x(progress_rect, step_width),
+                    progress.animation_step(),
+                    line,
+                    match &progress.state {
+                        progress::State::Running => Color::White,
+                        state => progress_color(state, 0.0),
+                    },
+                );
+            }
+        }
+    }
+
+    if needs_overflow_line {
+        let overflow_rect = Rect {
+            y: bound.y + bound.height,
+            height: 1,
+            ..bound
+        };
+        draw_overflow(entries, buf, overflow_rect, 0, bound.height, state.task_offset);
+    }
+}
+
+fn progress_color(state: &progress::State, fraction: f32) -> Color {
+    match state {
+        progress::State::Blocked(_, _) => Color::Red,
+        progress::State::Halted(_, _) => Color::LightRed,
+        progress::State::Finished(true) => Color::Green,
+        progress::State::Finished(false) => Color::Red,
+        progress::State::Running => {
+            if fraction >= 0.8 {
+                Color::Green
+            } else {
+                Color::Yellow
+            }
+        }
+    }
+}
+
 pub(crate) fn headline(
     entries: &[(Key, Task)],
     interrupt_mode: InterruptDrawInfo,
     duration_per_frame: Duration,
+    elapsed: Duration,
     buf: &mut Buffer,
     bound: Rect,
 ) {
-    let (num_running_tasks, num_blocked_tasks, num_groups) = entries.iter().fold(
-        (0, 0, 0),
-        |(mut running, mut blocked, mut groups), (_key, Task { progress, .. })| {
-            match progress.as_ref().map(|p| p.state) {
+    let (num_running_tasks, num_blocked_tasks, num_groups, num_finished_tasks) = entries.iter().fold(
+        (0, 0, 0, 0),
+        |(mut running, mut blocked, mut groups, mut finished), (_key, Task { progress, .. })| {
+            match progress.as_ref().map(|p| &p.state) {
                 Some(progress::State::Running) => running += 1,
                 Some(progress::State::Blocked(_, _)) | Some(progress::State::Halted(_, _)) => blocked += 1,
+                Some(progress::State::Finished(_)) => finished += 1,
                 None => groups += 1,
             }
-            (running, blocked, groups)
+            (running, blocked, groups, finished)
         },
     );
+    let finished = if num_finished_tasks > 0 {
+        format!(" + {num_finished_tasks:3} finished")
+    } else {
+        String::new()
+    };
     let text = format!(
-        " {} {} {:3} running + {:3} blocked + {:3} groups = {} ",
+        " {} {} ⏱ {} │ {:3} running + {:3} blocked + {:3} groups{} = {} ",
         match interrupt_mode {
             InterruptDrawInfo::Instantly => "'q' or CTRL+c to quit",
             InterruptDrawInfo::Deferred(interrupt_requested) => {
@@ -133,9 +412,11 @@ pub(crate) fn headline(
         } else {
             "".into()
         },
+        format_duration(Duration::from_secs(elapsed.as_secs())),
         num_running_tasks,
         num_blocked_tasks,
         num_groups,
+        finished,
         entries.len()
     );
 
@@ -149,11 +430,7 @@ impl<'a> fmt::Display for ProgressFormat<'a> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self.0 {
             Some(p) => match p.unit.as_ref() {
-                Some(unit) => write!(
-                    f,
-                    "{}",
-                    unit.display(p.step.load(Ordering::SeqCst), p.done_at, self.2.clone())
-                ),
+                Some(unit) => unit.write_to(f, p.step.load(Ordering::SeqCst), p.done_at, self.2.clone()),
                 None => match p.done_at {
                     Some(done_at) => write!(f, "{}/{}", p.step.load(Ordering::SeqCst), done_at),
                     None => write!(f, "{}", p.step.load(Ordering::SeqCst)),
@@ -175,13 +452,24 @@ fn has_child(entries: &[(Key, Task)], index: usize) -> bool {
         .unwrap_or(false)
 }
 
+/// Draw the progress of `entries` along with the columns configured in `state`, with the ETA and elapsed time only
+/// if `show_time_columns` is true.
 pub fn draw_progress(
     entries: &[(Key, Task)],
     buf: &mut Buffer,
     bound: Rect,
     offset: u16,
-    mut throughput: Option<&mut Throughput>,
+    state: &mut State,
+    show_time_columns: bool,
 ) {
+    let columns = state.columns;
+    let mut throughput = state.throughput.as_mut();
+    let blocked_since = &state.blocked_since;
+    let mut etas = (columns.eta && show_time_columns).then_some(&mut state.etas);
+    let first_seen = (columns.elapsed && show_time_columns).then_some(&state.first_seen);
+    let mut smooth_fractions = state.smooth_fractions.as_mut();
+    let now = SystemTime::now();
+    let frame_time = Instant::now();
     let title_spacing = 2u16 + 1; // 2 on the left, 1 on the right
     let max_progress_label_width = entries
         .iter()
@@ -198,6 +486,9 @@ pub fn draw_progress(
             None => state,
         });
 
+    use std::fmt::Write;
+    // Reuse the allocation of the progress text of the previous line.
+    let mut progress_text = String::new();
     for (
         line,
         (
@@ -207,7 +498,9 @@ pub fn draw_progress(
                 Task {
                     progress,
                     name: title,
-                    id: _,
+                    note,
+                    markers,
+                    ..
                 },
             ),
         ),
@@ -220,44 +513,105 @@ pub fn draw_progress(
     {
         let throughput = throughput
             .as_mut()
-            .and_then(|tp| tp.update_and_get(key, progress.as_ref()));
+            .and_then(|tp| tp.update_and_get(key, progress.as_ref()))
+            .filter(|_| columns.rate);
         let line_bound = rect::line_bound(bound, line);
-        let progress_text = format!(
-            " {progress}",
-            progress = ProgressFormat(
-                progress,
-                if has_child(entries, entry_index) {
-                    bound.width.saturating_sub(title_spacing)
-                } else {
-                    0
-                },
-                throughput
+        progress_text.clear();
+        match progress.as_ref() {
+            Some(progress) if !columns.value => {
+                if let Some((unit, throughput)) = progress.unit.as_ref().zip(throughput) {
+                    progress_text.push(' ');
+                    unit.as_display_value()
+                        .display_throughput(&mut progress_text, &throughput)
+                        .expect("writing to a string never fails");
+                }
+            }
+            _ => write!(
+                progress_text,
+                " {progress}",
+                progress = ProgressFormat(
+                    progress,
+                    if has_child(entries, entry_index) {
+                        bound.width.saturating_sub(title_spacing)
+                    } else {
+                        0
+                    },
+                    throughput
+                )
             )
-        );
+            .expect("writing to a string never fails"),
+        }
 
         draw_text_with_ellipsis_nowrap(line_bound, buf, VERTICAL_LINE, None);
 
         let tree_prefix = level_prefix(entries, entry_index);
+        draw_text_with_ellipsis_nowrap(line_bound, buf, tree_prefix.as_str(), None);
+        let dim = Style::default().add_modifier(Modifier::DIM);
+        let line_bound = match first_seen {
+            Some(first_seen) => {
+                let elapsed_rect = rect::snap_to_right(line_bound, TIME_COLUMN_WIDTH);
+                if let Some(since) = first_seen.get(key).filter(|_| progress.is_some()) {
+                    let elapsed = format_short_duration(now.duration_since(*since).unwrap_or_default());
+                    draw_text_with_ellipsis_nowrap(
+                        rect::snap_to_right(elapsed_rect, block_width(&elapsed) + 1),
+                        buf,
+                        elapsed,
+                        dim,
+                    );
+                }
+                Rect {
+                    width: line_bound.width.saturating_sub(TIME_COLUMN_WIDTH),
+                    ..line_bound
+                }
+            }
+            None => line_bound,
+        };
+        let line_bound = match etas.as_deref_mut() {
+            Some(etas) => {
+                let eta_rect = rect::snap_to_right(line_bound, TIME_COLUMN_WIDTH);
+                if let Some(eta) = progress.as_ref().and_then(task_eta) {
+                    let eta = format!("~{}", format_short_duration(stable_eta(etas, key, eta, now)));
+                    draw_text_with_ellipsis_nowrap(rect::snap_to_right(eta_rect, block_width(&eta) + 1), buf, eta, dim);
+                }
+                Rect {
+                    width: line_bound.width.saturating_sub(TIME_COLUMN_WIDTH),
+                    ..line_bound
+                }
+            }
+            None => line_bound,
+        };
         let progress_rect = rect::offset_x(line_bound, block_width(&tree_prefix));
-        draw_text_with_ellipsis_nowrap(line_bound, buf, tree_prefix, None);
-        match progress
-            .as_ref()
-            .map(|p| (p.fraction(), p.state, p.step.load(Ordering::SeqCst)))
-        {
+        let (progress_rect, note) = match note.as_deref().filter(|_| progress.is_some()) {
+            Some(note) => {
+                let note_width = block_width(note) + 1;
+                (
+                    Rect {
+                        width: progress_rect.width.saturating_sub(note_width),
+                        ..progress_rect
+                    },
+                    Some((rect::snap_to_right(line_bound, note_width), note)),
+                )
+            }
+            None => (progress_rect, None),
+        };
+        match progress.as_ref().map(|p| {
+            (
+                remaining_block_fraction(&p.state, blocked_since.get(key)).or_else(|| {
+                    p.fraction()
+                        .map(|fraction| bar_fraction(smooth_fractions.as_deref_mut(), key, fraction, frame_time))
+                }),
+                &p.state,
+                p.animation_step(),
+            )
+        }) {
+            Some((Some(_), state, _step)) if !columns.bar => {
+                add_block_eta(state, &mut progress_text);
+                draw_text_with_ellipsis_nowrap(progress_rect, buf, &progress_text, None);
+            }
             Some((Some(fraction), state, _step)) => {
-                let mut progress_text = progress_text;
                 add_block_eta(state, &mut progress_text);
-                let (bound, style) = draw_progress_bar_fn(buf, progress_rect, fraction, |fraction| match state {
-                    progress::State::Blocked(_, _) => Color::Red,
-                    progress::State::Halted(_, _) => Color::LightRed,
-                    progress::State::Running => {
-                        if fraction >= 0.8 {
-                            Color::Green
-                        } else {
-                            Color::Yellow
-                        }
-                    }
-                });
+                let (bound, style) =
+                    draw_progress_bar_fn(buf, progress_rect, fraction, |fraction| progress_color(state, fraction));
                 let style_fn = move |_t: &str, x: u16, _y: u16| {
                     if x < bound.right() {
                         style
@@ -265,35 +619,147 @@ pub fn draw_progress(
                         Style::default()
                     }
                 };
-                draw_text_nowrap_fn(progress_rect, buf, progress_text, style_fn);
+                draw_text_nowrap_fn(progress_rect, buf, &progress_text, style_fn);
+                if let Some(done_at) = progress
+                    .as_ref()
+                    .filter(|p| block_eta(&p.state).is_none())
+                    .and_then(|p| p.done_at)
+                {
+                    draw_marker_ticks(buf, progress_rect, markers, done_at);
+                }
             }
             Some((None, state, step)) => {
-                let mut progress_text = progress_text;
                 add_block_eta(state, &mut progress_text);
-                draw_text_with_ellipsis_nowrap(progress_rect, buf, progress_text, None);
-                let bar_rect = rect::offset_x(line_bound, max_progress_label_width as u16);
-                draw_spinner(
-                    buf,
-                    bar_rect,
-                    step,
-                    line,
-                    match state {
-                        progress::State::Blocked(_, _) => Color::Red,
-                        progress::State::Halted(_, _) => Color::LightRed,
-                        progress::State::Running => Color::White,
-                    },
-                );
+                draw_text_with_ellipsis_nowrap(progress_rect, buf, &progress_text, None);
+                if columns.bar {
+                    let bar_rect = rect::intersect(
+                        rect::offset_x(line_bound, max_progress_label_width as u16),
+                        progress_rect,
+                    );
+                    draw_spinner(
+                        buf,
+                        bar_rect,
+                        step,
+                        line,
+                        match state {
+                            progress::State::Blocked(_, _) => Color::Red,
+                            progress::State::Halted(_, _) => Color::LightRed,
+                            progress::State::Finished(true) => Color::Green,
+                            progress::State::Finished(false) => Color::Red,
+                            progress::State::Running => Color::White,
+                        },
+                    );
+                }
             }
             None => {
                 let bold = Style::default().add_modifier(Modifier::BOLD);
-                draw_text_nowrap_fn(progress_rect, buf, progress_text, |_, _, _| Style::default());
+                draw_text_nowrap_fn(progress_rect, buf, &progress_text, |_, _, _| Style::default());
                 draw_text_with_ellipsis_nowrap(progress_rect, buf, format!(" {} ", title), bold);
             }
         }
+        if let Some((note_rect, note)) = note {
+            draw_text_with_ellipsis_nowrap(rect::offset_x(note_rect, 1), buf, note, dim);
+        }
+    }
+}
+
+/// Return the fraction to draw the bar of the task at `key` with, which lags behind the actual `fraction` if bars are smoothed.
+fn bar_fraction(smooth_fractions: Option<&mut SmoothFractions>, key: &Key, fraction: f32, now: Instant) -> f32 {
+    smooth_fractions.map_or(fraction, |smooth_fractions| {
+        smooth_fractions.fraction(key, fraction, now)
+    })
+}
+
+/// Return the estimated time until the bounded and running `progress` is done, if its rate is known.
+fn task_eta(progress: &Value) -> Option<Duration> {
+    let rate = progress.rate();
+    if progress.state != progress::State::Running || rate <= 0.0 {
+        return None;
+    }
+    let remaining = progress.done_at?.checked_sub(progress.step.load(Ordering::SeqCst))?;
+    Some(Duration::from_secs_f64(remaining as f64 / rate))
+}
+
+/// Return the ETA to show for the task at `key`, which counts down from the one shown before unless `estimate` differs
+/// from it by more than a tenth, to keep it from changing with every frame.
+fn stable_eta(
+    etas: &mut HashMap<Key, (Duration, SystemTime)>,
+    key: &Key,
+    estimate: Duration,
+    now: SystemTime,
+) -> Duration {
+    if let Some(&(shown, shown_at)) = etas.get(key) {
+        let countdown = shown.saturating_sub(now.duration_since(shown_at).unwrap_or_default());
+        let difference = countdown.max(estimate) - countdown.min(estimate);
+        if difference <= (countdown / 10).max(Duration::from_secs(1)) {
+            return countdown;
+        }
+    }
+    etas.insert(key.clone(), (estimate, now));
+    estimate
+}
+
+/// Format `duration` compactly with its two most significant units, like `2m10s`.
+fn format_short_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    let (hours, minutes, secs) = (secs / 3600, secs / 60 % 60, secs % 60);
+    if hours > 0 {
+        format!("{hours}h{minutes:02}m")
+    } else if minutes > 0 {
+        format!("{minutes}m{secs:02}s")
+    } else {
+        format!("{secs}s")
+    }
+}
+
+/// Return the ETA of `state` if it's blocked or halted until a given time.
+fn block_eta(state: &progress::State) -> Option<SystemTime> {
+    match state {
+        progress::State::Blocked(_, eta) | progress::State::Halted(_, eta) => *eta,
+        progress::State::Running | progress::State::Finished(_) => None,
+    }
+}
+
+/// Remember when the ETA of blocked or halted tasks was first seen, and forget about all other tasks.
+fn update_blocked_since(entries: &[(Key, Task)], blocked_since: &mut HashMap<Key, (SystemTime, SystemTime)>) {
+    let etas: HashMap<_, _> = entries
+        .iter()
+        .filter_map(|(key, task)| {
+            task.progress
+                .as_ref()
+                .and_then(|p| block_eta(&p.state))
+                .map(|eta| (key.clone(), eta))
+        })
+        .collect();
+    blocked_since.retain(|key, (eta, _)| etas.get(key) == Some(eta));
+    let now = SystemTime::now();
+    for (key, eta) in etas {
+        blocked_since.entry(key).or_insert((eta, now));
     }
 }
 
-fn add_block_eta(state: progress::State, progress_text: &mut String) {
+/// Remember when each task was first seen, to show the time elapsed since then, and forget about tasks that are gone.
+fn update_first_seen(entries: &[(Key, Task)], first_seen: &mut HashMap<Key, SystemTime>) {
+    let keys: HashSet<_> = entries.iter().map(|(key, _)| key).collect();
+    first_seen.retain(|key, _| keys.contains(key));
+    let now = SystemTime::now();
+    for (key, _) in entries {
+        first_seen.entry(key.clone()).or_insert(now);
+    }
+}
+
+/// Return the fraction of time left until the ETA of a blocked or halted task, as seen `since` it was first seen.
+///
+/// It's `None` if the task isn't blocked until a given time.
+fn remaining_block_fraction(state: &progress::State, since: Option<&(SystemTime, SystemTime)>) -> Option<f32> {
+    let eta = block_eta(state)?;
+    let (_, first_seen) = since?;
+    let total = eta.duration_since(*first_seen).ok().filter(|total| !total.is_zero())?;
+    let remaining = eta.duration_since(SystemTime::now()).unwrap_or_default();
+    Some((remaining.as_secs_f32() / total.as_secs_f32()).min(1.0))
+}
+
+fn add_block_eta(state: &progress::State, progress_text: &mut String) {
     match state {
         progress::State::Blocked(reason, maybe_eta) | progress::State::Halted(reason, maybe_eta) => {
             progress_text.push_str(" [");
@@ -301,7 +767,7 @@ fn add_block_eta(state: progress::State, progress_text: &mut String) {
             progress_text.push(']');
             if let Some(eta) = maybe_eta {
                 let now = SystemTime::now();
-                if eta > now {
+                if *eta > now {
                     use std::fmt::Write;
                     write!(
                         progress_text,
@@ -317,15 +783,31 @@ fn add_block_eta(state: progress::State, progress_text: &mut String) {
                 }
             }
         }
+        progress::State::Finished(success) => progress_text.push_str(if *success { " [done]" } else { " [failed]" }),
         progress::State::Running => {}
     }
 }
 
+/// Draw a tick for each of the `markers` at its position on a bar spanning `bound`, without covering any text.
+fn draw_marker_ticks(buf: &mut Buffer, bound: Rect, markers: &[progress::Marker], done_at: Step) {
+    if done_at == 0 || bound.width == 0 {
+        return;
+    }
+    for marker in markers {
+        let fraction = (marker.step as f32 / done_at as f32).min(1.0);
+        let x = bound.x + ((bound.width as f32 * fraction) as u16).min(bound.width - 1);
+        let cell = buf.get_mut(x, bound.y);
+        if cell.symbol() == " " {
+            cell.set_symbol("╎");
+        }
+    }
+}
+
 fn draw_spinner(buf: &mut Buffer, bound: Rect, step: Step, seed: usize, color: Color) {
     if bound.width == 0 {
         return;
     }
-    let x = bound.x + ((step + seed) % bound.width as usize) as u16;
+    let x = bound.x + (step.wrapping_add(seed) % bound.width as usize) as u16;
     let width = 5;
     let bound = rect::intersect(Rect { x, width, ..bound }, bound);
     tui_react::fill_background(bound, buf, color);
@@ -376,8 +858,87 @@ fn draw_progress_bar_fn(
     (fractional_progress_rect, Style::default().bg(color).fg(Color::Black))
 }
 
-pub fn draw_tree(entries: &[(Key, Task)], buf: &mut Buffer, bound: Rect, offset: u16) -> u16 {
+fn search_query(state: &State) -> Option<&str> {
+    state.search.as_deref().filter(|query| !query.is_empty())
+}
+
+fn search_match_style(style: Option<Style>) -> Style {
+    style.unwrap_or_default().fg(Color::Black).bg(Color::Yellow)
+}
+
+/// The color of the names of tasks which take longer than expected.
+const OVERDUE_COLOR: Color = Color::Indexed(208);
+
+/// Return the text to show after the name of `task` if it exceeded its expected duration at `now`.
+fn overdue_suffix(task: &Task, now: SystemTime) -> Option<String> {
+    task.overdue_by(now)
+        .map(|overdue| format!(" (overdue {})", format_duration(Duration::from_secs(overdue.as_secs()))))
+}
+
+/// Return `style` with the color for overdue tasks if `is_overdue` is true.
+fn overdue_style(is_overdue: bool, style: Option<Style>) -> Option<Style> {
+    if is_overdue {
+        Some(style.unwrap_or_default().fg(OVERDUE_COLOR))
+    } else {
+        style
+    }
+}
+
+const FAILED_DESCENDANT_MARKER: &str = "✗ ";
+
+/// Draw a red marker if a descendant of `task` failed, returning the width it took.
+fn draw_failed_descendant_marker(bound: Rect, buf: &mut Buffer, task: &Task) -> u16 {
+    if !task.failed_descendant {
+        return 0;
+    }
+    draw_text_with_ellipsis_nowrap(
+        bound,
+        buf,
+        FAILED_DESCENDANT_MARKER,
+        Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+    )
+}
+
+/// Return a glyph showing the state of `task` for display before its name if `show` is true.
+fn status_prefix(task: &Task, show: bool) -> String {
+    if show {
+        format!(
+            "{} ",
+            status_glyph(task.progress.as_ref().map(|progress| &progress.state), false)
+        )
+    } else {
+        String::new()
+    }
+}
+
+/// Return the id formatted for display after the name of a task if `show` is true and the id is known.
+fn id_suffix(id: progress::Id, show: bool) -> String {
+    show.then(|| format_id(id))
+        .flatten()
+        .map(|id| format!(" [{id}]"))
+        .unwrap_or_default()
+}
+
+/// Return the attempt of `task` for display after its name if it was retried.
+fn attempt_suffix(task: &Task) -> String {
+    if task.retries > 0 {
+        format!(" (attempt {})", task.attempt())
+    } else {
+        String::new()
+    }
+}
+
+pub fn draw_tree(
+    entries: &[(Key, Task)],
+    buf: &mut Buffer,
+    bound: Rect,
+    offset: u16,
+    search: Option<&str>,
+    show_ids: bool,
+    show_status_glyphs: bool,
+) -> u16 {
     let mut max_prefix_len = 0;
+    let now = SystemTime::now();
     for (line, (entry_index, entry)) in entries
         .iter()
         .enumerate()
@@ -388,22 +949,41 @@ pub fn draw_tree(entries: &[(Key, Task)], buf: &mut Buffer, bound: Rect, offset:
         let mut line_bound = rect::line_bound(bound, line);
         line_bound.x = line_bound.x.saturating_sub(1);
         line_bound.width = line_bound.width.saturating_sub(1);
-        let tree_prefix = format!("{} {} ", level_prefix(entries, entry_index), entry.1.name);
-        max_prefix_len = max_prefix_len.max(block_width(&tree_prefix));
+        let overdue = overdue_suffix(&entry.1, now);
+        let tree_prefix = format!(
+            "{} {}{}{}{}{} ",
+            level_prefix(entries, entry_index),
+            status_prefix(&entry.1, show_status_glyphs),
+            entry.1.name,
+            id_suffix(entry.1.id, show_ids),
+            attempt_suffix(&entry.1),
+            overdue.as_deref().unwrap_or_default()
+        );
+        let marker_width = if entry.1.failed_descendant {
+            block_width(FAILED_DESCENDANT_MARKER)
+        } else {
+            0
+        };
+        max_prefix_len = max_prefix_len.max(block_width(&tree_prefix) + marker_width);
 
-        let style = if entry.1.progress.is_none() {
+        let mut style = if entry.1.progress.is_none() {
             Style::default().add_modifier(Modifier::BOLD).into()
         } else {
             None
         };
-        draw_text_with_ellipsis_nowrap(line_bound, buf, tree_prefix, style);
+        style = overdue_style(overdue.is_some(), style);
+        if search.is_some_and(|query| task_matches(&entry.1, query)) {
+            style = Some(search_match_style(style));
+        }
+        let label_width = draw_text_with_ellipsis_nowrap(line_bound, buf, tree_prefix, style);
+        draw_failed_descendant_marker(rect::offset_x(line_bound, label_width), buf, &entry.1);
     }
     max_prefix_len
 }
 
 fn level_prefix(entries: &[(Key, Task)], entry_index: usize) -> String {
     let adj = Key::adjacency(entries, entry_index);
-    let key = entries[entry_index].0;
+    let key = &entries[entry_index].0;
     let key_level = key.level();
     let is_orphan = adj.level() != key_level;
     let mut buf = String::with_capacity(key_level as usize);
@@ -454,6 +1034,37 @@ fn level_prefix(entries: &[(Key, Task)], entry_index: usize) -> String {
     buf
 }
 
+/// Return how many of the hidden `tasks` are running, blocked, halted or organizational, like `12 running, 3 blocked`,
+/// leaving out states no task is in.
+fn hidden_by_state(tasks: &[(Key, Task)]) -> String {
+    let (mut running, mut blocked, mut halted, mut finished, mut groups) = (0, 0, 0, 0, 0);
+    for (_key, task) in tasks {
+        match task.progress.as_ref().map(|p| &p.state) {
+            Some(progress::State::Running) => running += 1,
+            Some(progress::State::Blocked(_, _)) => blocked += 1,
+            Some(progress::State::Halted(_, _)) => halted += 1,
+            Some(progress::State::Finished(_)) => finished += 1,
+            None => groups += 1,
+        }
+    }
+    let counts: Vec<_> = [
+        (running, "running"),
+        (blocked, "blocked"),
+        (halted, "halted"),
+        (finished, "finished"),
+        (groups, "groups"),
+    ]
+    .into_iter()
+    .filter(|(count, _)| *count > 0)
+    .map(|(count, state)| format!("{count} {state}"))
+    .collect();
+    if counts.is_empty() {
+        "0".into()
+    } else {
+        counts.join(", ")
+    }
+}
+
 pub fn draw_overflow(
     entries: &[(Key, Task)],
     buf: &mut Buffer,
@@ -462,22 +1073,23 @@ pub fn draw_overflow(
     num_entries_on_display: u16,
     offset: u16,
 ) {
-    let (count, mut progress_fraction) = entries
-        .iter()
-        .take(offset as usize)
-        .chain(entries.iter().skip((offset + num_entries_on_display) as usize))
-        .fold((0usize, 0f32), |(count, progress_fraction), (_key, value)| {
-            let progress = value.progress.as_ref().and_then(|p| p.fraction()).unwrap_or_default();
-            (count + 1, progress_fraction + progress)
-        });
+    let first_below = (offset as usize + num_entries_on_display as usize).min(entries.len());
+    let above = &entries[..(offset as usize).min(first_below)];
+    let below = &entries[first_below..];
+    let (count, mut progress_fraction) =
+        above
+            .iter()
+            .chain(below)
+            .fold((0usize, 0f32), |(count, progress_fraction), (_key, value)| {
+                let progress = value.progress.as_ref().and_then(|p| p.fraction()).unwrap_or_default();
+                (count + 1, progress_fraction + progress)
+            });
     progress_fraction /= count as f32;
     let label = format!(
-        "{} …{} skipped and {} more",
+        "{} …{} hidden above; {} below",
         if label_offset == 0 { "" } else { VERTICAL_LINE },
-        offset,
-        entries
-            .len()
-            .saturating_sub((offset + num_entries_on_display + 1) as usize)
+        hidden_by_state(above),
+        hidden_by_state(below),
     );
     let (progress_rect, style) = draw_progress_bar_fn(buf, bound, progress_fraction, |_| Color::Green);
 
@@ -1,18 +1,39 @@
 use std::{
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
     io::{self, Write},
-    time::Duration,
+    ops::RangeInclusive,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, SystemTime},
 };
 
 use futures_lite::StreamExt;
-use tui::layout::Rect;
+use tui::{buffer::Buffer, layout::Rect};
 
 use crate::{
-    render::tui::{draw, ticker},
+    messages::Message,
+    progress::{self, Task},
+    render::{
+        handle::Signal,
+        registry,
+        tui::{
+            draw, snapshot,
+            utils::{adjustable_ticker, find_match},
+            Output,
+        },
+        Control, Eco, EcoMode, OnConflict, Report, SmoothFractions, StreamKind,
+    },
+    time::{TimestampFormat, Timezone},
     Root, Throughput, WeakRoot,
 };
 
 /// Configure the terminal user interface
 #[derive(Clone)]
+#[cfg_attr(feature = "config", derive(serde::Deserialize), serde(default))]
 pub struct Options {
     /// The initial title to show for the whole window.
     ///
@@ -22,6 +43,8 @@ pub struct Options {
     /// The amount of frames to draw per second. If below 1.0, it determines the amount of seconds between the frame.
     ///
     /// *e.g.* 1.0/4.0 is one frame every 4 seconds.
+    /// Double or halve it at runtime with the `+` and `-` keys, or set it with [`Event::SetFps`].
+    /// A frame rate set with [`Control::set_frames_per_second()`] takes precedence if [`control`][Options::control] is set.
     pub frames_per_second: f32,
 
     /// If true, (default false), we will keep track of the previous progress state to derive
@@ -42,10 +65,103 @@ pub struct Options {
     /// The initial window size.
     ///
     /// If unset, it will be retrieved from the current terminal.
+    #[cfg_attr(feature = "config", serde(skip))]
     pub window_size: Option<Rect>,
 
     /// If true (default: true), we will stop running the TUI once the progress isn't available anymore (went out of scope).
     pub stop_if_progress_missing: bool,
+
+    /// If set, all key presses not handled by the TUI's own key bindings will be sent to this channel.
+    ///
+    /// Use it to implement application-specific hotkeys while the dashboard is active.
+    /// Note that keys are dropped silently if the receiving end of the channel is gone.
+    #[cfg_attr(feature = "config", serde(skip))]
+    pub on_unhandled_key: Option<std::sync::mpsc::Sender<Key>>,
+    /// If set, it will be set to `true` once the user requests the TUI to be interrupted.
+    ///
+    /// This is particularly useful in [`Interrupt::Deferred`] mode, as it allows the application to learn about the pending
+    /// interrupt and wind down its work, to then allow the TUI to stop by sending [`Interrupt::Instantly`].
+    #[cfg_attr(feature = "config", serde(skip))]
+    pub interrupt_signal: Option<Arc<AtomicBool>>,
+
+    /// If set (default: 80), a compact layout is used if the window is at most this many columns wide.
+    ///
+    /// It shows each task on a single line along with its progress bar, and hides the information pane.
+    pub compact_layout_width: Option<u16>,
+
+    /// If true (default: false), the id of each task is shown after its name, as text if it's printable or as hexadecimal.
+    ///
+    /// This helps to verify which tasks carry which id when using `add_child_with_id(…)`. Toggle it at runtime with the `i` key.
+    pub show_ids: bool,
+
+    /// The columns to show for each task and how wide they are, which can be toggled at runtime with the keys `1` to `7`.
+    pub columns: Columns,
+
+    /// If true (default: false), messages are shown in chronological order with the newest one at the bottom, like in a
+    /// log, instead of showing the newest one at the top. Toggle it at runtime with the `r` key.
+    pub messages_oldest_first: bool,
+
+    /// If true (default: false), bars of bounded tasks move towards their actual fill level over several frames,
+    /// without ever exceeding it.
+    ///
+    /// This makes bars move smoothly even if progress is updated rarely, given a high enough `frames_per_second`.
+    pub smooth_bars: bool,
+
+    /// If true (default: false), a second row with details is shown below the selected task, which is the one at the top
+    /// of the task list and the one searches jump to.
+    ///
+    /// It shows the progress with percentage, throughput and ETA, the note and the last message of the task, and isn't
+    /// available in the compact layout. Toggle it at runtime with the `v` key.
+    pub show_details: bool,
+
+    /// If set, the latest lines of this output, like the one of a child process, are shown in a pane below the messages.
+    ///
+    /// Toggle the pane at runtime with the `o` key, and scroll it with `(` and `)`.
+    #[cfg_attr(feature = "config", serde(skip))]
+    pub output: Option<Output>,
+
+    /// The way the time of messages is formatted _(default: hours, minutes and seconds)_.
+    pub timestamp_format: TimestampFormat,
+
+    /// The timezone of the time of messages _(default: local time with the `local-time` feature toggle, UTC otherwise)_.
+    pub timezone: Timezone,
+
+    /// If true (default: true), the TUI is drawn into the alternate screen, which restores the previous contents of the
+    /// terminal once it stops.
+    ///
+    /// Otherwise it's drawn inline into the normal buffer, leaving its last frame in the scrollback after it stopped.
+    pub use_alternate_screen: bool,
+
+    /// If set, _(default: None)_, the stream `out` writes to, to prevent other renderers from drawing to it at the same time.
+    ///
+    /// Log messages of prodash are suppressed while drawing to the stream set with
+    /// [`set_log_stream()`][crate::render::set_log_stream()].
+    pub stream: Option<StreamKind>,
+
+    /// What to do if another renderer draws to [`stream`][Options::stream] already _(default: [fail][OnConflict::Fail])_.
+    pub on_conflict: OnConflict,
+
+    /// When to save energy by drawing at most one frame per second, and only if tasks or messages changed or a key
+    /// was pressed _(default: [on battery][EcoMode::OnBattery])_.
+    pub eco_mode: EcoMode,
+
+    /// The amount of key presses and pastes to hold while a frame is drawn _(default: 32)_.
+    ///
+    /// Once that many are waiting, no more input is read from the terminal until they were handled, so none is lost.
+    pub input_buffer_size: usize,
+
+    /// If set, _(default: None)_, a summary of the top-level tasks with their outcome and duration, followed by all
+    /// failures, is written to this stream once the TUI stopped and the terminal was restored.
+    ///
+    /// This keeps the gist of the run visible, which would otherwise disappear along with the alternate screen.
+    pub exit_report: Option<StreamKind>,
+
+    /// If set, it allows to pause and resume drawing frames, show only tasks of certain levels, or change the frame rate
+    /// while the TUI is running.
+    ///
+    /// Tasks of all levels are shown unless a level filter is set through it.
+    #[cfg_attr(feature = "config", serde(skip))]
+    pub control: Option<Control>,
 }
 
 impl Default for Options {
@@ -57,6 +173,71 @@ impl Default for Options {
             recompute_column_width_every_nth_frame: None,
             window_size: None,
             stop_if_progress_missing: true,
+            on_unhandled_key: None,
+            compact_layout_width: Some(80),
+            interrupt_signal: None,
+            show_ids: false,
+            columns: Columns::default(),
+            messages_oldest_first: false,
+            smooth_bars: false,
+            show_details: false,
+            output: None,
+            timestamp_format: TimestampFormat::default(),
+            timezone: Timezone::default(),
+            use_alternate_screen: true,
+            stream: None,
+            on_conflict: OnConflict::default(),
+            eco_mode: EcoMode::default(),
+            input_buffer_size: 32,
+            exit_report: None,
+            control: None,
+        }
+    }
+}
+
+/// The columns the terminal user interface shows for each task, as used in [`Options::columns`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "config", derive(serde::Deserialize), serde(default))]
+pub struct Columns {
+    /// If true (default: true), the names of all tasks are shown as a tree. Toggle it at runtime with the `1` key.
+    pub tree: bool,
+    /// The initial width of the tree relative to the width of the window in percent _(default: 33)_, until it's fitted
+    /// to the names of all tasks.
+    pub tree_width_percent: u16,
+    /// If true (default: true), the current value of each task is shown, like `3/10 items`. Toggle it at runtime with
+    /// the `2` key.
+    pub value: bool,
+    /// If true (default: true), a bar shows the progress of bounded tasks, and a spinner the activity of unbounded ones.
+    /// Toggle it at runtime with the `3` key.
+    pub bar: bool,
+    /// If true (default: true), the rate of tasks with a unit supporting it is shown along with their value, which is
+    /// only known if `throughput` is enabled. Toggle it at runtime with the `4` key.
+    pub rate: bool,
+    /// If true (default: false), the estimated time until each bounded and running task is done is shown on the right,
+    /// like `~2m10s`, unless the window is too narrow. Toggle it at runtime with the `5` or `e` key.
+    ///
+    /// The estimate is based on the [rate][crate::progress::Value::rate()] of tasks, which is only known if `throughput`
+    /// is enabled or a [`ThroughputSampler`][crate::ThroughputSampler] is used.
+    pub eta: bool,
+    /// If true (default: false), the time since each task was first shown is displayed on the right, like `1m03s`,
+    /// unless the window is too narrow. Toggle it at runtime with the `6` key.
+    pub elapsed: bool,
+    /// If true (default: false), a glyph showing whether a task is running (`▶`), halted (`⏸`) or blocked (`⛔`) is
+    /// shown before its name, to see the state of tasks at a glance. Toggle it at runtime with the `7` or `g` key.
+    pub status_glyph: bool,
+}
+
+impl Default for Columns {
+    fn default() -> Self {
+        Columns {
+            tree: true,
+            tree_width_percent: 33,
+            value: true,
+            bar: true,
+            rate: true,
+            eta: false,
+            elapsed: false,
+            status_glyph: false,
         }
     }
 }
@@ -68,6 +249,35 @@ pub enum Line {
     Title(String),
     /// Set a line of text with the given content
     Text(String),
+    /// Set a line of text with the given content, aligned as given and truncated with an ellipsis if it doesn't fit.
+    Aligned {
+        /// The text to show.
+        text: String,
+        /// How to align `text` within the pane.
+        align: Align,
+    },
+}
+
+impl Line {
+    /// Create a line of text aligned according to `align`.
+    pub fn aligned(text: impl Into<String>, align: Align) -> Self {
+        Line::Aligned {
+            text: text.into(),
+            align,
+        }
+    }
+}
+
+/// The alignment of a [`Line::Aligned`] within the information pane, which takes the display width of unicode text into account.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum Align {
+    /// Align the text to the left edge of the pane.
+    #[default]
+    Left,
+    /// Center the text within the pane.
+    Center,
+    /// Align the text to the right edge of the pane.
+    Right,
 }
 
 /// The variants represented here allow the user to control when the GUI can be shutdown.
@@ -94,12 +304,17 @@ compile_error!(
     "Please set either the 'render-tui-crossterm' or 'render-tui-termion' feature whne using the 'render-tui'"
 );
 
-use crosstermion::crossterm::event::{KeyCode, KeyEventKind, KeyModifiers};
+use crosstermion::crossterm::{
+    event::{self, KeyCode, KeyEventKind, KeyModifiers},
+    terminal,
+};
 use crosstermion::{
-    input::{key_input_stream, Key},
+    input::Key,
     terminal::{tui::new_terminal, AlternateRawScreen},
 };
 
+use crate::render::tui::input::{self, Input};
+
 /// An event to be sent in the [`tui::render_with_input(…events)`](./fn.render_with_input.html) stream.
 ///
 /// This way, the TUI can be instructed to draw frames or change the information to be displayed.
@@ -109,6 +324,8 @@ pub enum Event {
     Tick,
     /// Send any key - can be used to simulate user input, and is typically generated by the TUI's own input loop.
     Input(Key),
+    /// Text pasted into the terminal, which is added to the search query while it's edited.
+    Paste(String),
     /// Change the size of the window to the given rectangle.
     ///
     /// Useful to embed the TUI into other terminal user interfaces that can resize dynamically.
@@ -119,6 +336,386 @@ pub enum Event {
     SetInformation(Vec<Line>),
     /// The way the GUI will respond to interrupt requests. See `Interrupt` for more information.
     SetInterruptMode(Interrupt),
+    /// Write the tasks and messages currently shown to the file at the given path, as JSON if its extension is `json`
+    /// or as plain text otherwise.
+    ///
+    /// Pressing `s` does the same, writing plain text to `prodash-snapshot-<seconds since epoch>.txt` in the current directory.
+    Snapshot(PathBuf),
+    /// Draw the given amount of frames per second from now on, see [`Options::frames_per_second`].
+    ///
+    /// Values that aren't positive are ignored. This is useful to lower the refresh rate over slow connections.
+    SetFps(f32),
+}
+
+/// The range of frames per second to which the `+` and `-` keys are limited.
+const KEYBOARD_FPS_RANGE: RangeInclusive<f32> = 0.1..=60.0;
+
+/// What to do after an [`Event`] was passed to [`Dashboard::handle_event()`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Outcome {
+    /// The event changed the state of the dashboard, and a new frame should be drawn.
+    Redraw,
+    /// The event had no effect, there is no need to draw a new frame.
+    Ignored,
+    /// The user requested the dashboard to stop, no more frames should be drawn.
+    Quit,
+}
+
+/// The state of the progress dashboard for use with a terminal owned by the caller.
+///
+/// This allows to draw the dashboard alongside other widgets, feeding it [events](Event) with [`handle_event()`](Dashboard::handle_event())
+/// and drawing it with [`draw_frame()`](Dashboard::draw_frame()) or [`draw()`](Dashboard::draw()).
+/// It's what [`render_with_input()`] uses internally, but without owning the terminal or an event loop.
+pub struct Dashboard {
+    state: draw::State,
+    interrupt_mode: InterruptDrawInfo,
+    entries: Vec<(progress::Key, Task)>,
+    messages: Vec<Message>,
+    tick: usize,
+    store_task_size_every: usize,
+    on_unhandled_key: Option<std::sync::mpsc::Sender<Key>>,
+    interrupt_signal: Option<Arc<AtomicBool>>,
+    output: Option<Output>,
+    output_lines: Vec<String>,
+    control: Option<Control>,
+    /// The hash of the tasks and messages obtained by the last update, to learn if they changed.
+    content_hash: u64,
+}
+
+impl Dashboard {
+    /// Create a new instance configured with `options`.
+    ///
+    /// Note that `frames_per_second` is only used to compute throughput, while `window_size`, `stop_if_progress_missing`,
+    /// `use_alternate_screen`, `stream`, `on_conflict`, `eco_mode`, `input_buffer_size` and `exit_report` are ignored as
+    /// they concern the terminal and event loop, which are owned by the caller.
+    pub fn new(options: Options) -> Self {
+        let Options {
+            title,
+            frames_per_second,
+            window_size: _,
+            recompute_column_width_every_nth_frame,
+            throughput,
+            stop_if_progress_missing: _,
+            on_unhandled_key,
+            compact_layout_width,
+            interrupt_signal,
+            show_ids,
+            columns,
+            messages_oldest_first,
+            smooth_bars,
+            show_details,
+            output,
+            timestamp_format,
+            timezone,
+            use_alternate_screen: _,
+            stream: _,
+            on_conflict: _,
+            eco_mode: _,
+            input_buffer_size: _,
+            exit_report: _,
+            control,
+        } = options;
+        let mut state = draw::State {
+            title,
+            duration_per_frame: Duration::from_secs_f32(1.0 / frames_per_second),
+            compact_layout_width,
+            show_ids,
+            columns,
+            messages_oldest_first,
+            show_details,
+            timestamp_format,
+            timezone,
+            started_at: Some(SystemTime::now()),
+            smooth_fractions: smooth_bars.then(SmoothFractions::default),
+            ..draw::State::default()
+        };
+        if throughput {
+            state.throughput = Some(Throughput::default());
+        }
+        Dashboard {
+            state,
+            interrupt_mode: InterruptDrawInfo::Instantly,
+            entries: Vec::new(),
+            messages: Vec::new(),
+            tick: 0,
+            store_task_size_every: recompute_column_width_every_nth_frame.unwrap_or(1).max(1),
+            on_unhandled_key,
+            interrupt_signal,
+            output,
+            output_lines: Vec::new(),
+            control,
+            content_hash: 0,
+        }
+    }
+
+    /// Apply `event` to the dashboard and return what to do next.
+    pub fn handle_event(&mut self, event: Event) -> Outcome {
+        if let Event::Input(_) | Event::Paste(_) | Event::Snapshot(_) = event {
+            self.state.notice = None;
+        }
+        let state = &mut self.state;
+        match event {
+            Event::Tick if self.control.as_ref().is_some_and(Control::is_paused) => return Outcome::Ignored,
+            Event::Tick => {}
+            Event::Input(key) if key.kind != KeyEventKind::Release && state.search_input_active => match key.code {
+                KeyCode::Esc => {
+                    state.search = None;
+                    state.search_input_active = false;
+                }
+                KeyCode::Enter => {
+                    state.search_input_active = false;
+                    if let Some(idx) = state
+                        .search
+                        .as_deref()
+                        .and_then(|query| find_match(&self.entries, query, state.task_offset as usize, true, true))
+                    {
+                        state.task_offset = idx as u16;
+                    }
+                }
+                KeyCode::Backspace => {
+                    if let Some(query) = state.search.as_mut() {
+                        query.pop();
+                    }
+                }
+                KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    state.search.get_or_insert_with(String::new).push(c)
+                }
+                _ => return Outcome::Ignored,
+            },
+            Event::Input(key) if key.kind != KeyEventKind::Release => match key.code {
+                KeyCode::Char('c') | KeyCode::Char('[') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    return self.request_interrupt()
+                }
+                KeyCode::Esc | KeyCode::Char('q') => return self.request_interrupt(),
+                KeyCode::Char('`') => state.hide_messages = !state.hide_messages,
+                KeyCode::Char('~') => state.messages_fullscreen = !state.messages_fullscreen,
+                KeyCode::Char('J') => state.scroll_messages_down(1),
+                KeyCode::Char('D') => state.scroll_messages_down(10),
+                KeyCode::Char('j') => state.task_offset = state.task_offset.saturating_add(1),
+                KeyCode::Char('d') => state.task_offset = state.task_offset.saturating_add(10),
+                KeyCode::Char('K') => state.scroll_messages_down(-1),
+                KeyCode::Char('U') => state.scroll_messages_down(-10),
+                KeyCode::Char('k') => state.task_offset = state.task_offset.saturating_sub(1),
+                KeyCode::Char('u') => state.task_offset = state.task_offset.saturating_sub(10),
+                KeyCode::Char('[') => state.hide_info = !state.hide_info,
+                KeyCode::Char('{') => state.maximize_info = !state.maximize_info,
+                KeyCode::Char('i') => state.show_ids = !state.show_ids,
+                KeyCode::Char('1') => state.columns.tree = !state.columns.tree,
+                KeyCode::Char('2') => state.columns.value = !state.columns.value,
+                KeyCode::Char('3') => state.columns.bar = !state.columns.bar,
+                KeyCode::Char('4') => state.columns.rate = !state.columns.rate,
+                KeyCode::Char('5') | KeyCode::Char('e') => state.columns.eta = !state.columns.eta,
+                KeyCode::Char('6') => state.columns.elapsed = !state.columns.elapsed,
+                KeyCode::Char('7') | KeyCode::Char('g') => state.columns.status_glyph = !state.columns.status_glyph,
+                KeyCode::Char('r') => state.messages_oldest_first = !state.messages_oldest_first,
+                KeyCode::Char('v') => state.show_details = !state.show_details,
+                KeyCode::Char('o') => state.hide_output = !state.hide_output,
+                KeyCode::Char('(') => state.output_offset = state.output_offset.saturating_add(1),
+                KeyCode::Char(')') => state.output_offset = state.output_offset.saturating_sub(1),
+                KeyCode::Char('+') | KeyCode::Char('=') => {
+                    let fps = (self.frames_per_second() * 2.0).min(*KEYBOARD_FPS_RANGE.end());
+                    return self.set_frames_per_second(fps);
+                }
+                KeyCode::Char('-') => {
+                    let fps = (self.frames_per_second() / 2.0).max(*KEYBOARD_FPS_RANGE.start());
+                    return self.set_frames_per_second(fps);
+                }
+                KeyCode::Char('x') => return self.cancel_selected_task(),
+                KeyCode::Char('s') => {
+                    let secs = SystemTime::now()
+                        .duration_since(SystemTime::UNIX_EPOCH)
+                        .unwrap_or_default()
+                        .as_secs();
+                    return self.snapshot(format!("prodash-snapshot-{secs}.txt").into());
+                }
+                KeyCode::Char('/') => {
+                    state.search = Some(String::new());
+                    state.search_input_active = true;
+                }
+                KeyCode::Char(c @ ('n' | 'N')) if state.search.is_some() => {
+                    if let Some(idx) = state
+                        .search
+                        .as_deref()
+                        .and_then(|query| find_match(&self.entries, query, state.task_offset as usize, c == 'n', false))
+                    {
+                        state.task_offset = idx as u16;
+                    }
+                }
+                _ => {
+                    if let Some(tx) = self.on_unhandled_key.as_ref() {
+                        tx.send(key).ok();
+                    }
+                    return Outcome::Ignored;
+                }
+            },
+            Event::Input(_) => return Outcome::Ignored,
+            Event::Paste(text) => match state.search.as_mut().filter(|_| state.search_input_active) {
+                Some(query) => query.extend(text.chars().filter(|c| !c.is_control())),
+                None => return Outcome::Ignored,
+            },
+            Event::SetWindowSize(bound) => state.user_provided_window_size = Some(bound),
+            Event::SetTitle(title) => state.title = title,
+            Event::SetInformation(info) => state.information = info,
+            Event::Snapshot(path) => return self.snapshot(path),
+            Event::SetFps(fps) => return self.set_frames_per_second(fps),
+            Event::SetInterruptMode(mode) => {
+                self.interrupt_mode = match mode {
+                    Interrupt::Instantly => {
+                        if let InterruptDrawInfo::Deferred(true) = self.interrupt_mode {
+                            return Outcome::Quit;
+                        }
+                        InterruptDrawInfo::Instantly
+                    }
+                    Interrupt::Deferred => InterruptDrawInfo::Deferred(match self.interrupt_mode {
+                        InterruptDrawInfo::Deferred(interrupt_requested) => interrupt_requested,
+                        _ => false,
+                    }),
+                };
+            }
+        }
+        Outcome::Redraw
+    }
+
+    /// Obtain the latest tasks and messages from `progress` and draw them into `frame`, using all of its area.
+    pub fn draw_frame(&mut self, progress: &impl Root, frame: &mut tui::Frame<'_>) {
+        self.update(progress);
+        let bound = frame.size();
+        self.draw(bound, frame.buffer_mut());
+    }
+
+    /// Obtain the latest tasks and messages from `progress` to be shown by the next call to [`draw()`](Dashboard::draw()).
+    ///
+    /// Only tasks within the level filter of [`Options::control`] are kept, if it has one.
+    pub fn update(&mut self, progress: &impl Root) {
+        self.update_all_levels(progress);
+        self.retain_filtered_levels();
+    }
+
+    fn update_all_levels(&mut self, progress: &impl Root) {
+        progress.sorted_snapshot(&mut self.entries);
+        self.state.clock_paused = progress.is_clock_paused();
+        self.state.run_started_at = progress.started_at();
+        if !self.state.hide_messages {
+            progress.copy_messages(&mut self.messages);
+        }
+        match self.output.as_ref().filter(|_| !self.state.hide_output) {
+            Some(output) => output.copy_lines(&mut self.output_lines),
+            None => self.output_lines.clear(),
+        }
+    }
+
+    fn retain_filtered_levels(&mut self) {
+        if let Some(levels) = self.control.as_ref().and_then(Control::level_filter) {
+            self.entries.retain(|(key, _)| levels.contains(&key.level()));
+        }
+    }
+
+    /// Return true if the tasks, messages or output obtained by the last call to [`update()`](Dashboard::update())
+    /// differ from the ones obtained by the call before.
+    fn content_changed(&mut self) -> bool {
+        let mut hasher = DefaultHasher::new();
+        self.entries.hash(&mut hasher);
+        self.messages.len().hash(&mut hasher);
+        self.messages
+            .last()
+            .map(|message| (message.time, &message.message, message.count))
+            .hash(&mut hasher);
+        self.output_lines.hash(&mut hasher);
+        let hash = hasher.finish();
+        std::mem::replace(&mut self.content_hash, hash) != hash
+    }
+
+    /// Draw the tasks and messages obtained by the last call to [`update()`](Dashboard::update()) into `bound` of `buf`.
+    ///
+    /// Note that `bound` is overridden by [`Event::SetWindowSize`] if it was received.
+    /// If it's smaller than 40 columns by 10 rows, only a line stating this minimum size is drawn.
+    pub fn draw(&mut self, bound: Rect, buf: &mut Buffer) {
+        self.tick += 1;
+        let bound = self.state.user_provided_window_size.unwrap_or(bound);
+        draw::all(
+            &mut self.state,
+            self.interrupt_mode,
+            &self.entries,
+            &self.messages,
+            &self.output_lines,
+            bound,
+            buf,
+        );
+        if self.tick == 1
+            || self.tick % self.store_task_size_every == 0
+            || self.state.last_tree_column_width.unwrap_or(0) == 0
+        {
+            self.state.next_tree_column_width = self.state.last_tree_column_width;
+        }
+    }
+
+    /// Return true if the last call to [`update()`](Dashboard::update()) didn't yield any task, besides the name of the
+    /// root.
+    pub fn is_empty(&self) -> bool {
+        crate::render::has_no_tasks(&self.entries)
+    }
+
+    /// Return the amount of frames that should be drawn per second, as configured initially or changed by the user since,
+    /// unless it's set through [`Options::control`].
+    pub fn frames_per_second(&self) -> f32 {
+        1.0 / self.duration_per_frame().as_secs_f32()
+    }
+
+    fn duration_per_frame(&self) -> Duration {
+        self.control
+            .as_ref()
+            .and_then(Control::frames_per_second)
+            .and_then(|fps| Duration::try_from_secs_f32(1.0 / fps).ok())
+            .unwrap_or(self.state.duration_per_frame)
+    }
+
+    /// Write the tasks and messages obtained by the last call to [`update()`](Dashboard::update()) to the file at `path`,
+    /// as JSON if its extension is `json` or as plain text otherwise.
+    pub fn write_snapshot(&self, path: &Path) -> io::Result<()> {
+        snapshot::write(path, &self.state.title, &self.entries, &self.messages)
+    }
+
+    fn snapshot(&mut self, path: PathBuf) -> Outcome {
+        self.state.notice = Some(match self.write_snapshot(&path) {
+            Ok(()) => Ok(format!("snapshot written to {}", path.display())),
+            Err(err) => Err(format!("could not write snapshot to {}: {err}", path.display())),
+        });
+        Outcome::Redraw
+    }
+
+    /// Request the selected task, the one at the top of the task list, to stop through its cancel token.
+    fn cancel_selected_task(&mut self) -> Outcome {
+        self.state.notice = Some(match self.entries.get(self.state.task_offset as usize) {
+            Some((_, task)) => {
+                task.cancel_token.cancel();
+                Ok(format!("requested '{}' to stop", task.name))
+            }
+            None => Err("there is no task to cancel".into()),
+        });
+        Outcome::Redraw
+    }
+
+    fn set_frames_per_second(&mut self, fps: f32) -> Outcome {
+        match Duration::try_from_secs_f32(1.0 / fps) {
+            Ok(duration_per_frame) if fps > 0.0 => {
+                self.state.duration_per_frame = duration_per_frame;
+                self.state.notice = Some(Ok(format!("drawing {fps:.1} frames per second")));
+                Outcome::Redraw
+            }
+            _ => Outcome::Ignored,
+        }
+    }
+
+    fn request_interrupt(&mut self) -> Outcome {
+        signal_interrupt(self.interrupt_signal.as_deref());
+        match self.interrupt_mode {
+            InterruptDrawInfo::Instantly => Outcome::Quit,
+            InterruptDrawInfo::Deferred(_) => {
+                self.interrupt_mode = InterruptDrawInfo::Deferred(true);
+                Outcome::Redraw
+            }
+        }
+    }
 }
 
 /// Returns a future that draws the terminal user interface indefinitely.
@@ -128,134 +725,174 @@ pub enum Event {
 /// * `options` are configuring the TUI.
 /// * `events` is a stream of `Event`s which manipulate the TUI while it is running
 ///
-/// Failure may occour if there is no terminal to draw into.
+/// Failure may occour if there is no terminal to draw into, or if another renderer draws to [`Options::stream`] already.
+/// Use [`Dashboard`] to draw into a terminal owned by the caller instead.
 pub fn render_with_input(
     out: impl std::io::Write,
     progress: impl WeakRoot,
     options: Options,
     events: impl futures_core::Stream<Item = Event> + Send + Unpin,
 ) -> Result<impl std::future::Future<Output = ()>, std::io::Error> {
-    let Options {
-        title,
-        frames_per_second,
-        window_size,
-        recompute_column_width_every_nth_frame,
-        throughput,
-        stop_if_progress_missing,
-    } = options;
-    let mut terminal = new_terminal(AlternateRawScreen::try_from(out)?)?;
+    let window_size = options.window_size;
+    let stop_if_progress_missing = options.stop_if_progress_missing;
+    let micros_per_frame = Arc::new(AtomicU64::new(
+        Duration::from_secs_f32(1.0 / options.frames_per_second).as_micros() as u64,
+    ));
+    let replaced = Arc::new(AtomicBool::new(false));
+    let registration = options
+        .stream
+        .map(|stream| {
+            let replaced = Arc::clone(&replaced);
+            registry::register(stream, options.on_conflict, move || {
+                replaced.store(true, Ordering::SeqCst)
+            })
+        })
+        .transpose()?;
+    let use_alternate_screen = options.use_alternate_screen;
+    let mut eco = Eco::new(options.eco_mode);
+    let mut terminal = new_terminal(Screen::new(out, use_alternate_screen)?)?;
+    if !use_alternate_screen {
+        terminal.clear()?;
+    }
     terminal.hide_cursor()?;
+    let mut session = Session {
+        terminal,
+        report: options.exit_report.map(Report::new),
+    };
 
-    let duration_per_frame = Duration::from_secs_f32(1.0 / frames_per_second);
-    let key_receive = key_input_stream();
+    let input_events = input::stream(options.input_buffer_size.max(1)).map(|input| match input {
+        Input::Key(key) => Event::Input(key),
+        Input::Paste(text) => Event::Paste(text),
+    });
 
     let render_fut = async move {
-        let mut state = draw::State {
-            title,
-            duration_per_frame,
-            ..draw::State::default()
-        };
-        if throughput {
-            state.throughput = Some(Throughput::default());
-        }
-        let mut interrupt_mode = InterruptDrawInfo::Instantly;
+        let _registration = registration;
+        let mut dashboard = Dashboard::new(options);
         let (entries_cap, messages_cap) = progress
             .upgrade()
             .map(|p| (p.num_tasks(), p.messages_capacity()))
             .unwrap_or_default();
-        let mut entries = Vec::with_capacity(entries_cap);
-        let mut messages = Vec::with_capacity(messages_cap);
-        let mut events = ticker(duration_per_frame)
+        dashboard.entries.reserve(entries_cap);
+        dashboard.messages.reserve(messages_cap);
+        let mut events = adjustable_ticker(Arc::clone(&micros_per_frame))
             .map(|_| Event::Tick)
-            .or(key_receive.map(Event::Input))
+            .or(input_events)
             .or(events);
 
-        let mut tick = 0usize;
-        let store_task_size_every = recompute_column_width_every_nth_frame.unwrap_or(1).max(1);
         while let Some(event) = events.next().await {
-            let mut skip_redraw = false;
-            match event {
-                Event::Tick => {}
-                Event::Input(key) if key.kind != KeyEventKind::Release => match key.code {
-                    KeyCode::Char('c') | KeyCode::Char('[') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                        match interrupt_mode {
-                            InterruptDrawInfo::Instantly => break,
-                            InterruptDrawInfo::Deferred(_) => interrupt_mode = InterruptDrawInfo::Deferred(true),
-                        }
-                    }
-                    KeyCode::Esc | KeyCode::Char('q') => match interrupt_mode {
-                        InterruptDrawInfo::Instantly => break,
-                        InterruptDrawInfo::Deferred(_) => interrupt_mode = InterruptDrawInfo::Deferred(true),
-                    },
-                    KeyCode::Char('`') => state.hide_messages = !state.hide_messages,
-                    KeyCode::Char('~') => state.messages_fullscreen = !state.messages_fullscreen,
-                    KeyCode::Char('J') => state.message_offset = state.message_offset.saturating_add(1),
-                    KeyCode::Char('D') => state.message_offset = state.message_offset.saturating_add(10),
-                    KeyCode::Char('j') => state.task_offset = state.task_offset.saturating_add(1),
-                    KeyCode::Char('d') => state.task_offset = state.task_offset.saturating_add(10),
-                    KeyCode::Char('K') => state.message_offset = state.message_offset.saturating_sub(1),
-                    KeyCode::Char('U') => state.message_offset = state.message_offset.saturating_sub(10),
-                    KeyCode::Char('k') => state.task_offset = state.task_offset.saturating_sub(1),
-                    KeyCode::Char('u') => state.task_offset = state.task_offset.saturating_sub(10),
-                    KeyCode::Char('[') => state.hide_info = !state.hide_info,
-                    KeyCode::Char('{') => state.maximize_info = !state.maximize_info,
-                    _ => skip_redraw = true,
-                },
-                Event::Input(_) => skip_redraw = true,
-                Event::SetWindowSize(bound) => state.user_provided_window_size = Some(bound),
-                Event::SetTitle(title) => state.title = title,
-                Event::SetInformation(info) => state.information = info,
-                Event::SetInterruptMode(mode) => {
-                    interrupt_mode = match mode {
-                        Interrupt::Instantly => {
-                            if let InterruptDrawInfo::Deferred(true) = interrupt_mode {
-                                break;
-                            }
-                            InterruptDrawInfo::Instantly
-                        }
-                        Interrupt::Deferred => InterruptDrawInfo::Deferred(match interrupt_mode {
-                            InterruptDrawInfo::Deferred(interrupt_requested) => interrupt_requested,
-                            _ => false,
-                        }),
-                    };
-                }
+            if replaced.load(Ordering::SeqCst) {
+                break;
             }
-            if !skip_redraw {
-                tick += 1;
-
-                let progress = match progress.upgrade() {
-                    Some(progress) => progress,
-                    None if stop_if_progress_missing => break,
-                    None => continue,
-                };
-                progress.sorted_snapshot(&mut entries);
-                if stop_if_progress_missing && entries.is_empty() {
-                    break;
-                }
-                let terminal_window_size = terminal.pre_render().expect("pre-render to work");
-                let window_size = state
-                    .user_provided_window_size
-                    .or(window_size)
-                    .unwrap_or(terminal_window_size);
-                let buf = terminal.current_buffer_mut();
-                if !state.hide_messages {
-                    progress.copy_messages(&mut messages);
-                }
-
-                draw::all(&mut state, interrupt_mode, &entries, &messages, window_size, buf);
-                if tick == 1 || tick % store_task_size_every == 0 || state.last_tree_column_width.unwrap_or(0) == 0 {
-                    state.next_tree_column_width = state.last_tree_column_width;
-                }
-                terminal.post_render().expect("post render to work");
+            let is_tick = matches!(event, Event::Tick);
+            match dashboard.handle_event(event) {
+                Outcome::Quit => break,
+                Outcome::Ignored => continue,
+                Outcome::Redraw => {}
+            }
+            let eco_is_active = eco.is_active();
+            micros_per_frame.store(
+                eco.duration_per_frame(dashboard.duration_per_frame()).as_micros() as u64,
+                Ordering::Relaxed,
+            );
+            let progress = match progress.upgrade() {
+                Some(progress) => progress,
+                None if stop_if_progress_missing => break,
+                None => continue,
+            };
+            dashboard.update_all_levels(&progress);
+            if let Some(report) = session.report.as_mut() {
+                report.update(&dashboard.entries, &progress);
+            }
+            dashboard.retain_filtered_levels();
+            if stop_if_progress_missing && dashboard.is_empty() {
+                break;
+            }
+            if !dashboard.content_changed() && eco_is_active && is_tick {
+                continue;
+            }
+            let terminal = &mut session.terminal;
+            let terminal_window_size = terminal.pre_render().expect("pre-render to work");
+            let buf = terminal.current_buffer_mut();
+            dashboard.draw(window_size.unwrap_or(terminal_window_size), buf);
+            terminal.post_render().expect("post render to work");
+        }
+        if !use_alternate_screen {
+            // Leave the last frame in place and continue below it.
+            let terminal = &mut session.terminal;
+            if let Ok(size) = terminal.size() {
+                terminal.set_cursor(0, size.bottom().saturating_sub(1)).ok();
             }
         }
         // Make sure the terminal responds right away when this future stops, to reset back to the 'non-alternate' buffer
-        drop(terminal);
+        drop(session);
         io::stdout().flush().ok();
     };
     Ok(render_fut)
 }
 
+/// The terminal the TUI draws into along with the report to write once it stops.
+///
+/// Fields are dropped in order, so the report is written only after the terminal was restored, which also happens
+/// if the future drawing the TUI is dropped before it finished.
+struct Session<T> {
+    terminal: T,
+    report: Option<Report>,
+}
+
+/// The terminal the TUI draws into, which is restored once dropped.
+enum Screen<T: Write> {
+    /// The alternate screen in raw mode, which leaves the normal buffer untouched.
+    Alternate(AlternateRawScreen<T>),
+    /// The normal buffer in raw mode.
+    Inline(T),
+}
+
+impl<T: Write> Screen<T> {
+    fn new(out: T, use_alternate_screen: bool) -> io::Result<Self> {
+        let mut screen = if use_alternate_screen {
+            Screen::Alternate(AlternateRawScreen::try_from(out)?)
+        } else {
+            terminal::enable_raw_mode()?;
+            Screen::Inline(out)
+        };
+        crosstermion::crossterm::execute!(screen, event::EnableBracketedPaste)?;
+        Ok(screen)
+    }
+}
+
+impl<T: Write> Write for Screen<T> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Screen::Alternate(out) => out.write(buf),
+            Screen::Inline(out) => out.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Screen::Alternate(out) => out.flush(),
+            Screen::Inline(out) => out.flush(),
+        }
+    }
+}
+
+impl<T: Write> Drop for Screen<T> {
+    fn drop(&mut self) {
+        crosstermion::crossterm::execute!(self, event::DisableBracketedPaste).ok();
+        if let Screen::Inline(out) = self {
+            out.write_all(b"\r\n").ok();
+            out.flush().ok();
+            terminal::disable_raw_mode().ok();
+        }
+    }
+}
+
+fn signal_interrupt(signal: Option<&AtomicBool>) {
+    if let Some(signal) = signal {
+        signal.store(true, Ordering::SeqCst);
+    }
+}
+
 /// An easy-to-use version of `render_with_input(…)` that does not allow state manipulation via an event stream.
 pub fn render(
     out: impl std::io::Write,
@@ -264,3 +901,73 @@ pub fn render(
 ) -> Result<impl std::future::Future<Output = ()>, std::io::Error> {
     render_with_input(out, progress, config, futures_lite::stream::pending())
 }
+
+/// A handle to a terminal user interface drawn on its own thread, as returned by [`render_in_thread()`].
+///
+/// Dropping it shuts down the TUI and waits for it to restore the terminal.
+pub struct JoinHandle {
+    inner: Option<std::thread::JoinHandle<()>>,
+    shutdown: Arc<Signal>,
+}
+
+impl JoinHandle {
+    /// Instruct the TUI to stop, without waiting for it.
+    pub fn shutdown(&mut self) {
+        self.shutdown.set();
+    }
+    /// Wait for the TUI to stop naturally, for example because the user quit or the progress was dropped.
+    pub fn wait(mut self) {
+        self.inner.take().and_then(|h| h.join().ok());
+    }
+    /// Instruct the TUI to stop and wait until it did.
+    pub fn shutdown_and_wait(mut self) {
+        self.shutdown();
+        self.wait();
+    }
+}
+
+impl Drop for JoinHandle {
+    fn drop(&mut self) {
+        self.shutdown();
+        self.inner.take().and_then(|h| h.join().ok());
+    }
+}
+
+/// Like [`render()`], but draws the terminal user interface on a new thread instead of returning a future.
+///
+/// Fails if there is no terminal to draw into. Use it along with [`RenderHandle`][crate::render::RenderHandle] to
+/// switch between this and the line renderer at runtime.
+pub fn render_in_thread(
+    out: impl std::io::Write + Send + 'static,
+    progress: impl WeakRoot + Send + 'static,
+    options: Options,
+) -> io::Result<JoinHandle> {
+    let shutdown = Arc::new(Signal::default());
+    let (setup_send, setup_recv) = std::sync::mpsc::sync_channel(1);
+    let inner = std::thread::Builder::new().name("render-tui".into()).spawn({
+        let shutdown = Arc::clone(&shutdown);
+        move || {
+            let render_fut = match render(out, progress, options) {
+                Ok(render_fut) => {
+                    setup_send.send(Ok(())).ok();
+                    render_fut
+                }
+                Err(err) => {
+                    setup_send.send(Err(err)).ok();
+                    return;
+                }
+            };
+            futures_lite::future::block_on(futures_lite::future::or(
+                render_fut,
+                std::future::poll_fn(|cx| shutdown.poll_set(cx)),
+            ));
+        }
+    })?;
+    setup_recv
+        .recv()
+        .unwrap_or_else(|_| Err(io::Error::other("the TUI thread stopped unexpectedly")))?;
+    Ok(JoinHandle {
+        inner: Some(inner),
+        shutdown,
+    })
+}
@@ -0,0 +1,101 @@
+use std::{
+    ops::ControlFlow,
+    pin::Pin,
+    sync::{mpsc, Arc, Mutex},
+    task::{Context, Poll, Waker},
+};
+
+use crosstermion::{crossterm::event, input::Key};
+
+/// An event read from the terminal.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Input {
+    /// A key was pressed, repeated or released.
+    Key(Key),
+    /// Text was pasted into the terminal all at once, which requires bracketed paste to be enabled.
+    Paste(String),
+}
+
+/// Read input on a new thread and deliver it through a channel which holds up to `buffer_size` events.
+///
+/// Once the channel is full, no more input is read until events are received, so no input is lost if the receiver
+/// is busy. The thread stops once the receiver is dropped and the next event was read.
+pub fn channel(buffer_size: usize) -> mpsc::Receiver<Input> {
+    let (tx, rx) = mpsc::sync_channel(buffer_size);
+    spawn_reader(move |input| tx.send(input).map_or(ControlFlow::Break(()), ControlFlow::Continue));
+    rx
+}
+
+/// Read input on a new thread and deliver it through a stream which holds up to `buffer_size` events, without being
+/// tied to any particular executor.
+///
+/// See [`channel()`] for details.
+pub fn stream(buffer_size: usize) -> Stream {
+    let (tx, rx) = mpsc::sync_channel(buffer_size);
+    let waker = Arc::new(Mutex::new(None::<Waker>));
+    spawn_reader({
+        let waker = Arc::clone(&waker);
+        move |input| {
+            if tx.send(input).is_err() {
+                return ControlFlow::Break(());
+            }
+            if let Some(waker) = waker.lock().ok().and_then(|mut waker| waker.take()) {
+                waker.wake();
+            }
+            ControlFlow::Continue(())
+        }
+    });
+    Stream { rx, waker }
+}
+
+/// Read input on a new thread and call `on_input` with each event until it breaks.
+///
+/// Input is read only once `on_input` returned, which provides backpressure.
+pub fn with_callback(on_input: impl FnMut(Input) -> ControlFlow<()> + Send + 'static) -> std::thread::JoinHandle<()> {
+    spawn_reader(on_input)
+}
+
+fn spawn_reader(mut deliver: impl FnMut(Input) -> ControlFlow<()> + Send + 'static) -> std::thread::JoinHandle<()> {
+    std::thread::Builder::new()
+        .name("prodash-tui-input".into())
+        .spawn(move || {
+            while let Ok(event) = event::read() {
+                let input = match event {
+                    event::Event::Key(key) => Input::Key(key),
+                    event::Event::Paste(text) => Input::Paste(text),
+                    _ => continue,
+                };
+                if deliver(input).is_break() {
+                    break;
+                }
+            }
+        })
+        .expect("starting a thread works")
+}
+
+/// The input of the terminal as returned by [`stream()`].
+pub struct Stream {
+    rx: mpsc::Receiver<Input>,
+    waker: Arc<Mutex<Option<Waker>>>,
+}
+
+impl futures_core::Stream for Stream {
+    type Item = Input;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        match self.rx.try_recv() {
+            Ok(input) => return Poll::Ready(Some(input)),
+            Err(mpsc::TryRecvError::Disconnected) => return Poll::Ready(None),
+            Err(mpsc::TryRecvError::Empty) => {}
+        }
+        if let Ok(mut waker) = self.waker.lock() {
+            *waker = Some(cx.waker().clone());
+        }
+        // Input may have arrived before the waker was set, in which case nobody would wake us.
+        match self.rx.try_recv() {
+            Ok(input) => Poll::Ready(Some(input)),
+            Err(mpsc::TryRecvError::Disconnected) => Poll::Ready(None),
+            Err(mpsc::TryRecvError::Empty) => Poll::Pending,
+        }
+    }
+}
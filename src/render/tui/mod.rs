@@ -9,7 +9,7 @@
 * ```should_panic
 * # fn main() -> Result<(), Box<dyn std::error::Error>> {
 * use futures::task::{LocalSpawnExt, SpawnExt};
-* use prodash::render::tui::ticker;
+* use prodash::time::ticker;
 * use prodash::Root;
 * // obtain a progress tree
 * let root = prodash::tree::Root::new();
@@ -51,9 +51,14 @@
 */
 mod draw;
 mod engine;
+/// Reading input from the terminal on a dedicated thread, to receive it through a channel, a stream or a callback.
+pub mod input;
+mod output;
+mod snapshot;
 mod utils;
 
 pub use engine::*;
+pub use output::Output;
 /// Useful for bringing up the TUI without bringing in the `tui` crate yourself
 pub use tui as tui_export;
-pub use utils::ticker;
+pub use crate::time::ticker;
@@ -0,0 +1,142 @@
+use std::{
+    collections::VecDeque,
+    io::{self, BufRead, BufReader, Read},
+    process::{Child, Command, Stdio},
+    sync::{Arc, Mutex},
+};
+
+#[derive(Default)]
+struct Lines {
+    buf: VecDeque<String>,
+    capacity: usize,
+}
+
+/// The textual output of a child process or any other stream, whose latest lines are shown in a pane below the messages.
+///
+/// Pass it as [`Options::output`][crate::render::tui::Options::output] to show it in the dashboard.
+/// Clones share the same lines, so one clone can be handed to the TUI while another one receives output.
+///
+/// ```no_run
+/// let output = prodash::render::tui::Output::new(1000);
+/// let child = output.spawn(std::process::Command::new("cargo").arg("build"))?;
+/// let options = prodash::render::tui::Options {
+///     output: Some(output),
+///     ..Default::default()
+/// };
+/// # drop((child, options));
+/// # Ok::<_, std::io::Error>(())
+/// ```
+#[derive(Clone)]
+pub struct Output {
+    lines: Arc<Mutex<Lines>>,
+}
+
+impl Output {
+    /// Create a new instance which keeps up to `capacity` of the latest lines.
+    pub fn new(capacity: usize) -> Self {
+        Output {
+            lines: Arc::new(Mutex::new(Lines {
+                buf: VecDeque::with_capacity(capacity.min(1024)),
+                capacity: capacity.max(1),
+            })),
+        }
+    }
+
+    /// Add `line` as the latest line, dropping the oldest line if we are at capacity.
+    ///
+    /// As the pane shows plain text, ANSI escape sequences like colors are removed, and only the text after the last
+    /// carriage return is kept as it would have overwritten what came before it in a terminal.
+    pub fn push(&self, line: impl Into<String>) {
+        let line = plain_text(&line.into());
+        if let Ok(mut lines) = self.lines.lock() {
+            if lines.buf.len() == lines.capacity {
+                lines.buf.pop_front();
+            }
+            lines.buf.push_back(line);
+        }
+    }
+
+    /// Read lines from `read` on a new thread until it's depleted or fails, and add each of them.
+    pub fn read_from(&self, read: impl Read + Send + 'static) -> io::Result<()> {
+        let output = self.clone();
+        std::thread::Builder::new()
+            .name("prodash-tui-output".into())
+            .spawn(move || {
+                let mut read = BufReader::new(read);
+                let mut line = Vec::new();
+                loop {
+                    line.clear();
+                    match read.read_until(b'\n', &mut line) {
+                        Ok(0) | Err(_) => break,
+                        Ok(_) => {
+                            let text = String::from_utf8_lossy(&line);
+                            output.push(text.trim_end_matches(['\n', '\r']));
+                        }
+                    }
+                }
+            })?;
+        Ok(())
+    }
+
+    /// Spawn `command` with its standard output and standard error piped, and add all lines it writes to either of them.
+    ///
+    /// Note that the command isn't connected to a terminal, which makes some programs change their output,
+    /// for instance by not using colors. Colors and other escape sequences written anyway are removed from each line.
+    pub fn spawn(&self, command: &mut Command) -> io::Result<Child> {
+        let mut child = command
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+        if let Some(stdout) = child.stdout.take() {
+            self.read_from(stdout)?;
+        }
+        if let Some(stderr) = child.stderr.take() {
+            self.read_from(stderr)?;
+        }
+        Ok(child)
+    }
+
+    /// Copy all lines, oldest first, into `out` after clearing it.
+    pub fn copy_lines(&self, out: &mut Vec<String>) {
+        out.clear();
+        if let Ok(lines) = self.lines.lock() {
+            out.extend(lines.buf.iter().cloned());
+        }
+    }
+}
+
+/// Return `line` without escape sequences and control characters, keeping only the text after its last carriage return,
+/// with tabs expanded to four spaces.
+fn plain_text(line: &str) -> String {
+    let line = line.trim_end_matches('\r').rsplit('\r').next().unwrap_or_default();
+    let mut out = String::with_capacity(line.len());
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\x1b' => match chars.next() {
+                // Control Sequence Introducer, like colors or cursor movements, ending with a character in `@..=~`.
+                Some('[') => {
+                    for c in chars.by_ref() {
+                        if ('@'..='~').contains(&c) {
+                            break;
+                        }
+                    }
+                }
+                // Operating System Command, like setting the title or hyperlinks, ending with BEL or ESC \.
+                Some(']') => {
+                    while let Some(c) = chars.next() {
+                        if c == '\x07' || (c == '\x1b' && chars.next_if_eq(&'\\').is_some()) {
+                            break;
+                        }
+                    }
+                }
+                _ => {}
+            },
+            '\t' => out.push_str("    "),
+            c if c.is_control() => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
@@ -0,0 +1,156 @@
+use std::{
+    fmt::Write as _,
+    io::{self, Write},
+    path::Path,
+    sync::atomic::Ordering,
+};
+
+use crate::{
+    messages::{Message, MessageLevel},
+    progress::{Key, State, Task},
+};
+
+/// Write all `entries` and `messages` to the file at `path`, as JSON if its extension is `json` or as plain text otherwise.
+pub(crate) fn write(path: &Path, title: &str, entries: &[(Key, Task)], messages: &[Message]) -> io::Result<()> {
+    let text = if path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("json")) {
+        json(title, entries, messages)
+    } else {
+        text(title, entries, messages)
+    };
+    let mut file = std::fs::File::create(path)?;
+    file.write_all(text.as_bytes())?;
+    file.flush()
+}
+
+fn progress_text(task: &Task) -> Option<String> {
+    task.progress.as_ref().map(|progress| {
+        let step = progress.step.load(Ordering::Relaxed);
+        match (&progress.unit, progress.done_at) {
+            (Some(unit), done_at) => unit.display(step, done_at, None).to_string(),
+            (None, Some(done_at)) => format!("{step}/{done_at}"),
+            (None, None) => step.to_string(),
+        }
+    })
+}
+
+fn state_and_reason(task: &Task) -> (&'static str, Option<&str>) {
+    match task.progress.as_ref().map(|p| &p.state) {
+        Some(State::Blocked(reason, _)) => ("blocked", Some(reason)),
+        Some(State::Halted(reason, _)) => ("halted", Some(reason)),
+        Some(State::Finished(true)) => ("done", None),
+        Some(State::Finished(false)) => ("failed", None),
+        Some(State::Running) | None => ("running", None),
+    }
+}
+
+fn level_name(level: MessageLevel) -> &'static str {
+    match level {
+        MessageLevel::Info => "info",
+        MessageLevel::Success => "success",
+        MessageLevel::Failure => "failure",
+    }
+}
+
+fn text(title: &str, entries: &[(Key, Task)], messages: &[Message]) -> String {
+    let mut out = String::new();
+    writeln!(out, "{title}").ok();
+    let min_level = entries.iter().map(|(key, _)| key.level()).min().unwrap_or_default();
+    for (key, task) in entries {
+        let indent = (key.level() - min_level) as usize * 2;
+        write!(out, "{:indent$}{}", "", task.name, indent = indent).ok();
+        if task.retries > 0 {
+            write!(out, " (attempt {})", task.attempt()).ok();
+        }
+        if let Some(progress) = progress_text(task) {
+            write!(out, " {progress}").ok();
+        }
+        if let (state, Some(reason)) = state_and_reason(task) {
+            write!(out, " [{state}: {reason}]").ok();
+        }
+        if let Some(note) = &task.note {
+            write!(out, " ({note})").ok();
+        }
+        out.push('\n');
+    }
+    if !messages.is_empty() {
+        out.push('\n');
+        for message in messages {
+            writeln!(
+                out,
+                "{} {:>7} {}: {}",
+                humantime::format_rfc3339_seconds(message.time),
+                level_name(message.level),
+                message.origin,
+                message.message
+            )
+            .ok();
+        }
+    }
+    out
+}
+
+fn json(title: &str, entries: &[(Key, Task)], messages: &[Message]) -> String {
+    let mut out = String::new();
+    out.push_str("{\"title\":");
+    json_string(&mut out, title);
+    out.push_str(",\"tasks\":[");
+    for (idx, (key, task)) in entries.iter().enumerate() {
+        if idx != 0 {
+            out.push(',');
+        }
+        write!(out, "{{\"level\":{},\"name\":", key.level()).ok();
+        json_string(&mut out, &task.name);
+        out.push_str(",\"progress\":");
+        json_optional_string(&mut out, progress_text(task).as_deref());
+        let (state, reason) = state_and_reason(task);
+        write!(out, ",\"state\":\"{state}\",\"reason\":").ok();
+        json_optional_string(&mut out, reason);
+        out.push_str(",\"note\":");
+        json_optional_string(&mut out, task.note.as_deref());
+        out.push('}');
+    }
+    out.push_str("],\"messages\":[");
+    for (idx, message) in messages.iter().enumerate() {
+        if idx != 0 {
+            out.push(',');
+        }
+        write!(
+            out,
+            "{{\"time\":\"{}\",\"level\":\"{}\",\"origin\":",
+            humantime::format_rfc3339_seconds(message.time),
+            level_name(message.level)
+        )
+        .ok();
+        json_string(&mut out, &message.origin.name);
+        out.push_str(",\"message\":");
+        json_string(&mut out, &message.message);
+        out.push('}');
+    }
+    out.push_str("]}\n");
+    out
+}
+
+fn json_optional_string(out: &mut String, value: Option<&str>) {
+    match value {
+        Some(value) => json_string(out, value),
+        None => out.push_str("null"),
+    }
+}
+
+fn json_string(out: &mut String, value: &str) {
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if u32::from(c) < 0x20 => {
+                write!(out, "\\u{:04x}", u32::from(c)).ok();
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
@@ -1,18 +1,34 @@
-use std::{future::Future, pin::Pin, task::Poll, time::Duration};
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    task::Poll,
+    time::{Duration, Instant},
+};
 
 use async_io::Timer;
 
-/// Returns a stream of 'ticks', each being duration `dur` apart.
-///
-/// Can be useful to provide the TUI with additional events in regular intervals,
-/// when using the [`tui::render_with_input(…events)`](./fn.render_with_input.html) function.
-pub fn ticker(dur: Duration) -> impl futures_core::Stream<Item = ()> {
+use crate::progress::{self, Key, Task};
+
+/// Like [`ticker()`][crate::time::ticker()], but the duration between ticks is read from `micros_per_tick` so it can be changed while ticking,
+/// which also affects the tick currently being waited for.
+pub(crate) fn adjustable_ticker(micros_per_tick: Arc<AtomicU64>) -> impl futures_core::Stream<Item = ()> {
+    let current = move || Duration::from_micros(micros_per_tick.load(Ordering::Relaxed));
+    let (mut started_at, mut dur) = (Instant::now(), current());
     let mut delay = Timer::after(dur);
     futures_lite::stream::poll_fn(move |ctx| {
-        let res = Pin::new(&mut delay).poll(ctx);
-        match res {
+        let latest = current();
+        if latest != dur {
+            dur = latest;
+            delay.set_at(started_at + dur);
+        }
+        match Pin::new(&mut delay).poll(ctx) {
             Poll::Pending => Poll::Pending,
             Poll::Ready(_) => {
+                started_at = Instant::now();
                 delay = Timer::after(dur);
                 Poll::Ready(Some(()))
             }
@@ -23,3 +39,61 @@ pub fn ticker(dur: Duration) -> impl futures_core::Stream<Item = ()> {
 pub const VERTICAL_LINE: &str = "│";
 
 pub use tui_react::{draw_text_nowrap_fn, draw_text_with_ellipsis_nowrap, util::*};
+
+/// Return true if the name, the note or the hexadecimal id of `task` contain `query`.
+pub(crate) fn task_matches(task: &Task, query: &str) -> bool {
+    use std::fmt::Write;
+    if task.name.contains(query) || task.note.as_deref().is_some_and(|note| note.contains(query)) {
+        return true;
+    }
+    let mut id = String::with_capacity(task.id.len() * 2);
+    for byte in task.id {
+        write!(id, "{:02x}", byte).expect("in-memory writes never fail");
+    }
+    id.contains(&query.to_ascii_lowercase())
+}
+
+/// Return `id` as ASCII if it's printable, or as hexadecimal otherwise, or `None` if it's [unknown][progress::UNKNOWN].
+pub(crate) fn format_id(id: progress::Id) -> Option<String> {
+    use std::fmt::Write;
+    if id == progress::UNKNOWN {
+        return None;
+    }
+    if id.iter().all(|b| b.is_ascii_graphic() || *b == b' ') {
+        return Some(id.iter().map(|b| *b as char).collect());
+    }
+    let mut hex = String::with_capacity(2 + id.len() * 2);
+    hex.push_str("0x");
+    for byte in id {
+        write!(hex, "{:02x}", byte).expect("in-memory writes never fail");
+    }
+    Some(hex)
+}
+
+/// Return the index of the first entry matching `query` after the one at `from`, wrapping around,
+/// or before it if `forward` is false.
+///
+/// If `include_current` is true, the entry at `from` is checked first.
+pub(crate) fn find_match(
+    entries: &[(Key, Task)],
+    query: &str,
+    from: usize,
+    forward: bool,
+    include_current: bool,
+) -> Option<usize> {
+    let len = entries.len();
+    if len == 0 || query.is_empty() {
+        return None;
+    }
+    let from = from.min(len - 1);
+    let first_distance = usize::from(!include_current);
+    (first_distance..first_distance + len)
+        .map(|distance| {
+            if forward {
+                (from + distance) % len
+            } else {
+                (from + len - distance) % len
+            }
+        })
+        .find(|idx| task_matches(&entries[*idx].1, query))
+}
@@ -0,0 +1,351 @@
+//! Populate a progress tree with randomized tasks and messages, to exercise renderers without writing a workload.
+//!
+//! ```rust
+//! let root = prodash::tree::Root::new();
+//! let workload = prodash::testing::Workload::spawn(root.clone(), Default::default())?;
+//! // … render `root` for a while …
+//! workload.shutdown_and_wait();
+//! # Ok::<_, std::io::Error>(())
+//! ```
+use std::{
+    io,
+    ops::RangeInclusive,
+    sync::{mpsc, Arc},
+    time::{Duration, Instant, SystemTime},
+};
+
+use crate::{
+    progress::Step,
+    tree::{Item, Root},
+};
+
+const WORK_STEPS_NEEDED_FOR_UNBOUNDED_TASK: u64 = 100;
+const UNITS: &[&str] = &["Mb", "kb", "items", "files"];
+const REASONS: &[&str] = &["due to star alignment", "IO takes time", "仪表板演示", "just because"];
+const WORK_NAMES: &[&str] = &[
+    "Downloading Crate",
+    "下载板条箱",
+    "Running 'cargo geiger'",
+    "运行程序 'cargo geiger'",
+    "Counting lines of code",
+    "计数代码行",
+    "Checking for unused dependencies",
+    "检查未使用的依赖项",
+    "Checking for crate-bloat",
+    "检查板条箱膨胀",
+    "Generating report",
+    "生成报告",
+];
+const DONE_MESSAGES: &[&str] = &[
+    "Yeeeehaa! Finally!!",
+    "呀！ 最后！",
+    "It feels good to be done!",
+    "感觉好极了！",
+    "Told you so!!",
+    "告诉过你了！",
+];
+const FAIL_MESSAGES: &[&str] = &[
+    "That didn't seem to work!",
+    "那似乎没有用！",
+    "Oh my… I failed you 😞",
+    "哦，我…我让你失败😞",
+    "This didn't end well…",
+    "结局不好…",
+];
+const INFO_MESSAGES: &[&str] = &[
+    "Making good progress!",
+    "进展良好！",
+    "Humming along…",
+    "嗡嗡作响…",
+    "It will be done soooooon…",
+    "会很快完成的……",
+];
+const SHORT_DELAY_MS: u64 = 50;
+const WORK_DELAY_MS: u64 = 100;
+const LONG_WORK_DELAY_MS: u64 = 2000;
+const SPAWN_DELAY_MS: u64 = 200;
+/// The deepest level of simulated hierarchies, which are rarely deeper in practice.
+const MAX_SIMULATED_LEVEL: u64 = 6;
+
+/// Configure how a [`Workload`] populates the tree.
+#[derive(Debug, Clone)]
+pub struct Options {
+    /// The factor by which all delays are divided, _(default: 1.0)_, larger values make tasks progress faster.
+    pub speed_multiplier: f32,
+    /// If true _(default: false)_, tasks are renamed at about every other step instead of rarely.
+    pub changing_names: bool,
+    /// The range of the amount of chunks of work to run concurrently _(default: 6..=16)_, each being a hierarchy of tasks.
+    ///
+    /// New chunks are created once all chunks of the previous round are done.
+    pub chunks: RangeInclusive<usize>,
+    /// The chance for a task to be blocked or halted at each step _(default: 0.01)_.
+    pub chance_to_block_per_step: f64,
+    /// The chance for a task to send an info message at each step _(default: 0.01)_.
+    pub chance_of_message_per_step: f64,
+    /// The chance for a task to fail instead of finishing successfully _(default: 0.05)_.
+    pub chance_of_failure: f64,
+    /// The seed for the random number generator, to make workloads reproducible.
+    pub seed: u64,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            speed_multiplier: 1.0,
+            changing_names: false,
+            chunks: 6..=16,
+            chance_to_block_per_step: 0.01,
+            chance_of_message_per_step: 0.01,
+            chance_of_failure: 0.05,
+            seed: 0x5eed,
+        }
+    }
+}
+
+/// A thread populating a tree with randomized tasks and messages until it is shut down or dropped.
+pub struct Workload {
+    stop: Option<mpsc::Sender<()>>,
+    handle: Option<std::thread::JoinHandle<()>>,
+}
+
+impl Workload {
+    /// Spawn a thread to add tasks to `root` and make progress on them according to `options`.
+    pub fn spawn(root: Arc<Root>, options: Options) -> io::Result<Self> {
+        let (stop, stop_recv) = mpsc::channel::<()>();
+        let handle = std::thread::Builder::new()
+            .name("prodash-workload".into())
+            .spawn(move || {
+                let mut sim = Simulation {
+                    rng: Rng::new(options.seed),
+                    options,
+                    chunks: Vec::new(),
+                };
+                loop {
+                    if sim.chunks.is_empty() {
+                        sim.start_round(&root);
+                    }
+                    let wake_at = sim.advance(Instant::now());
+                    let timeout = wake_at.saturating_duration_since(Instant::now());
+                    if let Err(mpsc::RecvTimeoutError::Disconnected) = stop_recv.recv_timeout(timeout) {
+                        break;
+                    }
+                }
+            })?;
+        Ok(Workload {
+            stop: Some(stop),
+            handle: Some(handle),
+        })
+    }
+
+    /// Stop adding progress and wait for the thread to finish, which removes all of its tasks from the tree.
+    pub fn shutdown_and_wait(mut self) {
+        self.stop_and_join();
+    }
+
+    fn stop_and_join(&mut self) {
+        self.stop.take();
+        if let Some(handle) = self.handle.take() {
+            handle.join().ok();
+        }
+    }
+}
+
+impl Drop for Workload {
+    fn drop(&mut self) {
+        self.stop_and_join();
+    }
+}
+
+/// A task making progress, one step at a time.
+struct Task {
+    item: Item,
+    step: u64,
+    max: u64,
+    wake_at: Instant,
+}
+
+/// A hierarchy of tasks, with `max_level` levels each holding `max_level * 2` tasks which are spawned one after another.
+struct Chunk {
+    levels: Vec<Item>,
+    max_level: u8,
+    spawned_on_level: usize,
+    next_spawn_at: Instant,
+    tasks: Vec<Task>,
+}
+
+impl Chunk {
+    fn all_spawned(&self) -> bool {
+        self.levels.len() == self.max_level as usize && self.spawned_on_level == self.max_level as usize * 2
+    }
+}
+
+struct Simulation {
+    rng: Rng,
+    options: Options,
+    chunks: Vec<Chunk>,
+}
+
+impl Simulation {
+    fn delay(&self, ms: u64) -> Duration {
+        Duration::from_millis((ms as f32 / self.options.speed_multiplier) as u64)
+    }
+
+    fn start_round(&mut self, root: &Root) {
+        let num_chunks = self
+            .rng
+            .range(*self.options.chunks.start() as u64..=*self.options.chunks.end() as u64);
+        let now = Instant::now();
+        for _ in 0..num_chunks.max(1) {
+            let max_level = self.rng.range(1..=MAX_SIMULATED_LEVEL) as u8;
+            self.chunks.push(Chunk {
+                levels: vec![root.add_child(format!("level {} of {}", 1, max_level))],
+                max_level,
+                spawned_on_level: 0,
+                next_spawn_at: now,
+                tasks: Vec::new(),
+            });
+        }
+    }
+
+    /// Make progress on all chunks whose time has come, and return the time at which to call this method next.
+    fn advance(&mut self, now: Instant) -> Instant {
+        let mut chunks = std::mem::take(&mut self.chunks);
+        let mut wake_at = now + self.delay(LONG_WORK_DELAY_MS);
+        for chunk in &mut chunks {
+            self.spawn_tasks(chunk, now);
+            let mut idx = 0;
+            while idx < chunk.tasks.len() {
+                if chunk.tasks[idx].wake_at <= now && !self.step(&mut chunk.tasks[idx], now) {
+                    chunk.tasks.swap_remove(idx);
+                    continue;
+                }
+                wake_at = wake_at.min(chunk.tasks[idx].wake_at);
+                idx += 1;
+            }
+            if !chunk.all_spawned() {
+                wake_at = wake_at.min(chunk.next_spawn_at);
+            }
+        }
+        chunks.retain(|chunk| !(chunk.all_spawned() && chunk.tasks.is_empty()));
+        self.chunks = chunks;
+        wake_at
+    }
+
+    fn spawn_tasks(&mut self, chunk: &mut Chunk, now: Instant) {
+        while !chunk.all_spawned() && chunk.next_spawn_at <= now {
+            if chunk.spawned_on_level == chunk.max_level as usize * 2 {
+                let level = chunk.levels.len();
+                let next_level = chunk
+                    .levels
+                    .last_mut()
+                    .expect("levels are not empty")
+                    .add_child(format!("Level {level}"));
+                chunk.levels.push(next_level);
+                chunk.spawned_on_level = 0;
+            }
+            let name = format!("{} {}", self.rng.choose(WORK_NAMES), chunk.spawned_on_level + 1);
+            let item = chunk.levels.last_mut().expect("levels are not empty").add_child(name);
+            chunk.tasks.push(self.new_task(item, now));
+            chunk.spawned_on_level += 1;
+            chunk.next_spawn_at = now + self.delay(SPAWN_DELAY_MS);
+        }
+    }
+
+    fn new_task(&mut self, item: Item, now: Instant) -> Task {
+        let max = self.rng.range(25..=125);
+        item.init(
+            if max > WORK_STEPS_NEEDED_FOR_UNBOUNDED_TASK {
+                None
+            } else {
+                Some(max as Step)
+            },
+            if self.rng.bool(0.2) {
+                None
+            } else {
+                Some(self.rng.choose(UNITS).into())
+            },
+        );
+        Task {
+            item,
+            step: 0,
+            max,
+            wake_at: now,
+        }
+    }
+
+    /// Make one step of progress on `task`, returning false once it is done.
+    fn step(&mut self, task: &mut Task, now: Instant) -> bool {
+        let item = &mut task.item;
+        if task.step == task.max {
+            if self.rng.bool(self.options.chance_of_failure) {
+                item.fail(self.rng.choose(FAIL_MESSAGES));
+            } else {
+                item.done(self.rng.choose(DONE_MESSAGES));
+            }
+            return false;
+        }
+        item.set(task.step as Step);
+        let delay_ms = if self.rng.bool(self.options.chance_to_block_per_step) {
+            let eta = if self.rng.bool(0.5) {
+                Some(SystemTime::now() + self.delay(LONG_WORK_DELAY_MS))
+            } else {
+                None
+            };
+            let reason = self.rng.choose(REASONS);
+            if self.rng.bool(0.5) {
+                item.halted(reason, eta);
+            } else {
+                item.blocked(reason, eta);
+            }
+            self.rng.range(WORK_DELAY_MS..=LONG_WORK_DELAY_MS)
+        } else {
+            self.rng.range(SHORT_DELAY_MS..=WORK_DELAY_MS)
+        };
+        if self.rng.bool(0.01) {
+            item.init(Some(task.max as Step), Some(self.rng.choose(UNITS).into()));
+        }
+        if self.rng.bool(self.options.chance_of_message_per_step) {
+            item.info(self.rng.choose(INFO_MESSAGES));
+        }
+        if self.rng.bool(if self.options.changing_names { 0.5 } else { 0.01 }) {
+            item.set_name(self.rng.choose(WORK_NAMES).to_string());
+        }
+        task.step += 1;
+        task.wake_at = now + self.delay(delay_ms);
+        true
+    }
+}
+
+/// A small xorshift random number generator, good enough to make workloads look lively.
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        Rng(seed.max(1))
+    }
+
+    fn next(&mut self) -> u64 {
+        let mut x = self.0;
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.0 = x;
+        x
+    }
+
+    fn range(&mut self, range: RangeInclusive<u64>) -> u64 {
+        let (start, end) = (*range.start(), *range.end());
+        if end <= start {
+            return start;
+        }
+        start + self.next() % (end - start + 1)
+    }
+
+    fn bool(&mut self, chance: f64) -> bool {
+        ((self.next() >> 11) as f64 / (1u64 << 53) as f64) < chance
+    }
+
+    fn choose<'a>(&mut self, items: &[&'a str]) -> &'a str {
+        items[self.range(0..=items.len() as u64 - 1) as usize]
+    }
+}
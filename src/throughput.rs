@@ -1,15 +1,18 @@
 use std::{
     collections::VecDeque,
-    sync::atomic::Ordering,
+    io,
+    sync::{atomic::Ordering, mpsc},
     time::{Duration, SystemTime},
 };
 
-use crate::{progress, unit};
+use crate::{progress, unit, Root, WeakRoot};
 
 const THROTTLE_INTERVAL: Duration = Duration::from_secs(1);
 const ONCE_A_SECOND: Duration = Duration::from_secs(1);
+/// The weight of the latest throughput in the smoothed throughput that ETAs are based on.
+const ETA_SMOOTHING: f64 = 0.2;
 
-#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Debug)]
+#[derive(Clone, PartialEq, Debug)]
 struct State {
     observed: Duration,
     last_value: progress::Step,
@@ -17,6 +20,11 @@ struct State {
 
     last_update_duration: Duration,
     precomputed_throughput: Option<progress::Step>,
+    peak_throughput: Option<progress::Step>,
+    /// The exponentially smoothed throughput per second, to keep ETAs from jumping around.
+    smoothed_throughput: Option<f64>,
+    /// The amount of steps left until the task is done, if it is bounded and running.
+    remaining: Option<progress::Step>,
 }
 
 impl State {
@@ -32,6 +40,9 @@ impl State {
 
             last_update_duration: elapsed,
             precomputed_throughput: None,
+            peak_throughput: None,
+            smoothed_throughput: None,
+            remaining: None,
         }
     }
 
@@ -59,7 +70,13 @@ impl State {
             .push_back((elapsed, value.saturating_sub(self.last_value)));
         self.last_value = value;
         if self.observed - self.last_update_duration > THROTTLE_INTERVAL {
-            self.precomputed_throughput = Some(self.compute_throughput());
+            let throughput = self.compute_throughput();
+            self.precomputed_throughput = Some(throughput);
+            self.peak_throughput = self.peak_throughput.max(Some(throughput));
+            self.smoothed_throughput = Some(match self.smoothed_throughput {
+                Some(smoothed) => smoothed + ETA_SMOOTHING * (throughput as f64 - smoothed),
+                None => throughput as f64,
+            });
             self.last_update_duration = self.observed;
         }
         self.throughput()
@@ -69,6 +86,7 @@ impl State {
         self.precomputed_throughput.map(|tp| unit::display::Throughput {
             value_change_in_timespan: tp,
             timespan: ONCE_A_SECOND,
+            peak_value_change_in_timespan: self.peak_throughput,
         })
     }
 }
@@ -90,35 +108,140 @@ impl Throughput {
         self.updated_at = Some(now);
     }
 
+    /// Forget when progress values were last updated, so that the time until the next call to
+    /// [`update_elapsed()`][Throughput::update_elapsed()] isn't accounted for.
+    ///
+    /// Call it instead of `update_elapsed()` while the [clock is paused][crate::Root::is_clock_paused()].
+    pub fn skip_elapsed(&mut self) {
+        self.elapsed = None;
+        self.updated_at = None;
+    }
+
     /// Lookup or create the progress value at `key` and set its current `progress`, returning its computed
     /// throughput.
+    ///
+    /// The throughput is also stored in the [rate][progress::Value::rate] of `progress` for others to read.
     pub fn update_and_get(
         &mut self,
         key: &progress::Key,
         progress: Option<&progress::Value>,
     ) -> Option<unit::display::Throughput> {
         progress.and_then(|progress| {
-            self.elapsed
-                .and_then(|elapsed| match self.sorted_by_key.binary_search_by_key(key, |t| t.0) {
-                    Ok(index) => self.sorted_by_key[index]
-                        .1
-                        .update(progress.step.load(Ordering::SeqCst), elapsed),
+            let step = progress.step.load(Ordering::SeqCst);
+            let tp = self.elapsed.and_then(|elapsed| {
+                let (state, tp) = match self.sorted_by_key.binary_search_by(|t| t.0.cmp(key)) {
+                    Ok(index) => {
+                        let state = &mut self.sorted_by_key[index].1;
+                        let tp = state.update(step, elapsed);
+                        (state, tp)
+                    }
                     Err(index) => {
-                        let state = State::new(progress.step.load(Ordering::SeqCst), elapsed);
+                        self.sorted_by_key
+                            .insert(index, (key.clone(), State::new(step, elapsed)));
+                        let state = &mut self.sorted_by_key[index].1;
                         let tp = state.throughput();
-                        self.sorted_by_key.insert(index, (*key, state));
-                        tp
+                        (state, tp)
                     }
-                })
+                };
+                state.remaining = progress
+                    .max()
+                    .filter(|_| progress.state == progress::State::Running)
+                    .map(|max| max.saturating_sub(step));
+                tp
+            });
+            if let Some(tp) = tp.as_ref().filter(|tp| !tp.timespan.is_zero()) {
+                let rate = tp.value_change_in_timespan as f64 / tp.timespan.as_secs_f64();
+                progress.rate.store(rate.to_bits(), Ordering::Relaxed);
+            }
+            tp
         })
     }
 
-    /// Compare the keys in `sorted_values` with our internal state and remove all missing tasks from it.
+    /// Return the estimated time until the bounded task at `key` is done, based on its remaining steps and its
+    /// smoothed throughput.
+    ///
+    /// It's known once the throughput of the task was computed, which takes about a second of calls to
+    /// [`update_and_get(…)`][Throughput::update_and_get()], and as long as the task is running and makes progress.
+    pub fn eta(&self, key: &progress::Key) -> Option<Duration> {
+        let index = self.sorted_by_key.binary_search_by(|t| t.0.cmp(key)).ok()?;
+        let state = &self.sorted_by_key[index].1;
+        let remaining = state.remaining?;
+        let rate = state.smoothed_throughput.filter(|rate| *rate > 0.0)?;
+        Duration::try_from_secs_f64(remaining as f64 / rate * ONCE_A_SECOND.as_secs_f64()).ok()
+    }
+
+    /// Compare the keys in `values` with our internal state and remove all missing tasks from it.
     ///
     /// This should be called after [`update_and_get(…)`][Throughput::update_and_get()] to pick up removed/finished
     /// progress.
-    pub fn reconcile(&mut self, sorted_values: &[(progress::Key, progress::Task)]) {
-        self.sorted_by_key
-            .retain(|(key, _)| sorted_values.binary_search_by_key(key, |e| e.0).is_ok());
+    /// Note that `values` don't have to be sorted by key, as siblings may be displayed in a [different order][progress::ChildOrder].
+    pub fn reconcile(&mut self, values: &[(progress::Key, progress::Task)]) {
+        let mut keys: Vec<_> = values.iter().map(|(key, _)| key.clone()).collect();
+        keys.sort_unstable();
+        self.sorted_by_key.retain(|(key, _)| keys.binary_search(key).is_ok());
+    }
+}
+
+/// A background thread that periodically samples all tasks of a progress tree with a [`Throughput`] instance.
+///
+/// This keeps the [rate][progress::Value::rate()] of all tasks up to date even if renderers draw rarely, or if there is
+/// no renderer at all, which is useful for exporting metrics.
+///
+/// The thread stops once the progress tree is dropped, or once this instance is dropped.
+pub struct ThroughputSampler {
+    stop: Option<mpsc::Sender<()>>,
+    handle: Option<std::thread::JoinHandle<()>>,
+}
+
+impl ThroughputSampler {
+    /// Spawn a thread to sample all tasks of `progress` every `interval`.
+    ///
+    /// Note that rates are computed over a window of about a second, so intervals much larger than that reduce accuracy.
+    pub fn spawn(progress: impl WeakRoot + Send + 'static, interval: Duration) -> io::Result<Self> {
+        let (stop, stop_recv) = mpsc::channel::<()>();
+        let handle = std::thread::Builder::new()
+            .name("throughput-sampler".into())
+            .spawn(move || {
+                let mut throughput = Throughput::default();
+                let mut tasks = Vec::new();
+                while let Err(mpsc::RecvTimeoutError::Timeout) = stop_recv.recv_timeout(interval) {
+                    let progress = match progress.upgrade() {
+                        Some(progress) => progress,
+                        None => break,
+                    };
+                    progress.sorted_snapshot(&mut tasks);
+                    if progress.is_clock_paused() {
+                        throughput.skip_elapsed();
+                    } else {
+                        throughput.update_elapsed();
+                    }
+                    for (key, task) in &tasks {
+                        throughput.update_and_get(key, task.progress.as_ref());
+                    }
+                    throughput.reconcile(&tasks);
+                }
+            })?;
+        Ok(ThroughputSampler {
+            stop: Some(stop),
+            handle: Some(handle),
+        })
+    }
+
+    /// Stop the sampler thread and wait for it to finish.
+    pub fn shutdown_and_wait(mut self) {
+        self.stop_and_join();
+    }
+
+    fn stop_and_join(&mut self) {
+        self.stop.take();
+        if let Some(handle) = self.handle.take() {
+            handle.join().ok();
+        }
+    }
+}
+
+impl Drop for ThroughputSampler {
+    fn drop(&mut self) {
+        self.stop_and_join();
     }
 }
@@ -1,8 +1,12 @@
+use std::time::SystemTime;
+
 #[cfg(feature = "local-time")]
 mod localtime {
     use std::time::SystemTime;
 
-    use jiff::Zoned;
+    use jiff::{tz::TimeZone, Zoned};
+
+    use super::Timezone;
 
     /// Return a string representing the current date and time as localtime.
     ///
@@ -11,14 +15,14 @@ mod localtime {
         Zoned::now().strftime("%F %T %Z").to_string()
     }
 
-    /// Return a string representing the current time as localtime.
-    ///
-    /// Available with the `localtime` feature toggle.
-    pub fn format_time_for_messages(time: SystemTime) -> String {
-        Zoned::try_from(time)
-            .expect("system time is always in range -9999-01-01..=9999-12-31")
-            .strftime("%T")
-            .to_string()
+    pub(crate) fn strftime(time: SystemTime, timezone: Timezone, format: &str) -> String {
+        let timestamp =
+            jiff::Timestamp::try_from(time).expect("system time is always in range -9999-01-01..=9999-12-31");
+        let tz = match timezone {
+            Timezone::Utc => TimeZone::UTC,
+            Timezone::Local => TimeZone::system(),
+        };
+        timestamp.to_zoned(tz).strftime(format).to_string()
     }
 }
 
@@ -27,21 +31,9 @@ pub const DATE_TIME_HMS: usize = "00:51:45".len();
 
 #[cfg(not(feature = "local-time"))]
 mod utc {
-    use std::time::SystemTime;
-
-    use super::DATE_TIME_HMS;
-    const DATE_TIME_YMD: usize = "2020-02-13T".len();
+    use std::{fmt::Write, time::SystemTime};
 
-    /// Return a string representing the current date and time as UTC.
-    ///
-    /// Available without the `localtime` feature toggle.
-    pub fn format_time_for_messages(time: SystemTime) -> String {
-        String::from_utf8_lossy(
-            &humantime::format_rfc3339_seconds(time).to_string().as_bytes()
-                [DATE_TIME_YMD..DATE_TIME_YMD + DATE_TIME_HMS],
-        )
-        .into_owned()
-    }
+    use super::Timezone;
 
     /// Return a string representing the current time as UTC.
     ///
@@ -54,9 +46,197 @@ mod utc {
         )
         .into_owned()
     }
+
+    /// Format `time` as UTC according to `format`, which supports only `%Y`, `%m`, `%d`, `%H`, `%M`, `%S`, `%F`, `%T`,
+    /// `%3f`, `%.3f` and `%%` as the timezone database isn't available.
+    pub(crate) fn strftime(time: SystemTime, _timezone: Timezone, format: &str) -> String {
+        let since_epoch = time.duration_since(SystemTime::UNIX_EPOCH).unwrap_or_default();
+        let secs = since_epoch.as_secs();
+        let (year, month, day) = civil_from_days((secs / 86_400) as i64);
+        let (hour, minute, second) = (secs / 3600 % 24, secs / 60 % 60, secs % 60);
+        let millis = since_epoch.subsec_millis();
+
+        let mut out = String::with_capacity(format.len() * 2);
+        let mut chars = format.chars();
+        while let Some(c) = chars.next() {
+            if c != '%' {
+                out.push(c);
+                continue;
+            }
+            let rest = chars.as_str();
+            let (written, skip) = if rest.starts_with(".3f") {
+                (write!(out, ".{millis:03}"), 3)
+            } else if rest.starts_with("3f") {
+                (write!(out, "{millis:03}"), 2)
+            } else {
+                match rest.chars().next() {
+                    Some('Y') => (write!(out, "{year:04}"), 1),
+                    Some('m') => (write!(out, "{month:02}"), 1),
+                    Some('d') => (write!(out, "{day:02}"), 1),
+                    Some('H') => (write!(out, "{hour:02}"), 1),
+                    Some('M') => (write!(out, "{minute:02}"), 1),
+                    Some('S') => (write!(out, "{second:02}"), 1),
+                    Some('F') => (write!(out, "{year:04}-{month:02}-{day:02}"), 1),
+                    Some('T') => (write!(out, "{hour:02}:{minute:02}:{second:02}"), 1),
+                    Some('%') => (write!(out, "%"), 1),
+                    _ => (write!(out, "%"), 0),
+                }
+            };
+            written.ok();
+            for _ in 0..skip {
+                chars.next();
+            }
+        }
+        out
+    }
+
+    /// Convert the amount of days since the unix epoch into year, month and day.
+    fn civil_from_days(days: i64) -> (i64, u32, u32) {
+        let days = days + 719_468;
+        let era = days.div_euclid(146_097);
+        let day_of_era = days.rem_euclid(146_097);
+        let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let mp = (5 * day_of_year + 2) / 153;
+        let day = (day_of_year - (153 * mp + 2) / 5 + 1) as u32;
+        let month = (if mp < 10 { mp + 3 } else { mp - 9 }) as u32;
+        let year = year_of_era + era * 400 + i64::from(month <= 2);
+        (year, month, day)
+    }
 }
 
+#[cfg(feature = "time-ticker")]
+mod ticker {
+    use std::{
+        collections::hash_map::RandomState,
+        future::Future,
+        hash::{BuildHasher, Hasher},
+        pin::Pin,
+        task::Poll,
+        time::Duration,
+    };
+
+    use async_io::Timer;
+
+    /// Returns a stream of 'ticks', each being duration `dur` apart.
+    ///
+    /// Can be useful to drive event streams in regular intervals, like the ones passed to
+    /// [`tui::render_with_input(…events)`][crate::render::tui::render_with_input()].
+    ///
+    /// Available with the `time-ticker` feature toggle.
+    pub fn ticker(dur: Duration) -> impl futures_core::Stream<Item = ()> {
+        ticker_with_jitter(dur, Duration::ZERO)
+    }
+
+    /// Like [`ticker()`], but each tick is up to `jitter` earlier or later than `dur` after the previous one, chosen
+    /// at random, while ticking at the same average rate.
+    ///
+    /// This keeps many processes started at the same time from doing their work at the very same moments.
+    ///
+    /// Available with the `time-ticker` feature toggle.
+    pub fn ticker_with_jitter(dur: Duration, jitter: Duration) -> impl futures_core::Stream<Item = ()> {
+        let next_delay = move || {
+            if jitter.is_zero() {
+                return dur;
+            }
+            let offset = Duration::from_nanos(random() % (jitter.as_nanos() as u64 * 2 + 1));
+            (dur + offset).saturating_sub(jitter)
+        };
+        let mut delay = Timer::after(next_delay());
+        futures_lite::stream::poll_fn(move |ctx| {
+            let res = Pin::new(&mut delay).poll(ctx);
+            match res {
+                Poll::Pending => Poll::Pending,
+                Poll::Ready(_) => {
+                    delay = Timer::after(next_delay());
+                    Poll::Ready(Some(()))
+                }
+            }
+        })
+    }
+
+    /// Return a random number, good enough to spread ticks, without needing a dependency for it.
+    fn random() -> u64 {
+        RandomState::new().build_hasher().finish()
+    }
+}
+
+#[cfg(feature = "time-ticker")]
+pub use ticker::{ticker, ticker_with_jitter};
+
+#[cfg(feature = "local-time")]
+pub use localtime::format_now_datetime_seconds;
 #[cfg(feature = "local-time")]
-pub use localtime::*;
+use localtime::strftime;
+#[cfg(not(feature = "local-time"))]
+pub use utc::format_now_datetime_seconds;
 #[cfg(not(feature = "local-time"))]
-pub use utc::*;
+use utc::strftime;
+
+/// The timezone in which to show the time of messages.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "config", derive(serde::Deserialize), serde(rename_all = "kebab-case"))]
+pub enum Timezone {
+    /// Coordinated universal time.
+    Utc,
+    /// The local time of the system, which needs the `local-time` feature toggle and falls back to UTC without it.
+    Local,
+}
+
+impl Default for Timezone {
+    /// Local time with the `local-time` feature toggle, and UTC otherwise.
+    fn default() -> Self {
+        if cfg!(feature = "local-time") {
+            Timezone::Local
+        } else {
+            Timezone::Utc
+        }
+    }
+}
+
+/// The way renderers format the time of messages.
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "config", derive(serde::Deserialize), serde(rename_all = "kebab-case"))]
+pub enum TimestampFormat {
+    /// Hours, minutes and seconds, like `13:37:42`.
+    #[default]
+    Seconds,
+    /// Hours, minutes, seconds and milliseconds, like `13:37:42.042`.
+    Millis,
+    /// The seconds since the renderer started, like `+3.2s`.
+    Relative,
+    /// The seconds since the first message was shown, like `+3.2s`, which is easy to correlate with durations in the messages.
+    ///
+    /// Until there is a first message, the time at which the renderer started is used.
+    RelativeToFirstMessage,
+    /// A custom `strftime` format string, like `%F %T`.
+    ///
+    /// Without the `local-time` feature toggle, only `%Y`, `%m`, `%d`, `%H`, `%M`, `%S`, `%F`, `%T`, `%3f`, `%.3f` and `%%`
+    /// are supported.
+    Custom(String),
+}
+
+impl TimestampFormat {
+    /// Return the point of reference for relative timestamps, which is either `renderer_start` or `first_message`,
+    /// if known, to be passed to [`format()`][TimestampFormat::format()].
+    pub fn start(&self, renderer_start: SystemTime, first_message: Option<SystemTime>) -> SystemTime {
+        match self {
+            TimestampFormat::RelativeToFirstMessage => first_message.unwrap_or(renderer_start),
+            _ => renderer_start,
+        }
+    }
+
+    /// Format `time` in the given `timezone`, using `start` as the point of reference for [relative][TimestampFormat::Relative]
+    /// timestamps.
+    pub fn format(&self, time: SystemTime, timezone: Timezone, start: SystemTime) -> String {
+        match self {
+            TimestampFormat::Seconds => strftime(time, timezone, "%T"),
+            TimestampFormat::Millis => strftime(time, timezone, "%T%.3f"),
+            TimestampFormat::Relative | TimestampFormat::RelativeToFirstMessage => match time.duration_since(start) {
+                Ok(elapsed) => format!("+{:.1}s", elapsed.as_secs_f64()),
+                Err(err) => format!("-{:.1}s", err.duration().as_secs_f64()),
+            },
+            TimestampFormat::Custom(format) => strftime(time, timezone, format),
+        }
+    }
+}
@@ -108,6 +108,22 @@ pub trait Progress: Count + Send + Sync {
         None
     }
 
+    /// Return an atomic holding the maximum for direct access to the underlying state, or `None` if that isn't supported.
+    ///
+    /// This is useful if the amount of work is learned late on a thread other than the one owning this instance,
+    /// as storing into it updates the bound without any locking. [`Step::MAX`][progress::Step] makes the progress unbounded.
+    fn max_counter(&self) -> Option<progress::StepShared> {
+        None
+    }
+
+    /// Return `true` if the user requested this task to stop, for example from a renderer, which is never the case
+    /// if that isn't supported.
+    ///
+    /// Long-running tasks can check it regularly to abort early, without affecting other tasks.
+    fn is_cancelled(&self) -> bool {
+        false
+    }
+
     /// Set the name of the instance, altering the value given when crating it with `add_child(…)`
     /// The progress is allowed to discard it.
     fn set_name(&mut self, name: String);
@@ -140,22 +156,32 @@ pub trait Progress: Count + Send + Sync {
     }
     /// A shorthand to print throughput information
     fn show_throughput(&self, start: Instant) {
+        self.info(self.format_throughput(start))
+    }
+
+    /// A shorthand to print throughput information, with the given step and unit, and message level.
+    fn show_throughput_with(&self, start: Instant, step: progress::Step, unit: Unit, level: MessageLevel) {
+        self.message(level, self.format_throughput_with(start, step, unit))
+    }
+
+    /// Return the throughput information that [`show_throughput()`][Progress::show_throughput()] would emit as message.
+    ///
+    /// This is useful to display it elsewhere, like in a summary at the end of the program.
+    fn format_throughput(&self, start: Instant) -> String {
         let step = self.step();
         match self.unit() {
-            Some(unit) => self.show_throughput_with(start, step, unit, MessageLevel::Info),
+            Some(unit) => self.format_throughput_with(start, step, unit),
             None => {
                 let elapsed = start.elapsed().as_secs_f32();
                 let steps_per_second = (step as f32 / elapsed) as progress::Step;
-                self.info(format!(
-                    "done {} items in {:.02}s ({} items/s)",
-                    step, elapsed, steps_per_second
-                ))
+                format!("done {} items in {:.02}s ({} items/s)", step, elapsed, steps_per_second)
             }
-        };
+        }
     }
 
-    /// A shorthand to print throughput information, with the given step and unit, and message level.
-    fn show_throughput_with(&self, start: Instant, step: progress::Step, unit: Unit, level: MessageLevel) {
+    /// Return the throughput information that [`show_throughput_with()`][Progress::show_throughput_with()] would emit
+    /// as message, with the given step and unit.
+    fn format_throughput_with(&self, start: Instant, step: progress::Step, unit: Unit) -> String {
         use std::fmt::Write;
         let elapsed = start.elapsed().as_secs_f32();
         let steps_per_second = (step as f32 / elapsed) as progress::Step;
@@ -178,8 +204,18 @@ pub trait Progress: Count + Send + Sync {
         unit.display_current_value(&mut buf, steps_per_second, None).ok();
         push_unit(&mut buf);
         buf.push_str("/s)");
+        buf
+    }
 
-        self.message(level, buf);
+    /// Wrap this instance to send each message with the level `map` returns for its original level and the message.
+    ///
+    /// See [`MapMessageLevel`][progress::MapMessageLevel] for details.
+    fn map_message_level<F>(self, map: F) -> progress::MapMessageLevel<Self, F>
+    where
+        Self: Sized,
+        F: Fn(MessageLevel, &str) -> MessageLevel + Send + Sync,
+    {
+        progress::MapMessageLevel::new(self, map)
     }
 }
 
@@ -226,6 +262,32 @@ pub trait Root {
 
     /// Similar to `Arc::downgrade()`
     fn downgrade(&self) -> Self::WeakRoot;
+
+    /// Returns true if time shouldn't be accounted for, for example while the system is suspended.
+    ///
+    /// Renderers exclude such periods when computing throughput.
+    fn is_clock_paused(&self) -> bool {
+        false
+    }
+
+    /// Returns the time the run this root tracks started at, if known.
+    ///
+    /// Renderers show the time elapsed since then, or since they were started if it's unknown.
+    fn started_at(&self) -> Option<std::time::SystemTime> {
+        None
+    }
+
+    /// Returns the amount of changes made to the tasks so far if known, which is cheap to compare with a previous value
+    /// to learn if anything changed without taking a snapshot.
+    fn mutations(&self) -> Option<u64> {
+        None
+    }
+
+    /// Returns the amount of messages dropped so far instead of storing them, for instance as the task sending them
+    /// exceeded a rate limit.
+    fn suppressed_messages(&self) -> usize {
+        0
+    }
 }
 
 mod impls {
@@ -234,7 +296,7 @@ mod impls {
         time::Instant,
     };
 
-    use crate::traits::{BoxedProgress, Progress};
+    use crate::traits::Progress;
     use crate::{
         messages::MessageLevel,
         progress::{Id, Step, StepShared},
@@ -313,6 +375,14 @@ mod impls {
             self.deref_mut().set_max(max)
         }
 
+        fn max_counter(&self) -> Option<StepShared> {
+            self.deref().max_counter()
+        }
+
+        fn is_cancelled(&self) -> bool {
+            self.deref().is_cancelled()
+        }
+
         fn set_name(&mut self, name: String) {
             self.deref_mut().set_name(name)
         }
@@ -348,6 +418,14 @@ mod impls {
         fn show_throughput_with(&self, start: Instant, step: Step, unit: Unit, level: MessageLevel) {
             self.deref().show_throughput_with(start, step, unit, level)
         }
+
+        fn format_throughput(&self, start: Instant) -> String {
+            self.deref().format_throughput(start)
+        }
+
+        fn format_throughput_with(&self, start: Instant, step: Step, unit: Unit) -> String {
+            self.deref().format_throughput_with(start, step, unit)
+        }
     }
 
     impl<'a, T> NestedProgress for &'a mut T
@@ -405,6 +483,14 @@ mod impls {
             self.0.set_max(max)
         }
 
+        fn max_counter(&self) -> Option<StepShared> {
+            self.0.max_counter()
+        }
+
+        fn is_cancelled(&self) -> bool {
+            self.0.is_cancelled()
+        }
+
         fn set_name(&mut self, name: String) {
             self.0.set_name(name)
         }
@@ -421,6 +507,14 @@ mod impls {
             self.0.message(level, message)
         }
 
+        fn done(&self, message: String) {
+            self.0.done(message)
+        }
+
+        fn fail(&self, message: String) {
+            self.0.fail(message)
+        }
+
         fn show_throughput(&self, start: Instant) {
             self.0.show_throughput(start)
         }
@@ -428,9 +522,20 @@ mod impls {
         fn show_throughput_with(&self, start: Instant, step: Step, unit: Unit, level: MessageLevel) {
             self.0.show_throughput_with(start, step, unit, level)
         }
+
+        fn format_throughput(&self, start: Instant) -> String {
+            self.0.format_throughput(start)
+        }
+
+        fn format_throughput_with(&self, start: Instant, step: Step, unit: Unit) -> String {
+            self.0.format_throughput_with(start, step, unit)
+        }
     }
 
-    impl Progress for BoxedProgress {
+    impl<T> Progress for Box<T>
+    where
+        T: Progress + ?Sized,
+    {
         fn init(&mut self, max: Option<Step>, unit: Option<Unit>) {
             self.deref_mut().init(max, unit)
         }
@@ -447,6 +552,14 @@ mod impls {
             self.deref_mut().set_max(max)
         }
 
+        fn max_counter(&self) -> Option<StepShared> {
+            self.deref().max_counter()
+        }
+
+        fn is_cancelled(&self) -> bool {
+            self.deref().is_cancelled()
+        }
+
         fn set_name(&mut self, name: String) {
             self.deref_mut().set_name(name)
         }
@@ -463,6 +576,18 @@ mod impls {
             self.deref().message(level, message)
         }
 
+        fn info(&self, message: String) {
+            self.deref().info(message)
+        }
+
+        fn done(&self, message: String) {
+            self.deref().done(message)
+        }
+
+        fn fail(&self, message: String) {
+            self.deref().fail(message)
+        }
+
         fn show_throughput(&self, start: Instant) {
             self.deref().show_throughput(start)
         }
@@ -470,6 +595,29 @@ mod impls {
         fn show_throughput_with(&self, start: Instant, step: Step, unit: Unit, level: MessageLevel) {
             self.deref().show_throughput_with(start, step, unit, level)
         }
+
+        fn format_throughput(&self, start: Instant) -> String {
+            self.deref().format_throughput(start)
+        }
+
+        fn format_throughput_with(&self, start: Instant, step: Step, unit: Unit) -> String {
+            self.deref().format_throughput_with(start, step, unit)
+        }
+    }
+
+    impl<T> NestedProgress for Box<T>
+    where
+        T: NestedProgress + ?Sized,
+    {
+        type SubProgress = T::SubProgress;
+
+        fn add_child(&mut self, name: impl Into<String>) -> Self::SubProgress {
+            self.deref_mut().add_child(name)
+        }
+
+        fn add_child_with_id(&mut self, name: impl Into<String>, id: Id) -> Self::SubProgress {
+            self.deref_mut().add_child_with_id(name, id)
+        }
     }
 
     impl Count for BoxedDynNestedProgress {
@@ -494,7 +642,10 @@ mod impls {
         }
     }
 
-    impl Count for BoxedProgress {
+    impl<T> Count for Box<T>
+    where
+        T: Count + ?Sized,
+    {
         fn set(&self, step: Step) {
             self.deref().set(step)
         }
@@ -548,6 +699,14 @@ mod impls {
             self.0.set_max(max)
         }
 
+        fn max_counter(&self) -> Option<StepShared> {
+            self.0.max_counter()
+        }
+
+        fn is_cancelled(&self) -> bool {
+            self.0.is_cancelled()
+        }
+
         fn set_name(&mut self, name: String) {
             self.0.set_name(name)
         }
@@ -564,6 +723,14 @@ mod impls {
             self.0.message(level, message)
         }
 
+        fn done(&self, message: String) {
+            self.0.done(message)
+        }
+
+        fn fail(&self, message: String) {
+            self.0.fail(message)
+        }
+
         fn show_throughput(&self, start: Instant) {
             self.0.show_throughput(start)
         }
@@ -571,6 +738,14 @@ mod impls {
         fn show_throughput_with(&self, start: Instant, step: Step, unit: Unit, level: MessageLevel) {
             self.0.show_throughput_with(start, step, unit, level)
         }
+
+        fn format_throughput(&self, start: Instant) -> String {
+            self.0.format_throughput(start)
+        }
+
+        fn format_throughput_with(&self, start: Instant, step: Step, unit: Unit) -> String {
+            self.0.format_throughput_with(start, step, unit)
+        }
     }
 
     impl<T> Count for DynNestedProgressToNestedProgress<T>
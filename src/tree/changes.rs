@@ -0,0 +1,181 @@
+use std::{
+    sync::{
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+        Arc,
+    },
+    task::Waker,
+};
+
+use parking_lot::{Condvar, Mutex};
+
+use crate::{
+    progress::{Key, State},
+    tree::{subscription::Queue, ProgressEvent, Subscription, TaskCounts},
+};
+
+/// Allows threads and futures to wait for tasks to be added to or removed from the tree, without polling.
+///
+/// It also counts tasks by state and all mutations of the tree, so these can be read without locking it, and
+/// delivers events to subscribers.
+#[derive(Debug, Default)]
+pub(crate) struct Changes {
+    wakers: Mutex<Vec<Waker>>,
+    condvar: Condvar,
+    running: AtomicUsize,
+    blocked: AtomicUsize,
+    halted: AtomicUsize,
+    finished: AtomicUsize,
+    mutations: AtomicU64,
+    /// The keys of the tasks kept in the tree after their item was dropped, in the order they were dropped.
+    retained: Mutex<Vec<Key>>,
+    subscribers: Mutex<Vec<Arc<Queue>>>,
+    /// The amount of `subscribers`, to avoid creating events nobody receives without locking.
+    num_subscribers: AtomicUsize,
+}
+
+impl Changes {
+    /// Return a new instance with the same counts, but nobody waiting for changes.
+    pub(crate) fn clone_counts(&self) -> Changes {
+        let counts = self.counts();
+        let changes = Changes::default();
+        changes.running.store(counts.running, Ordering::Relaxed);
+        changes.blocked.store(counts.blocked, Ordering::Relaxed);
+        changes.halted.store(counts.halted, Ordering::Relaxed);
+        changes.finished.store(counts.finished, Ordering::Relaxed);
+        *changes.retained.lock() = self.retained.lock().clone();
+        changes.mutations.store(self.mutations(), Ordering::Relaxed);
+        changes
+    }
+
+    /// Return the amount of tasks in each state.
+    pub(crate) fn counts(&self) -> TaskCounts {
+        TaskCounts {
+            running: self.running.load(Ordering::Relaxed),
+            blocked: self.blocked.load(Ordering::Relaxed),
+            halted: self.halted.load(Ordering::Relaxed),
+            finished: self.finished.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Return the amount of mutations so far.
+    pub(crate) fn mutations(&self) -> u64 {
+        self.mutations.load(Ordering::Relaxed)
+    }
+
+    /// Remember that the task at `key` is kept in the tree even though its item was dropped.
+    pub(crate) fn retain(&self, key: Key) {
+        self.retained.lock().push(key);
+    }
+
+    /// Return the keys of all retained tasks, forgetting about them.
+    pub(crate) fn take_retained(&self) -> Vec<Key> {
+        std::mem::take(&mut *self.retained.lock())
+    }
+
+    /// Return the amount of retained tasks.
+    pub(crate) fn num_retained(&self) -> usize {
+        self.retained.lock().len()
+    }
+
+    /// Return a subscription receiving all events published from now on, keeping up to `buffer_size` of them.
+    pub(crate) fn subscribe(&self, buffer_size: usize) -> Subscription {
+        let queue = Arc::new(Queue::new(buffer_size));
+        let mut subscribers = self.subscribers.lock();
+        subscribers.push(Arc::clone(&queue));
+        self.num_subscribers.store(subscribers.len(), Ordering::Relaxed);
+        Subscription { queue }
+    }
+
+    /// Return true if there may be subscribers to publish events to.
+    pub(crate) fn has_subscribers(&self) -> bool {
+        self.num_subscribers.load(Ordering::Relaxed) != 0
+    }
+
+    /// Send the event created by `event()` to all subscribers, if there are any.
+    ///
+    /// Subscribers are removed once their subscription was dropped.
+    pub(crate) fn publish(&self, event: impl FnOnce() -> ProgressEvent) {
+        if !self.has_subscribers() {
+            return;
+        }
+        let event = event();
+        let mut subscribers = self.subscribers.lock();
+        subscribers.retain(|queue| Arc::strong_count(queue) > 1);
+        for queue in subscribers.iter() {
+            queue.push(event.clone());
+        }
+        self.num_subscribers.store(subscribers.len(), Ordering::Relaxed);
+    }
+
+    /// Count a mutation of the tree.
+    pub(crate) fn record_mutation(&self) {
+        self.mutations.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Account for a task going from state `from` to state `to`, with `None` if it has no progress.
+    pub(crate) fn change_state(&self, from: Option<&State>, to: Option<&State>) {
+        if let Some(from) = from {
+            self.state_counter(from).fetch_sub(1, Ordering::Relaxed);
+        }
+        if let Some(to) = to {
+            self.state_counter(to).fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    fn state_counter(&self, state: &State) -> &AtomicUsize {
+        match state {
+            State::Running => &self.running,
+            State::Blocked(..) => &self.blocked,
+            State::Halted(..) => &self.halted,
+            State::Finished(_) => &self.finished,
+        }
+    }
+
+    /// Count a mutation and wake up all threads and futures waiting for a change.
+    ///
+    /// Must be called after tasks were added to or removed from the tree.
+    pub(crate) fn notify(&self) {
+        self.record_mutation();
+        let wakers = {
+            let mut wakers = self.wakers.lock();
+            self.condvar.notify_all();
+            std::mem::take(&mut *wakers)
+        };
+        for waker in wakers {
+            waker.wake();
+        }
+    }
+
+    /// Block the current thread until `is_done()` returns true, which is checked once initially and after each change.
+    pub(crate) fn wait_until(&self, mut is_done: impl FnMut() -> bool) {
+        let mut wakers = self.wakers.lock();
+        while !is_done() {
+            self.condvar.wait(&mut wakers);
+        }
+    }
+
+    /// Return `Poll::Ready` if `is_done()` returns true, or arrange for the waker in `cx` to be woken on the next change.
+    #[cfg(feature = "progress-tree-async")]
+    pub(crate) fn poll_until(
+        &self,
+        cx: &mut std::task::Context<'_>,
+        is_done: impl FnOnce() -> bool,
+    ) -> std::task::Poll<()> {
+        let mut wakers = self.wakers.lock();
+        if is_done() {
+            return std::task::Poll::Ready(());
+        }
+        if !wakers.iter().any(|waker| waker.will_wake(cx.waker())) {
+            wakers.push(cx.waker().clone());
+        }
+        std::task::Poll::Pending
+    }
+}
+
+impl Drop for Changes {
+    fn drop(&mut self) {
+        for queue in self.subscribers.get_mut().iter() {
+            queue.close();
+        }
+    }
+}
@@ -2,24 +2,72 @@ use std::{
     fmt::Debug,
     ops::Deref,
     sync::{
-        atomic::{AtomicUsize, Ordering},
+        atomic::{AtomicBool, AtomicUsize, Ordering},
         Arc,
     },
-    time::SystemTime,
+    time::{Duration, Instant, SystemTime},
 };
 
 use parking_lot::Mutex;
 
 use crate::{
-    messages::MessageLevel,
-    progress::{Id, State, Step, StepShared, Task, Value},
-    tree::Item,
+    messages::{MessageLevel, Origin},
+    progress::{CancelToken, ChildOrder, Id, Key, Marker, State, Step, StepShared, Task, Timing, Value},
+    tree::{Event, Item, MeasureScope, ProgressEvent},
     unit::Unit,
 };
 
 impl Drop for Item {
     fn drop(&mut self) {
-        self.tree.remove(&self.key);
+        if self.retain_finished && self.key.level() > 0 {
+            self.retain();
+            return;
+        }
+        #[cfg(feature = "progress-tree-hp-hashmap")]
+        let removed = self.tree.remove(&self.key).map(|(_, task)| task);
+        #[cfg(not(feature = "progress-tree-hp-hashmap"))]
+        let removed = self.tree.remove(&self.key);
+        self.changes
+            .change_state(removed.and_then(|task| task.progress).map(|p| p.state).as_ref(), None);
+        self.changes.notify();
+        self.emit(Event::ChildRemoved { key: &self.key });
+    }
+}
+
+impl MeasureScope<'_> {
+    /// Add the elapsed time to the [total][crate::progress::Task::timings] of all scopes with the same label on the
+    /// task once dropped.
+    pub fn accumulate(mut self) -> Self {
+        self.accumulate = true;
+        self
+    }
+
+    /// Return the time elapsed since the scope was entered.
+    pub fn elapsed(&self) -> Duration {
+        self.start.elapsed()
+    }
+}
+
+impl Drop for MeasureScope<'_> {
+    fn drop(&mut self) {
+        let elapsed = self.elapsed();
+        if self.accumulate {
+            self.item.alter_task(
+                |t| match t.timings.iter_mut().find(|timing| timing.label == self.label) {
+                    Some(timing) => {
+                        timing.total += elapsed;
+                        timing.count += 1;
+                    }
+                    None => t.timings.push(Timing {
+                        label: self.label.clone(),
+                        total: elapsed,
+                        count: 1,
+                    }),
+                },
+            );
+        }
+        self.item
+            .message(MessageLevel::Info, format!("{} took {:.2?}", self.label, elapsed));
     }
 }
 
@@ -46,34 +94,98 @@ impl Item {
     /// to the progress tree.
     ///
     /// **Note** that this method can be called multiple times, changing the bounded-ness and unit at will.
+    /// With the `minimal` feature toggle, `unit` is ignored.
     pub fn init(&self, max: Option<usize>, unit: Option<Unit>) {
+        let unit = if cfg!(feature = "minimal") {
+            None
+        } else {
+            unit.map(Unit::into_shared)
+        };
+        let event_unit = self.is_observed().then(|| unit.clone()).flatten();
         #[cfg(feature = "progress-tree-hp-hashmap")]
         {
             if let Some(mut r) = self.tree.get_mut(&self.key) {
-                self.value.store(0, Ordering::SeqCst);
-                r.value_mut().progress = (max.is_some() || unit.is_some()).then(|| Value {
-                    done_at: max,
-                    unit,
-                    step: Arc::clone(&self.value),
-                    ..Default::default()
-                })
+                let progress = (max.is_some() || unit.is_some()).then(|| self.new_value(max, unit));
+                self.set_progress(r.value_mut(), progress);
             };
         }
         #[cfg(not(feature = "progress-tree-hp-hashmap"))]
         {
             self.tree.get_mut(&self.key, |v| {
-                self.value.store(0, Ordering::SeqCst);
-                v.progress = (max.is_some() || unit.is_some()).then(|| Value {
-                    done_at: max,
-                    unit,
-                    step: Arc::clone(&self.value),
-                    ..Default::default()
-                });
+                let progress = (max.is_some() || unit.is_some()).then(|| self.new_value(max, unit));
+                self.set_progress(v, progress);
             });
         }
+        self.changes.record_mutation();
+        self.emit(Event::Init {
+            key: &self.key,
+            max,
+            unit: event_unit.as_deref(),
+        });
+    }
+
+    /// Reset all counters and return a value for receiving progress sharing them.
+    fn new_value(&self, max: Option<Step>, unit: Option<Arc<Unit>>) -> Value {
+        self.value.store(0, Ordering::SeqCst);
+        self.max.store(max.unwrap_or(Step::MAX), Ordering::SeqCst);
+        self.activity.store(0, Ordering::SeqCst);
+        Value {
+            done_at: max,
+            unit,
+            step: Arc::clone(&self.value),
+            shared_max: Some(Arc::clone(&self.max)),
+            activity: Arc::clone(&self.activity),
+            ..Default::default()
+        }
+    }
+
+    /// Replace the progress of `task` with `progress`, keeping the amount of tasks by state up to date.
+    fn set_progress(&self, task: &mut Task, progress: Option<Value>) {
+        self.changes.change_state(
+            task.progress.as_ref().map(|p| &p.state),
+            progress.as_ref().map(|p| &p.state),
+        );
+        task.progress = progress;
+    }
+
+    /// Indicate that work is being done without counting it, to let renderers animate this task without changing its step.
+    ///
+    /// Use it instead of [`inc()`][Item::inc()] for tasks which can't tell how much work they did. If this instance
+    /// wasn't initialized to receive progress, it's initialized to be unbounded on the first tick.
+    pub fn tick(&self) {
+        if self.activity.fetch_add(1, Ordering::Relaxed) == 0 {
+            self.alter_task(|task| {
+                if task.progress.is_none() {
+                    let value = self.new_value(None, None);
+                    value.activity.store(1, Ordering::Relaxed);
+                    self.set_progress(task, Some(value));
+                }
+            });
+        }
+    }
+
+    /// Return true if anyone receives events, which are only worth creating in that case.
+    fn is_observed(&self) -> bool {
+        self.on_event.is_some() || self.changes.has_subscribers()
+    }
+
+    fn emit(&self, event: Event<'_>) {
+        if let Some(on_event) = self.on_event.as_ref() {
+            on_event(event);
+        }
+        self.changes.publish(|| event.into());
+    }
+
+    /// Let subscribers know that the step of this task changed.
+    fn publish_step(&self) {
+        self.changes.publish(|| ProgressEvent::ValueChanged {
+            key: self.key.clone(),
+            step: self.value.load(Ordering::Relaxed),
+        });
     }
 
     fn alter_progress(&self, f: impl FnMut(&mut Value)) {
+        self.changes.record_mutation();
         #[cfg(feature = "progress-tree-hp-hashmap")]
         {
             if let Some(mut r) = self.tree.get_mut(&self.key) {
@@ -93,31 +205,75 @@ impl Item {
         }
     }
 
-    /// Set the name of this task's progress to the given `name`.
-    pub fn set_name(&self, name: impl Into<String>) {
+    fn alter_task(&self, f: impl FnOnce(&mut Task)) {
+        self.alter_task_at(&self.key, f)
+    }
+
+    fn alter_task_at(&self, key: &Key, f: impl FnOnce(&mut Task)) {
+        self.changes.record_mutation();
         #[cfg(feature = "progress-tree-hp-hashmap")]
         {
-            if let Some(mut r) = self.tree.get_mut(&self.key) {
-                r.value_mut().name = name.into();
+            if let Some(mut r) = self.tree.get_mut(key) {
+                f(r.value_mut());
             };
         }
         #[cfg(not(feature = "progress-tree-hp-hashmap"))]
         {
-            self.tree.get_mut(&self.key, |v| {
-                v.name = name.into();
-            });
+            self.tree.get_mut(key, f);
+        }
+    }
+
+    /// Mark all ancestors of this task as having a failed descendant.
+    fn escalate_failure(&self) {
+        for ancestor in self.key.ancestors() {
+            self.alter_task_at(&ancestor, |t| t.failed_descendant = true);
+        }
+    }
+
+    /// Set the name of this task's progress to the given `name`.
+    ///
+    /// If the tree debounces renames, see [`Options::name_debounce`][crate::tree::root::Options::name_debounce],
+    /// a rename within the debounce window of the previous one becomes visible only once the window passed.
+    ///
+    /// This does nothing with the `minimal` feature toggle.
+    pub fn set_name(&self, name: impl Into<String>) {
+        if cfg!(feature = "minimal") {
+            return;
         }
+        let name = name.into();
+        let debounce = self.name_debounce;
+        self.alter_task(|t| {
+            let now = SystemTime::now();
+            match (debounce, t.renamed_at) {
+                (Some(window), Some(renamed_at))
+                    if now.duration_since(renamed_at).map_or(true, |elapsed| elapsed < window) =>
+                {
+                    t.pending_name = Some((name, renamed_at + window));
+                }
+                _ => {
+                    t.name = name;
+                    t.pending_name = None;
+                    t.renamed_at = debounce.map(|_| now);
+                }
+            }
+        });
     }
 
-    /// Get the name of this task's progress
+    /// Get the name of this task's progress, which is the latest name set even if it isn't visible yet due to debouncing.
     pub fn name(&self) -> Option<String> {
+        fn latest_name(task: &Task) -> String {
+            task.pending_name
+                .as_ref()
+                .map_or(&task.name, |(name, _)| name)
+                .to_owned()
+        }
         #[cfg(feature = "progress-tree-hp-hashmap")]
         {
-            self.tree.get(&self.key).map(|r| r.value().name.to_owned())
+            self.tree.get(&self.key).map(|r| latest_name(r.value()))
         }
         #[cfg(not(feature = "progress-tree-hp-hashmap"))]
         {
-            self.tree.get(&self.key, |v| v.name.to_owned())
+            self.tree.get(&self.key, latest_name)
         }
     }
 
@@ -147,18 +303,27 @@ impl Item {
         {
             self.tree
                 .get(&self.key)
-                .and_then(|r| r.value().progress.as_ref().and_then(|p| p.done_at))
+                .and_then(|r| r.value().progress.as_ref().and_then(Value::max))
         }
         #[cfg(not(feature = "progress-tree-hp-hashmap"))]
         {
             self.tree
-                .get(&self.key, |v| v.progress.as_ref().and_then(|p| p.done_at))
+                .get(&self.key, |v| v.progress.as_ref().and_then(Value::max))
                 .flatten()
         }
     }
 
+    /// Returns an atomic holding the maximum, to update it from anywhere without holding this instance or locking the tree.
+    ///
+    /// Storing [`Step::MAX`] makes the progress unbounded, and [`init()`][Item::init()] overwrites it.
+    /// Note that it only takes effect once this instance was initialized to receive progress.
+    pub fn max_counter(&self) -> StepShared {
+        Arc::clone(&self.max)
+    }
+
     /// Set the maximum value to `max` and return the old maximum value.
     pub fn set_max(&self, max: Option<Step>) -> Option<Step> {
+        self.changes.record_mutation();
         #[cfg(feature = "progress-tree-hp-hashmap")]
         {
             self.tree
@@ -167,8 +332,9 @@ impl Item {
                 .progress
                 .as_mut()
                 .and_then(|p| {
-                    let prev = p.done_at;
+                    let prev = p.max();
                     p.done_at = max;
+                    self.max.store(max.unwrap_or(Step::MAX), Ordering::SeqCst);
                     prev
                 })
         }
@@ -177,8 +343,9 @@ impl Item {
             self.tree
                 .get_mut(&self.key, |v| {
                     v.progress.as_mut().and_then(|p| {
-                        let prev = p.done_at;
+                        let prev = p.max();
                         p.done_at = max;
+                        self.max.store(max.unwrap_or(Step::MAX), Ordering::SeqCst);
                         prev
                     })
                 })
@@ -192,12 +359,14 @@ impl Item {
         {
             self.tree
                 .get(&self.key)
-                .and_then(|r| r.value().progress.as_ref().and_then(|p| p.unit.clone()))
+                .and_then(|r| r.value().progress.as_ref().and_then(|p| p.unit.as_deref().cloned()))
         }
         #[cfg(not(feature = "progress-tree-hp-hashmap"))]
         {
             self.tree
-                .get(&self.key, |v| v.progress.as_ref().and_then(|p| p.unit.clone()))
+                .get(&self.key, |v| {
+                    v.progress.as_ref().and_then(|p| p.unit.as_deref().cloned())
+                })
                 .flatten()
         }
     }
@@ -207,6 +376,7 @@ impl Item {
     /// **Note**: that this call has no effect unless `init(…)` was called before.
     pub fn set(&self, step: Step) {
         self.value.store(step, Ordering::SeqCst);
+        self.publish_step();
     }
 
     /// Increment the current progress by the given `step`.
@@ -214,6 +384,7 @@ impl Item {
     /// **Note**: that this call has no effect unless `init(…)` was called before.
     pub fn inc_by(&self, step: Step) {
         self.value.fetch_add(step, Ordering::Relaxed);
+        self.publish_step();
     }
 
     /// Increment the current progress by one.
@@ -221,6 +392,7 @@ impl Item {
     /// **Note**: that this call has no effect unless `init(…)` was called before.
     pub fn inc(&self) {
         self.value.fetch_add(1, Ordering::Relaxed);
+        self.publish_step();
     }
 
     /// Call to indicate that progress cannot be indicated, and that the task cannot be interrupted.
@@ -232,7 +404,7 @@ impl Item {
     ///
     /// The halted-state is undone next time [`tree::Item::running(…)`][Item::running()] is called.
     pub fn blocked(&self, reason: &'static str, eta: Option<SystemTime>) {
-        self.alter_progress(|p| p.state = State::Blocked(reason, eta));
+        self.set_state(State::Blocked(reason.into(), eta));
     }
 
     /// Call to indicate that progress cannot be indicated, even though the task can be interrupted.
@@ -244,47 +416,169 @@ impl Item {
     ///
     /// The halted-state is undone next time [`tree::Item::running(…)`][Item::running()] is called.
     pub fn halted(&self, reason: &'static str, eta: Option<SystemTime>) {
-        self.alter_progress(|p| p.state = State::Halted(reason, eta));
+        self.set_state(State::Halted(reason.into(), eta));
+    }
+
+    /// Returns `true` if this task was requested to stop, for example by the user of a renderer, with
+    /// [`cancel()`][Item::cancel()] or its [cancel token][Item::cancel_token()].
+    pub fn is_cancelled(&self) -> bool {
+        self.cancel_token.is_cancelled()
+    }
+
+    /// Request this task to stop, which is up to the code making progress to notice with [`is_cancelled()`][Item::is_cancelled()].
+    pub fn cancel(&self) {
+        self.cancel_token.cancel()
+    }
+
+    /// Returns the flag to request this task to stop, to check or set it without holding this instance.
+    ///
+    /// Renderers set it through the copy of it stored in the tree.
+    pub fn cancel_token(&self) -> CancelToken {
+        self.cancel_token.clone()
     }
 
     /// Call to indicate that progress is back in running state, which should be called after the reason for
     /// calling `blocked()` or `halted()` has passed.
+    ///
+    /// This also clears the note set with [`set_note(…)`][Item::set_note()].
     pub fn running(&self) {
-        self.alter_progress(|p| p.state = State::Running);
+        self.set_state(State::Running);
+        self.clear_note();
+    }
+
+    fn set_state(&self, state: State) {
+        self.alter_progress(|p| {
+            self.changes.change_state(Some(&p.state), Some(&state));
+            p.state = state.clone();
+        });
+        self.emit(Event::StateChanged {
+            key: &self.key,
+            state: &state,
+        });
+    }
+
+    /// Start another attempt at this task after the previous one failed, resetting its progress to zero and returning
+    /// the number of the new attempt.
+    ///
+    /// Renderers show the attempt after the name of the task, like `(attempt 3)`, so it doesn't have to be part of it.
+    pub fn new_attempt(&self) -> u32 {
+        let mut attempt = 0;
+        self.alter_task(|t| {
+            t.retries = t.retries.saturating_add(1);
+            attempt = t.attempt();
+        });
+        self.set(0);
+        attempt
+    }
+
+    /// Set a short `note` to be displayed after the progress of this task, like the name of the file currently being processed.
+    ///
+    /// As opposed to [messages][Item::message()], notes are meant to be updated frequently and only the latest one is shown.
+    /// The note is cleared when calling [`running()`][Item::running()] or [`done(…)`][Item::done()].
+    pub fn set_note(&self, note: impl Into<String>) {
+        let note = note.into();
+        self.alter_task(|t| t.note = Some(note));
+    }
+
+    /// Remove the note previously set with [`set_note(…)`][Item::set_note()].
+    pub fn clear_note(&self) {
+        self.alter_task(|t| t.note = None);
+    }
+
+    /// Record a marker with the given `label` at the current step, like the boundary between two phases of one long task.
+    ///
+    /// Renderers may show markers as ticks on the progress bar.
+    pub fn mark(&self, label: impl Into<String>) {
+        let marker = Marker {
+            step: self.value.load(Ordering::SeqCst),
+            time: SystemTime::now(),
+            label: label.into(),
+        };
+        self.alter_task(|t| t.markers.push(marker));
+    }
+
+    /// Measure the time until the returned guard is dropped, which then sends an info message like
+    /// `resolve deltas took 1.25s`.
+    ///
+    /// Use [`MeasureScope::accumulate()`] to also keep the total time of all scopes with the same `label` on the task,
+    /// which the detail row of the terminal user interface shows. This allows coarse profiling within the dashboard.
+    pub fn measure(&self, label: impl Into<String>) -> MeasureScope<'_> {
+        MeasureScope {
+            item: self,
+            label: label.into(),
+            start: Instant::now(),
+            accumulate: false,
+        }
+    }
+
+    /// Declare that this task is expected to be done within `duration` from now.
+    ///
+    /// Renderers highlight tasks that take longer than that, which helps to spot regressions in steps with a known cost.
+    pub fn expect_duration(&self, duration: Duration) {
+        let now = SystemTime::now();
+        self.alter_task(|t| t.expected_duration = Some((duration, now)));
+    }
+
+    /// Set the `order` in which the children of this task are displayed by renderers.
+    ///
+    /// By default, children are shown in the order they were added.
+    pub fn set_child_order(&self, order: ChildOrder) {
+        self.alter_task(|t| t.child_order = order);
+    }
+
+    /// Set the `rank` of this task among its siblings, used if the parent orders its children by [rank][ChildOrder::Rank].
+    pub fn set_rank(&self, rank: i64) {
+        self.alter_task(|t| t.rank = rank);
     }
 
     /// Adds a new child `Tree`, whose parent is this instance, with the given `name`.
     ///
-    /// **Important**: The depth of the hierarchy is limited to [`tree::Key::max_level`](./struct.Key.html#method.max_level).
-    /// Exceeding the level will be ignored, and new tasks will be added to this instance's
-    /// level instead.
     pub fn add_child(&mut self, name: impl Into<String>) -> Item {
         self.add_child_with_id(name, crate::progress::UNKNOWN)
     }
 
     /// Adds a new child `Tree`, whose parent is this instance, with the given `name` and `id`.
     ///
-    /// **Important**: The depth of the hierarchy is limited to [`tree::Key::max_level`](./struct.Key.html#method.max_level).
-    /// Exceeding the level will be ignored, and new tasks will be added to this instance's
-    /// level instead.
     pub fn add_child_with_id(&mut self, name: impl Into<String>, id: Id) -> Item {
         let child_key = self.key.add_child(self.highest_child_id);
+        let name = name.into();
+        let event_name = self.is_observed().then(|| name.clone());
+        let cancel_token = CancelToken::default();
         let task = Task {
-            name: name.into(),
+            name,
             id,
-            progress: None,
+            created_at: Some(SystemTime::now()),
+            cancel_token: cancel_token.clone(),
+            ..Default::default()
         };
         #[cfg(feature = "progress-tree-hp-hashmap")]
-        self.tree.insert(child_key, task);
+        self.tree.insert(child_key.clone(), task);
         #[cfg(not(feature = "progress-tree-hp-hashmap"))]
-        self.tree.insert(child_key, task);
+        self.tree.insert(child_key.clone(), task);
         self.highest_child_id = self.highest_child_id.wrapping_add(1);
+        self.changes.notify();
+        if let Some(name) = event_name {
+            self.emit(Event::ChildAdded {
+                key: &child_key,
+                id,
+                name: &name,
+            });
+        }
         Item {
             highest_child_id: 0,
             value: Default::default(),
+            max: Default::default(),
+            activity: Default::default(),
+            cancel_token,
             key: child_key,
             tree: Arc::clone(&self.tree),
             messages: Arc::clone(&self.messages),
+            name_debounce: self.name_debounce,
+            escalate_failures: self.escalate_failures,
+            retain_finished: self.retain_finished,
+            failed: Default::default(),
+            on_event: self.on_event.clone(),
+            changes: Arc::clone(&self.changes),
         }
     }
 
@@ -292,43 +586,121 @@ impl Item {
     ///
     /// Use this to provide additional,human-readable information about the progress
     /// made, including indicating success or failure.
+    ///
+    /// This does nothing with the `minimal` feature toggle.
     pub fn message(&self, level: MessageLevel, message: impl Into<String>) {
+        if cfg!(feature = "minimal") {
+            return;
+        }
         let message: String = message.into();
-        self.messages.lock().push_overwrite(
-            level,
+        let origin = {
+            let (name, id);
+            #[cfg(feature = "progress-tree-hp-hashmap")]
             {
-                let name;
-                #[cfg(feature = "progress-tree-hp-hashmap")]
-                {
-                    name = self.tree.get(&self.key).map(|v| v.name.to_owned()).unwrap_or_default();
-                }
-                #[cfg(not(feature = "progress-tree-hp-hashmap"))]
-                {
-                    name = self.tree.get(&self.key, |v| v.name.to_owned()).unwrap_or_default()
-                }
-
-                #[cfg(feature = "progress-tree-log")]
+                (name, id) = self
+                    .tree
+                    .get(&self.key)
+                    .map(|v| (v.name.to_owned(), v.id))
+                    .unwrap_or_default();
+            }
+            #[cfg(not(feature = "progress-tree-hp-hashmap"))]
+            {
+                (name, id) = self
+                    .tree
+                    .get(&self.key, |v| (v.name.to_owned(), v.id))
+                    .unwrap_or_default()
+            }
+
+            #[cfg(feature = "progress-tree-log")]
+            if !crate::render::is_log_suppressed() {
                 match level {
                     MessageLevel::Failure => crate::warn!("{} → {}", name, message),
                     MessageLevel::Info | MessageLevel::Success => crate::info!("{} → {}", name, message),
                 };
+            }
 
-                name
-            },
-            message,
-        )
+            Origin {
+                key: self.key.clone(),
+                id,
+                name,
+            }
+        };
+        self.emit(Event::Message {
+            level,
+            origin: &origin,
+            message: &message,
+        });
+        if self.escalate_failures && level == MessageLevel::Failure {
+            self.escalate_failure();
+        }
+        self.changes.record_mutation();
+        self.messages.lock().push_overwrite(level, origin, message)
     }
 
-    /// Create a message indicating the task is done
+    /// Create a message indicating the task is done, clearing its [note][Item::set_note()] and recording the time it
+    /// [finished at][Task::finished_at].
     pub fn done(&mut self, message: impl Into<String>) {
+        self.finish(false);
         self.message(MessageLevel::Success, message)
     }
 
-    /// Create a message indicating the task failed
+    /// Create a message indicating the task failed, recording the time it [finished at][Task::finished_at].
     pub fn fail(&mut self, message: impl Into<String>) {
+        self.finish(true);
         self.message(MessageLevel::Failure, message)
     }
 
+    /// Record the time we finished and whether we `failed`, clearing our note if we didn't.
+    fn finish(&self, failed: bool) {
+        if !failed {
+            self.clear_note();
+        }
+        self.failed.store(failed, Ordering::SeqCst);
+        let now = SystemTime::now();
+        self.alter_task(|t| t.finished_at = Some(now));
+    }
+
+    /// Keep our task in the tree as finished once we are dropped, instead of removing it.
+    fn retain(&self) {
+        let now = SystemTime::now();
+        let state = State::Finished(!self.failed.load(Ordering::SeqCst));
+        let mut has_progress = false;
+        self.alter_task(|t| {
+            t.finished_at.get_or_insert(now);
+            if let Some(progress) = t.progress.as_mut() {
+                self.changes.change_state(Some(&progress.state), Some(&state));
+                progress.state = state.clone();
+                has_progress = true;
+            }
+        });
+        self.changes.retain(self.key.clone());
+        self.changes.notify();
+        if has_progress {
+            self.emit(Event::StateChanged {
+                key: &self.key,
+                state: &state,
+            });
+        }
+    }
+
+    /// Remove all tasks kept in the tree after their item was dropped.
+    pub(crate) fn prune_finished(&self) {
+        let retained = self.changes.take_retained();
+        if retained.is_empty() {
+            return;
+        }
+        for key in &retained {
+            #[cfg(feature = "progress-tree-hp-hashmap")]
+            let removed = self.tree.remove(key).map(|(_, task)| task);
+            #[cfg(not(feature = "progress-tree-hp-hashmap"))]
+            let removed = self.tree.remove(key);
+            self.changes
+                .change_state(removed.and_then(|task| task.progress).map(|p| p.state).as_ref(), None);
+            self.emit(Event::ChildRemoved { key });
+        }
+        self.changes.notify();
+    }
+
     /// Create a message providing additional information about the progress thus far.
     pub fn info(&mut self, message: impl Into<String>) {
         self.message(MessageLevel::Info, message)
@@ -336,11 +708,20 @@ impl Item {
 
     pub(crate) fn deep_clone(&self) -> Item {
         Item {
-            key: self.key,
+            key: self.key.clone(),
             value: Arc::new(AtomicUsize::new(self.value.load(Ordering::SeqCst))),
+            max: Arc::new(AtomicUsize::new(self.max.load(Ordering::SeqCst))),
+            activity: Arc::new(AtomicUsize::new(self.activity.load(Ordering::SeqCst))),
+            cancel_token: self.cancel_token.clone(),
             highest_child_id: self.highest_child_id,
             tree: Arc::new(self.tree.deref().clone()),
             messages: Arc::new(Mutex::new(self.messages.lock().clone())),
+            name_debounce: self.name_debounce,
+            escalate_failures: self.escalate_failures,
+            retain_finished: self.retain_finished,
+            failed: AtomicBool::new(self.failed.load(Ordering::SeqCst)),
+            on_event: self.on_event.clone(),
+            changes: Arc::new(self.changes.clone_counts()),
         }
     }
 }
@@ -380,6 +761,14 @@ impl crate::Progress for Item {
         Item::set_max(self, max)
     }
 
+    fn max_counter(&self) -> Option<StepShared> {
+        Some(Item::max_counter(self))
+    }
+
+    fn is_cancelled(&self) -> bool {
+        Item::is_cancelled(self)
+    }
+
     fn set_name(&mut self, name: String) {
         Item::set_name(self, name)
     }
@@ -395,6 +784,16 @@ impl crate::Progress for Item {
     fn message(&self, level: MessageLevel, message: String) {
         Item::message(self, level, message)
     }
+
+    fn done(&self, message: String) {
+        self.finish(false);
+        Item::message(self, MessageLevel::Success, message)
+    }
+
+    fn fail(&self, message: String) {
+        self.finish(true);
+        Item::message(self, MessageLevel::Failure, message)
+    }
 }
 
 impl crate::NestedProgress for Item {
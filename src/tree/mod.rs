@@ -4,6 +4,28 @@ use crate::messages::MessageRingBuffer;
 #[derive(Debug)]
 pub struct Root {
     pub(crate) inner: parking_lot::Mutex<Item>,
+    pub(crate) clock_paused: std::sync::atomic::AtomicBool,
+    /// True while the whole tree is [paused][Root::pause()].
+    pub(crate) paused: std::sync::atomic::AtomicBool,
+    /// The keys of all tasks in order as seen by the last call to `for_each_task_sorted()`, to avoid sorting them again.
+    pub(crate) sorted_keys: parking_lot::Mutex<Vec<crate::progress::Key>>,
+    /// The time the tree was created, as reference for the time the whole run took.
+    pub(crate) started_at: std::time::SystemTime,
+    /// The changes shared with all items, to read their counts without locking `inner`.
+    pub(crate) changes: std::sync::Arc<changes::Changes>,
+}
+
+/// The amount of tasks in each [state][crate::progress::State], as returned by [`Root::task_counts()`].
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub struct TaskCounts {
+    /// The amount of running tasks.
+    pub running: usize,
+    /// The amount of [blocked][Item::blocked()] tasks.
+    pub blocked: usize,
+    /// The amount of [halted][Item::halted()] tasks.
+    pub halted: usize,
+    /// The amount of [finished][crate::progress::State::Finished] tasks kept in the tree.
+    pub finished: usize,
 }
 
 /// A `Tree` represents an element of the progress tree.
@@ -26,9 +48,177 @@ pub struct Root {
 pub struct Item {
     pub(crate) key: crate::progress::Key,
     pub(crate) value: crate::progress::StepShared,
+    pub(crate) max: crate::progress::StepShared,
+    pub(crate) activity: crate::progress::StepShared,
+    pub(crate) cancel_token: crate::progress::CancelToken,
     pub(crate) highest_child_id: crate::progress::key::Id,
     pub(crate) tree: std::sync::Arc<HashMap<crate::progress::Key, crate::progress::Task>>,
     pub(crate) messages: std::sync::Arc<parking_lot::Mutex<MessageRingBuffer>>,
+    pub(crate) name_debounce: Option<std::time::Duration>,
+    pub(crate) escalate_failures: bool,
+    pub(crate) retain_finished: bool,
+    /// True if the last call to [`done()`][Item::done()] or [`fail()`][Item::fail()] was the latter.
+    pub(crate) failed: std::sync::atomic::AtomicBool,
+    pub(crate) on_event: Option<OnEvent>,
+    pub(crate) changes: std::sync::Arc<changes::Changes>,
+}
+
+/// A guard measuring the time until it's dropped, as returned by [`Item::measure()`].
+///
+/// Once dropped, the task it belongs to sends an info message with the elapsed time.
+#[must_use = "the time is measured until the scope is dropped"]
+pub struct MeasureScope<'a> {
+    pub(crate) item: &'a Item,
+    pub(crate) label: String,
+    pub(crate) start: std::time::Instant,
+    pub(crate) accumulate: bool,
+}
+
+/// A change to the progress tree, as passed to [`Options::on_event`][root::Options::on_event].
+#[derive(Debug, Clone, Copy)]
+pub enum Event<'a> {
+    /// A task was added to the tree.
+    ChildAdded {
+        /// The key of the new task.
+        key: &'a crate::progress::Key,
+        /// The id of the new task.
+        id: crate::progress::Id,
+        /// The name of the new task.
+        name: &'a str,
+    },
+    /// A task was removed from the tree as its [`Item`] was dropped.
+    ChildRemoved {
+        /// The key of the removed task.
+        key: &'a crate::progress::Key,
+    },
+    /// A task was [initialized][Item::init()] to receive progress.
+    Init {
+        /// The key of the task.
+        key: &'a crate::progress::Key,
+        /// The step at which the task is done, if it is bounded.
+        max: Option<crate::progress::Step>,
+        /// The unit of the progress, if set.
+        unit: Option<&'a crate::unit::Unit>,
+    },
+    /// A task was blocked, halted or is running again.
+    StateChanged {
+        /// The key of the task.
+        key: &'a crate::progress::Key,
+        /// The new state of the task.
+        state: &'a crate::progress::State,
+    },
+    /// A message was sent by a task.
+    Message {
+        /// The level of the message.
+        level: crate::messages::MessageLevel,
+        /// The task that sent the message.
+        origin: &'a crate::messages::Origin,
+        /// The message itself.
+        message: &'a str,
+    },
+}
+
+/// The function called for each [`Event`] in the tree.
+pub type OnEvent = std::sync::Arc<dyn Fn(Event<'_>) + Send + Sync>;
+
+/// A change to the progress tree, as received through [`Root::subscribe()`].
+///
+/// Besides all [events][Event], it includes changes to the step of tasks.
+#[derive(Debug, Clone)]
+pub enum ProgressEvent {
+    /// A task was added to the tree.
+    ChildAdded {
+        /// The key of the new task.
+        key: crate::progress::Key,
+        /// The id of the new task.
+        id: crate::progress::Id,
+        /// The name of the new task.
+        name: String,
+    },
+    /// A task was removed from the tree as its [`Item`] was dropped.
+    ChildRemoved {
+        /// The key of the removed task.
+        key: crate::progress::Key,
+    },
+    /// A task was [initialized][Item::init()] to receive progress.
+    Init {
+        /// The key of the task.
+        key: crate::progress::Key,
+        /// The step at which the task is done, if it is bounded.
+        max: Option<crate::progress::Step>,
+        /// The unit of the progress, if set.
+        unit: Option<crate::unit::Unit>,
+    },
+    /// The step of a task was [set][Item::set()] or [incremented][Item::inc_by()].
+    ValueChanged {
+        /// The key of the task.
+        key: crate::progress::Key,
+        /// The new step of the task.
+        step: crate::progress::Step,
+    },
+    /// A task was blocked, halted or is running again.
+    StateChanged {
+        /// The key of the task.
+        key: crate::progress::Key,
+        /// The new state of the task.
+        state: crate::progress::State,
+    },
+    /// A message was sent by a task.
+    Message {
+        /// The level of the message.
+        level: crate::messages::MessageLevel,
+        /// The task that sent the message.
+        origin: crate::messages::Origin,
+        /// The message itself.
+        message: String,
+    },
+}
+
+/// A subscription to the [events][ProgressEvent] of a tree, as returned by [`Root::subscribe()`].
+///
+/// It buffers events until they are received, without ever blocking the tasks producing them. Changes to the step
+/// of a task are merged into its pending step change, so only its latest step is received at the position of the
+/// first change. If the buffer is full, all further events except for messages are dropped until there is room again.
+/// [Statistics][Subscription::stats()] tell how many events were affected, to detect slow consumers.
+#[derive(Debug)]
+pub struct Subscription {
+    pub(crate) queue: std::sync::Arc<subscription::Queue>,
+}
+
+/// The amount of events a [`Subscription`] didn't receive individually, as returned by [`Subscription::stats()`].
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub struct SubscriptionStats {
+    /// The amount of step changes which were merged into a step change of the same task that wasn't received yet.
+    pub coalesced: u64,
+    /// The amount of events which were dropped as the buffer was full.
+    pub dropped: u64,
+}
+
+impl From<Event<'_>> for ProgressEvent {
+    fn from(event: Event<'_>) -> Self {
+        match event {
+            Event::ChildAdded { key, id, name } => ProgressEvent::ChildAdded {
+                key: key.clone(),
+                id,
+                name: name.to_owned(),
+            },
+            Event::ChildRemoved { key } => ProgressEvent::ChildRemoved { key: key.clone() },
+            Event::Init { key, max, unit } => ProgressEvent::Init {
+                key: key.clone(),
+                max,
+                unit: unit.cloned(),
+            },
+            Event::StateChanged { key, state } => ProgressEvent::StateChanged {
+                key: key.clone(),
+                state: state.clone(),
+            },
+            Event::Message { level, origin, message } => ProgressEvent::Message {
+                level,
+                origin: origin.clone(),
+                message: message.to_owned(),
+            },
+        }
+    }
 }
 
 #[cfg(feature = "dashmap")]
@@ -39,14 +229,24 @@ type HashMap<K, V> = sync::HashMap<K, V>;
 
 #[cfg(not(feature = "dashmap"))]
 pub(crate) mod sync {
-    pub struct HashMap<K, V>(parking_lot::Mutex<std::collections::HashMap<K, V>>);
+    #[cfg(not(feature = "progress-tree-btreemap"))]
+    type Map<K, V> = std::collections::HashMap<K, V>;
+    #[cfg(feature = "progress-tree-btreemap")]
+    type Map<K, V> = std::collections::BTreeMap<K, V>;
+
+    pub struct HashMap<K, V>(parking_lot::Mutex<Map<K, V>>);
 
     impl<K, V> HashMap<K, V>
     where
-        K: Eq + std::hash::Hash,
+        K: Ord + std::hash::Hash,
     {
+        #[cfg(not(feature = "progress-tree-btreemap"))]
         pub fn with_capacity(cap: usize) -> Self {
-            HashMap(parking_lot::Mutex::new(std::collections::HashMap::with_capacity(cap)))
+            HashMap(parking_lot::Mutex::new(Map::with_capacity(cap)))
+        }
+        #[cfg(feature = "progress-tree-btreemap")]
+        pub fn with_capacity(_cap: usize) -> Self {
+            HashMap(parking_lot::Mutex::new(Map::new()))
         }
         pub fn extend_to(&self, out: &mut Vec<(K, V)>)
         where
@@ -71,6 +271,11 @@ pub(crate) mod sync {
         pub fn len(&self) -> usize {
             self.0.lock().len()
         }
+        pub fn for_each(&self, mut cb: impl FnMut(&K, &V)) {
+            for (key, value) in self.0.lock().iter() {
+                cb(key, value)
+            }
+        }
         pub fn clone(&self) -> Self
         where
             K: Clone,
@@ -81,9 +286,11 @@ pub(crate) mod sync {
     }
 }
 
+mod changes;
 mod item;
 ///
 pub mod root;
+mod subscription;
 
 #[cfg(test)]
 mod tests;
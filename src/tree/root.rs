@@ -1,14 +1,19 @@
 use std::{
+    fmt,
     ops::Deref,
-    sync::{atomic::AtomicUsize, Arc, Weak},
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc, Weak,
+    },
+    time::{Duration, SystemTime},
 };
 
 use parking_lot::Mutex;
 
 use crate::{
-    messages::{Message, MessageCopyState, MessageRingBuffer},
-    progress::{Id, Key, Task},
-    tree::{Item, Root},
+    messages::{Message, MessageCopyState, MessageRingBuffer, RateLimit},
+    progress::{ChildOrder, Id, Key, State, Task},
+    tree::{changes::Changes, HashMap, Item, OnEvent, Root, Subscription, TaskCounts},
 };
 
 impl Root {
@@ -20,9 +25,45 @@ impl Root {
         Options::default().into()
     }
 
+    /// Create a new tree with default configuration whose root is shown with the given `name`.
+    ///
+    /// See [`set_name()`][Root::set_name()] for details.
+    pub fn with_parent_name(name: impl Into<String>) -> Arc<Root> {
+        let root = Root::new();
+        root.set_name(name);
+        root
+    }
+
+    /// Set the name of the root to `name`, making it visible as parent of all top-level tasks.
+    ///
+    /// Renderers will show it as the first line of the tree, at level 0, as if it was a task serving as organizational unit.
+    /// This can be called at any time to update the name.
+    pub fn set_name(&self, name: impl Into<String>) {
+        let inner = self.inner.lock();
+        inner.tree.insert(
+            inner.key.clone(),
+            Task {
+                name: name.into(),
+                ..Default::default()
+            },
+        );
+        inner.changes.record_mutation();
+    }
+
+    /// Returns the name of the root if it was set with [`set_name()`][Root::set_name()].
+    pub fn name(&self) -> Option<String> {
+        self.inner.lock().name()
+    }
+
     /// Returns the maximum amount of messages we can keep before overwriting older ones.
     pub fn messages_capacity(&self) -> usize {
-        self.inner.lock().messages.lock().buf.capacity()
+        self.inner.lock().messages.lock().capacity()
+    }
+
+    /// Returns the amount of messages dropped so far as their task exceeded the
+    /// [rate limit][Options::message_rate_limit].
+    pub fn suppressed_messages(&self) -> usize {
+        self.inner.lock().messages.lock().suppressed()
     }
 
     /// Returns the current amount of `Item`s stored in the tree.
@@ -38,6 +79,52 @@ impl Root {
         }
     }
 
+    /// Return the approximate amount of bytes used by all tasks and messages in the tree.
+    ///
+    /// It accounts for the tasks along with their names, notes, markers and units, and for the message buffer with
+    /// all messages in it. It doesn't account for allocator overhead and unused capacity of the tree itself, so it's
+    /// best used to learn how memory usage grows with the amount of tasks.
+    pub fn memory_estimate(&self) -> usize {
+        let inner = self.inner.lock();
+        let entry_size = std::mem::size_of::<(Key, Task)>();
+        #[cfg(feature = "progress-tree-hp-hashmap")]
+        let tasks: usize = inner.tree.iter().map(|r| entry_size + r.value().heap_size()).sum();
+        #[cfg(not(feature = "progress-tree-hp-hashmap"))]
+        let tasks = {
+            let mut tasks = 0;
+            inner.tree.for_each(|_, task| tasks += entry_size + task.heap_size());
+            tasks
+        };
+        let messages = inner.messages.lock().memory_estimate();
+        std::mem::size_of::<Root>() + tasks + messages
+    }
+
+    /// Block the current thread until all tasks were removed from the tree, not counting the root's [name][Root::set_name()]
+    /// and [retained][Options::retain_finished] tasks.
+    ///
+    /// This is useful to wait for all producers to finish before shutting down renderers.
+    /// It doesn't poll, but checks the tree each time a task is added or removed, and returns immediately if there
+    /// are no tasks.
+    pub fn wait_until_empty(&self) {
+        let (tree, root_key, changes) = self.tree_and_changes();
+        changes.wait_until(|| has_no_tasks(&tree, &root_key, &changes));
+    }
+
+    /// Like [`wait_until_empty()`][Root::wait_until_empty()], but returns a future which is ready once all tasks were
+    /// removed from the tree.
+    ///
+    /// Available with the `progress-tree-async` feature toggle.
+    #[cfg(feature = "progress-tree-async")]
+    pub fn wait_until_empty_async(&self) -> impl std::future::Future<Output = ()> + Send + 'static {
+        let (tree, root_key, changes) = self.tree_and_changes();
+        std::future::poll_fn(move |cx| changes.poll_until(cx, || has_no_tasks(&tree, &root_key, &changes)))
+    }
+
+    fn tree_and_changes(&self) -> (Arc<HashMap<Key, Task>>, Key, Arc<Changes>) {
+        let inner = self.inner.lock();
+        (Arc::clone(&inner.tree), inner.key.clone(), Arc::clone(&inner.changes))
+    }
+
     /// Adds a new child `tree::Item`, whose parent is this instance, with the given `name`.
     ///
     /// This builds a hierarchy of `tree::Item`s, each having their own progress.
@@ -54,15 +141,131 @@ impl Root {
         self.inner.lock().add_child_with_id(name, id)
     }
 
+    /// Returns the amount of steps per second the task at `key` currently makes, or `None` if there is no such task
+    /// or if it doesn't have progress.
+    ///
+    /// The rate is only updated while a renderer with throughput enabled samples the tree, and is `0.0` until then.
+    pub fn rate_for(&self, key: &Key) -> Option<f64> {
+        #[cfg(feature = "progress-tree-hp-hashmap")]
+        {
+            self.inner
+                .lock()
+                .tree
+                .get(key)
+                .and_then(|r| r.value().progress.as_ref().map(|p| p.rate()))
+        }
+        #[cfg(not(feature = "progress-tree-hp-hashmap"))]
+        {
+            self.inner
+                .lock()
+                .tree
+                .get(key, |v| v.progress.as_ref().map(|p| p.rate()))
+                .flatten()
+        }
+    }
+
     /// Copy the entire progress tree into the given `out` vector, so that
     /// it can be traversed from beginning to end in order of hierarchy.
     pub fn sorted_snapshot(&self, out: &mut Vec<(Key, Task)>) {
         out.clear();
         #[cfg(feature = "progress-tree-hp-hashmap")]
-        out.extend(self.inner.lock().tree.iter().map(|r| (*r.key(), r.value().clone())));
+        out.extend(
+            self.inner
+                .lock()
+                .tree
+                .iter()
+                .map(|r| (r.key().clone(), r.value().clone())),
+        );
         #[cfg(not(feature = "progress-tree-hp-hashmap"))]
         self.inner.lock().tree.extend_to(out);
-        out.sort_by_key(|t| t.0);
+        out.sort_by(|lhs, rhs| lhs.0.cmp(&rhs.0));
+        apply_pending_names(out);
+        if self.is_paused() {
+            apply_pause(out);
+        }
+        apply_child_order(out);
+    }
+
+    /// Call `f` with each task in the tree in no particular order, without copying them.
+    ///
+    /// This is cheaper than a [sorted snapshot][Root::sorted_snapshot()] for exporters and quick scans. Tasks added or
+    /// removed while visiting may or may not be seen, and `f` must not add, change or remove tasks of this tree as
+    /// doing so may deadlock.
+    pub fn for_each_task(&self, mut f: impl FnMut(&Key, &Task)) {
+        let tree = Arc::clone(&self.inner.lock().tree);
+        #[cfg(feature = "progress-tree-hp-hashmap")]
+        for r in tree.iter() {
+            f(r.key(), r.value())
+        }
+        #[cfg(not(feature = "progress-tree-hp-hashmap"))]
+        tree.for_each(&mut f);
+    }
+
+    /// Like [`for_each_task()`][Root::for_each_task()], but visits tasks in order of hierarchy.
+    ///
+    /// Unlike with [`sorted_snapshot()`][Root::sorted_snapshot()], siblings are always visited in the order they were
+    /// added and debounced names aren't applied. The order is cached, so tasks are only sorted again once they change.
+    pub fn for_each_task_sorted(&self, mut f: impl FnMut(&Key, &Task)) {
+        let tree = Arc::clone(&self.inner.lock().tree);
+        let mut keys = self.sorted_keys.lock();
+        #[cfg(feature = "progress-tree-hp-hashmap")]
+        let is_current = keys.len() == tree.len() && keys.iter().all(|key| tree.contains_key(key));
+        #[cfg(not(feature = "progress-tree-hp-hashmap"))]
+        let is_current = keys.len() == tree.len() && keys.iter().all(|key| tree.get(key, |_| ()).is_some());
+        if !is_current {
+            keys.clear();
+            #[cfg(feature = "progress-tree-hp-hashmap")]
+            keys.extend(tree.iter().map(|r| r.key().clone()));
+            #[cfg(not(feature = "progress-tree-hp-hashmap"))]
+            tree.for_each(|key, _| keys.push(key.clone()));
+            keys.sort_unstable();
+        }
+        for key in keys.iter() {
+            #[cfg(feature = "progress-tree-hp-hashmap")]
+            if let Some(r) = tree.get(key) {
+                f(key, r.value())
+            }
+            #[cfg(not(feature = "progress-tree-hp-hashmap"))]
+            tree.get(key, |task| f(key, task));
+        }
+    }
+
+    /// Return the task along with its key whose [id][Task::id] and the ids of its ancestors match `path`, starting at
+    /// the top level, or `None` if there is no such task or `path` is empty.
+    ///
+    /// If multiple tasks match, the one that was added first is returned.
+    pub fn find_by_id(&self, path: &[Id]) -> Option<(Key, Task)> {
+        let (id, parent_ids) = path.split_last()?;
+        let mut ids = std::collections::HashMap::new();
+        let mut candidates = Vec::new();
+        self.for_each_task(|key, task| {
+            ids.insert(key.clone(), task.id);
+            if key.level() as usize == path.len() && task.id == *id {
+                candidates.push((key.clone(), task.clone()));
+            }
+        });
+        candidates
+            .into_iter()
+            .filter(|(key, _)| {
+                parent_ids
+                    .iter()
+                    .rev()
+                    .zip(key.ancestors())
+                    .all(|(id, ancestor)| ids.get(&ancestor) == Some(id))
+            })
+            .min_by(|lhs, rhs| lhs.0.cmp(&rhs.0))
+    }
+
+    /// Return all tasks with the given `id` along with their keys, at any level and in order of hierarchy.
+    pub fn tasks_with_id(&self, id: Id) -> impl Iterator<Item = (Key, Task)> {
+        let mut tasks = Vec::new();
+        self.for_each_task(|key, task| {
+            if task.id == id {
+                tasks.push((key.clone(), task.clone()));
+            }
+        });
+        tasks.sort_by(|lhs, rhs| lhs.0.cmp(&rhs.0));
+        tasks.into_iter()
     }
 
     /// Copy all messages from the internal ring buffer into the given `out`
@@ -77,27 +280,293 @@ impl Root {
         self.inner.lock().messages.lock().copy_new(out, prev)
     }
 
+    /// Take a snapshot of all tasks in the tree, which can be displayed as indented plain-text tree.
+    pub fn snapshot(&self) -> Snapshot {
+        let mut tasks = Vec::with_capacity(self.num_tasks());
+        self.sorted_snapshot(&mut tasks);
+        Snapshot { tasks }
+    }
+
+    /// Return all tasks in the tree as indented plain text, one task per line, along with their progress and state.
+    ///
+    /// This is useful to log the state of the tree once, for example when a timeout or deadlock is detected, without
+    /// running a renderer.
+    pub fn format_tree(&self) -> String {
+        self.snapshot().to_string()
+    }
+
+    /// Stop accounting for time until [`resume_clock()`][Root::resume_clock()] is called.
+    ///
+    /// Call it before the system is suspended, so that renderers don't compute absurd throughput from the time spent
+    /// sleeping once it resumes.
+    pub fn pause_clock(&self) {
+        self.clock_paused.store(true, Ordering::SeqCst);
+    }
+
+    /// Account for time again after [`pause_clock()`][Root::pause_clock()] was called.
+    pub fn resume_clock(&self) {
+        self.clock_paused.store(false, Ordering::SeqCst);
+    }
+
+    /// Returns true if [`pause_clock()`][Root::pause_clock()] or [`pause()`][Root::pause()] was called without resuming.
+    pub fn is_clock_paused(&self) -> bool {
+        self.clock_paused.load(Ordering::SeqCst) || self.is_paused()
+    }
+
+    /// Pause the whole tree, for example while a debugger or a prompt waits for the user.
+    ///
+    /// Time isn't accounted for just like with [`pause_clock()`][Root::pause_clock()], so throughput and ETAs don't
+    /// drift, and all running tasks are [halted][State::Halted] with the reason `paused` in snapshots, so that renderers
+    /// show that they are paused. The tasks themselves are unaffected and may continue to make progress.
+    pub fn pause(&self) {
+        self.paused.store(true, Ordering::SeqCst);
+    }
+
+    /// Resume the tree after it was [paused][Root::pause()], showing tasks in their actual state again.
+    pub fn resume(&self) {
+        self.paused.store(false, Ordering::SeqCst);
+    }
+
+    /// Returns true if [`pause()`][Root::pause()] was called without resuming.
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::SeqCst)
+    }
+
+    /// Returns the time this tree was created, which renderers use to show how long the whole run took.
+    pub fn started_at(&self) -> SystemTime {
+        self.started_at
+    }
+
+    /// Return a subscription which receives a [`ProgressEvent`][crate::tree::ProgressEvent] for each change of the tree from now on, to react to
+    /// changes instead of polling [snapshots][Root::sorted_snapshot()].
+    ///
+    /// Up to `buffer_size` events are kept until they are received, see [`Subscription`] for how tasks never wait for
+    /// slow subscribers. The subscription ends once it's dropped.
+    /// **Note** that changes to steps are only seen if made through the [`Item`], not through its shared
+    /// [counter][crate::Count::counter()].
+    pub fn subscribe(&self, buffer_size: usize) -> Subscription {
+        self.changes.subscribe(buffer_size)
+    }
+
+    /// Remove all tasks which were kept in the tree after their item was dropped, as configured with
+    /// [`Options::retain_finished`].
+    pub fn prune_finished(&self) {
+        self.inner.lock().prune_finished()
+    }
+
+    /// Returns the amount of tasks in each state, without locking the tree.
+    ///
+    /// Only tasks [initialized][Item::init()] to receive progress have a state and are counted.
+    /// **Note** that like [`num_tasks()`][Root::num_tasks()], this is at most a guess as tasks change in parallel.
+    pub fn task_counts(&self) -> TaskCounts {
+        self.changes.counts()
+    }
+
+    /// Returns the amount of changes made to the tree so far, without locking it.
+    ///
+    /// Compare it with a previous value to cheaply learn if anything happened since, for instance in health checks.
+    /// Changes to the step of tasks aren't counted to keep them free of contention, observe them through
+    /// [`sorted_snapshot()`][Root::sorted_snapshot()] instead.
+    pub fn mutations(&self) -> u64 {
+        self.changes.mutations()
+    }
+
     /// Duplicate all content and return it.
     ///
     /// This is an expensive operation, whereas `clone()` is not as it is shallow.
     pub fn deep_clone(&self) -> Arc<Root> {
+        let inner = self.inner.lock().deep_clone();
         Arc::new(Root {
-            inner: Mutex::new(self.inner.lock().deep_clone()),
+            changes: Arc::clone(&inner.changes),
+            inner: Mutex::new(inner),
+            clock_paused: AtomicBool::new(self.clock_paused.load(Ordering::SeqCst)),
+            paused: AtomicBool::new(self.is_paused()),
+            sorted_keys: Default::default(),
+            started_at: self.started_at,
         })
     }
 }
 
+/// Return true if `tree` contains no task other than the one holding the name of the root at `root_key`, if set,
+/// and those retained by `changes` after their item was dropped.
+fn has_no_tasks(tree: &HashMap<Key, Task>, root_key: &Key, changes: &Changes) -> bool {
+    match tree.len().saturating_sub(changes.num_retained()) {
+        0 => true,
+        #[cfg(feature = "progress-tree-hp-hashmap")]
+        1 => tree.contains_key(root_key),
+        #[cfg(not(feature = "progress-tree-hp-hashmap"))]
+        1 => tree.get(root_key, |_| ()).is_some(),
+        _ => false,
+    }
+}
+
+/// Make the pending names of debounced renames visible if their debounce window passed.
+fn apply_pending_names(tasks: &mut [(Key, Task)]) {
+    let now = SystemTime::now();
+    for (_, task) in tasks {
+        if task
+            .pending_name
+            .as_ref()
+            .is_some_and(|(_, visible_at)| *visible_at <= now)
+        {
+            if let Some((name, _)) = task.pending_name.take() {
+                task.name = name;
+            }
+        }
+    }
+}
+
+/// Show all running tasks in `tasks` as halted while the tree is paused.
+fn apply_pause(tasks: &mut [(Key, Task)]) {
+    for progress in tasks.iter_mut().filter_map(|(_, task)| task.progress.as_mut()) {
+        if progress.state == State::Running {
+            progress.state = State::Halted("paused".into(), None);
+        }
+    }
+}
+
+/// Reorder the siblings in `tasks`, which are sorted by key, according to the [child order][Task::child_order] of their parent.
+///
+/// Each task stays followed by all of its descendants, keeping the hierarchy intact.
+fn apply_child_order(tasks: &mut Vec<(Key, Task)>) {
+    if tasks.iter().all(|(_, task)| task.child_order == ChildOrder::Creation) {
+        return;
+    }
+    fn append_ordered(siblings: &[(Key, Task)], order: ChildOrder, out: &mut Vec<(Key, Task)>) {
+        let level = match siblings.first() {
+            Some((key, _)) => key.level(),
+            None => return,
+        };
+        let mut subtrees: Vec<_> = Vec::new();
+        let mut start = 0;
+        for (idx, (key, _)) in siblings.iter().enumerate().skip(1) {
+            if key.level() <= level {
+                subtrees.push(&siblings[start..idx]);
+                start = idx;
+            }
+        }
+        subtrees.push(&siblings[start..]);
+        match order {
+            ChildOrder::Creation => {}
+            ChildOrder::Name => subtrees.sort_by(|a, b| a[0].1.name.cmp(&b[0].1.name)),
+            ChildOrder::Id => subtrees.sort_by_key(|subtree| subtree[0].1.id),
+            ChildOrder::Rank => subtrees.sort_by_key(|subtree| subtree[0].1.rank),
+        }
+        for subtree in subtrees {
+            let (parent, descendants) = subtree.split_first().expect("subtrees are never empty");
+            out.push(parent.clone());
+            append_ordered(descendants, parent.1.child_order, out);
+        }
+    }
+    let sorted_by_key = std::mem::take(tasks);
+    append_ordered(&sorted_by_key, ChildOrder::Creation, tasks);
+}
+
+/// All tasks of a [`Root`] at a point in time, as obtained by [`Root::snapshot()`].
+///
+/// Its [`Display`][std::fmt::Display] implementation produces an indented plain-text tree.
+#[derive(Clone, Debug, Default)]
+pub struct Snapshot {
+    /// All tasks in order of hierarchy.
+    pub tasks: Vec<(Key, Task)>,
+}
+
+impl fmt::Display for Snapshot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let min_level = self.tasks.iter().map(|(key, _)| key.level()).min().unwrap_or_default();
+        for (key, task) in &self.tasks {
+            let indent = (key.level() - min_level) as usize * 2;
+            write!(f, "{:indent$}{}", "", task.name, indent = indent)?;
+            if task.retries > 0 {
+                write!(f, " (attempt {})", task.attempt())?;
+            }
+            if let Some(progress) = &task.progress {
+                let step = progress.step.load(Ordering::Relaxed);
+                match (&progress.unit, progress.done_at) {
+                    (Some(unit), done_at) => write!(f, " {}", unit.display(step, done_at, None))?,
+                    (None, Some(done_at)) => write!(f, " {step}/{done_at}")?,
+                    (None, None) => write!(f, " {step}")?,
+                }
+                match &progress.state {
+                    State::Running => {}
+                    State::Blocked(reason, _) => write!(f, " [blocked: {reason}]")?,
+                    State::Halted(reason, _) => write!(f, " [halted: {reason}]")?,
+                    State::Finished(true) => write!(f, " [done]")?,
+                    State::Finished(false) => write!(f, " [failed]")?,
+                }
+            }
+            if let Some(note) = &task.note {
+                write!(f, " ({note})")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
 /// A way to configure new [`tree::Root`](./tree/struct.Root.html) instances
 /// ```rust
 /// let tree = prodash::tree::root::Options::default().create();
 /// let tree2 = prodash::tree::root::Options { message_buffer_capacity: 100, ..Default::default() }.create();
 /// ```
-#[derive(Clone, Debug)]
+#[derive(Clone)]
+#[cfg_attr(feature = "config", derive(serde::Deserialize), serde(default))]
 pub struct Options {
     /// The amount of [items][Item] the tree can hold without being forced to allocate.
     pub initial_capacity: usize,
     /// The amount of messages we can hold before we start overwriting old ones.
     pub message_buffer_capacity: usize,
+    /// The amount of the latest failure and success messages to keep even after they were overwritten by newer messages.
+    ///
+    /// This prevents them from being lost if many info messages are sent.
+    pub retained_message_capacity: usize,
+    /// If set, _(default: None)_, messages identical in level, origin and text to one sent within this duration before
+    /// are collapsed into it, and renderers show how often it was sent.
+    ///
+    /// This prevents retry loops from flooding the message buffer with the same failure.
+    pub message_dedupe_window: Option<Duration>,
+    /// If set, _(default: None)_, messages of tasks sending more of them than allowed are dropped, except for failures.
+    ///
+    /// This keeps a single task from pushing the messages of all others out of the message buffer. The amount of dropped
+    /// messages is available through [`Root::suppressed_messages()`].
+    pub message_rate_limit: Option<RateLimit>,
+    /// If set, _(default: None)_, renames of a task within this duration of its previous rename are coalesced,
+    /// and only the latest name becomes visible once the duration passed.
+    ///
+    /// This prevents columns of renderers from changing their width constantly if tasks are renamed rapidly.
+    pub name_debounce: Option<Duration>,
+    /// If true, _(default: false)_, all ancestors of a task that sends a [failure message][crate::messages::MessageLevel::Failure]
+    /// are marked as [having a failed descendant][Task::failed_descendant].
+    ///
+    /// This way renderers can point out failures at higher levels, even if the failed task itself isn't visible.
+    pub escalate_failures: bool,
+    /// If true, _(default: false)_, tasks are kept in the tree once their [item][Item] is dropped, in the
+    /// [finished][State::Finished] state if they have progress, until they are [pruned][Root::prune_finished()].
+    ///
+    /// This keeps short tasks from flickering in and out of renderers.
+    pub retain_finished: bool,
+    /// If set, _(default: None)_, the function is called whenever tasks are added or removed, initialized,
+    /// change their state or send a message.
+    ///
+    /// It is called on the thread making the change, so it should return quickly.
+    #[cfg_attr(feature = "config", serde(skip))]
+    pub on_event: Option<OnEvent>,
+}
+
+impl fmt::Debug for Options {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Options")
+            .field("initial_capacity", &self.initial_capacity)
+            .field("message_buffer_capacity", &self.message_buffer_capacity)
+            .field("retained_message_capacity", &self.retained_message_capacity)
+            .field("message_dedupe_window", &self.message_dedupe_window)
+            .field("message_rate_limit", &self.message_rate_limit)
+            .field("name_debounce", &self.name_debounce)
+            .field("escalate_failures", &self.escalate_failures)
+            .field("retain_finished", &self.retain_finished)
+            .field("on_event", &self.on_event.as_ref().map(|_| "<callback>"))
+            .finish()
+    }
 }
 
 impl Options {
@@ -113,6 +582,13 @@ impl Default for Options {
         Options {
             initial_capacity: 100,
             message_buffer_capacity: 20,
+            retained_message_capacity: 5,
+            message_dedupe_window: None,
+            message_rate_limit: None,
+            name_debounce: None,
+            escalate_failures: false,
+            retain_finished: false,
+            on_event: None,
         }
     }
 }
@@ -128,16 +604,42 @@ impl From<Options> for Root {
         Options {
             initial_capacity,
             message_buffer_capacity,
+            retained_message_capacity,
+            message_dedupe_window,
+            message_rate_limit,
+            name_debounce,
+            escalate_failures,
+            retain_finished,
+            on_event,
         }: Options,
     ) -> Self {
+        let mut messages =
+            MessageRingBuffer::with_capacity_and_retention(message_buffer_capacity, retained_message_capacity);
+        messages.set_dedupe_window(message_dedupe_window);
+        messages.set_rate_limit(message_rate_limit);
+        let changes = Arc::<Changes>::default();
         Root {
             inner: Mutex::new(Item {
                 highest_child_id: 0,
                 value: Arc::new(AtomicUsize::default()),
+                max: Arc::new(AtomicUsize::default()),
+                activity: Arc::new(AtomicUsize::default()),
+                cancel_token: Default::default(),
                 key: Key::default(),
                 tree: Arc::new(crate::tree::HashMap::with_capacity(initial_capacity)),
-                messages: Arc::new(Mutex::new(MessageRingBuffer::with_capacity(message_buffer_capacity))),
+                messages: Arc::new(Mutex::new(messages)),
+                name_debounce,
+                escalate_failures,
+                retain_finished,
+                failed: Default::default(),
+                on_event,
+                changes: Arc::clone(&changes),
             }),
+            changes,
+            clock_paused: AtomicBool::new(false),
+            paused: AtomicBool::new(false),
+            sorted_keys: Default::default(),
+            started_at: SystemTime::now(),
         }
     }
 }
@@ -176,4 +678,20 @@ impl crate::Root for Arc<Root> {
     fn downgrade(&self) -> Self::WeakRoot {
         Arc::downgrade(self)
     }
+
+    fn is_clock_paused(&self) -> bool {
+        self.deref().is_clock_paused()
+    }
+
+    fn started_at(&self) -> Option<SystemTime> {
+        Some(self.deref().started_at())
+    }
+
+    fn mutations(&self) -> Option<u64> {
+        Some(self.deref().mutations())
+    }
+
+    fn suppressed_messages(&self) -> usize {
+        self.deref().suppressed_messages()
+    }
 }
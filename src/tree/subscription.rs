@@ -0,0 +1,121 @@
+use std::collections::{HashMap, VecDeque};
+
+use parking_lot::{Condvar, Mutex};
+
+use crate::{
+    progress::Key,
+    tree::{ProgressEvent, Subscription, SubscriptionStats},
+};
+
+/// The events of a [`Subscription`] which weren't received yet.
+#[derive(Debug)]
+pub(crate) struct Queue {
+    state: Mutex<State>,
+    condvar: Condvar,
+    capacity: usize,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    events: VecDeque<ProgressEvent>,
+    /// The amount of events ever received, to turn the positions in `steps` into indices of `events`.
+    received: u64,
+    /// The position of the pending step change of each task.
+    steps: HashMap<Key, u64>,
+    stats: SubscriptionStats,
+    /// True once the tree is gone, so no more events will be pushed.
+    closed: bool,
+}
+
+impl State {
+    fn pop(&mut self) -> Option<ProgressEvent> {
+        let event = self.events.pop_front()?;
+        if let ProgressEvent::ValueChanged { key, .. } = &event {
+            if self.steps.get(key) == Some(&self.received) {
+                self.steps.remove(key);
+            }
+        }
+        self.received += 1;
+        Some(event)
+    }
+}
+
+impl Queue {
+    pub(crate) fn new(capacity: usize) -> Self {
+        Queue {
+            state: Default::default(),
+            condvar: Condvar::new(),
+            capacity,
+        }
+    }
+
+    /// Add `event`, merging it into the pending step change of the same task, or dropping it if we are full
+    /// unless it's a message.
+    pub(crate) fn push(&self, event: ProgressEvent) {
+        let mut state = self.state.lock();
+        let state = &mut *state;
+        if let ProgressEvent::ValueChanged { key, step } = &event {
+            if let Some(position) = state.steps.get(key) {
+                if let Some(ProgressEvent::ValueChanged { step: pending, .. }) =
+                    state.events.get_mut((position - state.received) as usize)
+                {
+                    *pending = *step;
+                }
+                state.stats.coalesced += 1;
+                return;
+            }
+        }
+        if state.events.len() >= self.capacity && !matches!(event, ProgressEvent::Message { .. }) {
+            state.stats.dropped += 1;
+            return;
+        }
+        if let ProgressEvent::ValueChanged { key, .. } = &event {
+            state
+                .steps
+                .insert(key.clone(), state.received + state.events.len() as u64);
+        }
+        state.events.push_back(event);
+        self.condvar.notify_all();
+    }
+
+    /// Let the subscription know that no more events will be pushed.
+    pub(crate) fn close(&self) {
+        self.state.lock().closed = true;
+        self.condvar.notify_all();
+    }
+}
+
+impl Subscription {
+    /// Block until the next event arrives and return it, or return `None` once the tree is gone and all of its events
+    /// were received.
+    pub fn recv(&self) -> Option<ProgressEvent> {
+        let mut state = self.queue.state.lock();
+        loop {
+            if let Some(event) = state.pop() {
+                return Some(event);
+            }
+            if state.closed {
+                return None;
+            }
+            self.queue.condvar.wait(&mut state);
+        }
+    }
+
+    /// Return the next event if there is one, without blocking.
+    pub fn try_recv(&self) -> Option<ProgressEvent> {
+        self.queue.state.lock().pop()
+    }
+
+    /// Return an iterator over all events which arrived so far, without blocking.
+    pub fn try_iter(&self) -> impl Iterator<Item = ProgressEvent> + '_ {
+        std::iter::from_fn(|| self.try_recv())
+    }
+
+    /// Return how many events were merged or dropped so far.
+    ///
+    /// Consumers may take a new [snapshot][crate::tree::Root::sorted_snapshot()] once events were dropped, as
+    /// they can't know the tree's current state from events alone anymore.
+    pub fn stats(&self) -> SubscriptionStats {
+        self.queue.state.lock().stats
+    }
+}
@@ -1,8 +1,8 @@
 mod message_buffer {
-    use crate::messages::{Message, MessageLevel, MessageRingBuffer};
+    use crate::messages::{Message, MessageLevel, MessageRingBuffer, RateLimit};
 
     fn push(buf: &mut MessageRingBuffer, msg: impl Into<String>) {
-        buf.push_overwrite(MessageLevel::Info, "test".into(), msg);
+        buf.push_overwrite(MessageLevel::Info, "test", msg);
     }
     fn push_and_copy_all(buf: &mut MessageRingBuffer, msg: impl Into<String>, out: &mut Vec<Message>) {
         push(buf, msg);
@@ -38,11 +38,104 @@ mod message_buffer {
         assert_messages(&out, &["four", "five"]);
     }
 
+    #[test]
+    fn failures_and_successes_are_retained_when_overwritten() {
+        let mut buf = MessageRingBuffer::with_capacity_and_retention(2, 1);
+        let mut out = Vec::new();
+        buf.push_overwrite(MessageLevel::Failure, "test", "failed");
+        buf.push_overwrite(MessageLevel::Success, "test", "succeeded");
+        push(&mut buf, "one");
+        buf.copy_all(&mut out);
+        assert_messages(&out, &["failed", "succeeded", "one"]);
+
+        push(&mut buf, "two");
+        push(&mut buf, "three");
+        buf.copy_all(&mut out);
+        assert_messages(&out, &["succeeded", "two", "three"]);
+        assert_eq!(buf.capacity(), 3);
+    }
+
+    #[test]
+    fn messages_of_origins_exceeding_the_rate_limit_are_dropped() {
+        let mut buf = MessageRingBuffer::with_capacity(10);
+        buf.set_dedupe_window(Some(std::time::Duration::from_secs(60)));
+        buf.set_rate_limit(Some(RateLimit {
+            messages: 2,
+            period: std::time::Duration::from_secs(60),
+        }));
+        let mut out = Vec::new();
+        buf.push_overwrite(MessageLevel::Failure, "fetch", "connection refused");
+        buf.push_overwrite(MessageLevel::Failure, "fetch", "connection refused");
+        buf.push_overwrite(MessageLevel::Info, "fetch", "retrying");
+        buf.push_overwrite(MessageLevel::Info, "fetch", "retrying with backoff");
+        buf.push_overwrite(MessageLevel::Info, "fetch", "retrying again");
+        buf.push_overwrite(MessageLevel::Info, "other", "still working");
+        buf.push_overwrite(MessageLevel::Failure, "fetch", "gave up");
+        buf.copy_all(&mut out);
+        assert_messages(
+            &out,
+            &[
+                "connection refused",
+                "retrying",
+                "retrying with backoff",
+                "still working",
+                "gave up",
+            ],
+        );
+        assert_eq!(out[0].count, 2);
+        assert_eq!(
+            buf.suppressed(),
+            1,
+            "failures neither count towards the limit nor are they dropped"
+        );
+
+        buf.set_rate_limit(Some(RateLimit {
+            messages: 1,
+            period: std::time::Duration::ZERO,
+        }));
+        buf.push_overwrite(MessageLevel::Info, "fetch", "one");
+        std::thread::sleep(std::time::Duration::from_millis(1));
+        buf.push_overwrite(MessageLevel::Info, "fetch", "two");
+        buf.copy_all(&mut out);
+        assert_eq!(out.len(), 7, "periods end, allowing more messages");
+        assert_eq!(buf.suppressed(), 1);
+    }
+
+    #[test]
+    fn identical_messages_within_the_dedupe_window_are_counted() {
+        let mut buf = MessageRingBuffer::with_capacity(3);
+        buf.set_dedupe_window(Some(std::time::Duration::from_secs(60)));
+        let mut out = Vec::new();
+        buf.push_overwrite(MessageLevel::Failure, "fetch", "connection refused");
+        push(&mut buf, "retrying");
+        buf.push_overwrite(MessageLevel::Failure, "fetch", "connection refused");
+        buf.push_overwrite(MessageLevel::Failure, "other", "connection refused");
+        buf.copy_all(&mut out);
+        assert_messages(&out, &["retrying", "connection refused", "connection refused"]);
+        assert_eq!(
+            out.iter()
+                .map(|m| (m.origin.name.as_str(), m.count))
+                .collect::<Vec<_>>(),
+            [("test", 1), ("fetch", 2), ("other", 1)],
+            "the message collapsed into is the newest of its origin, and messages of other origins are kept"
+        );
+
+        let state = buf.copy_new(&mut out, None);
+        push(&mut buf, "retrying");
+        buf.copy_new(&mut out, Some(state));
+        assert_messages(&out, &["retrying"]);
+        assert_eq!(out[0].count, 2, "consumers of new messages see the count change");
+        buf.copy_all(&mut out);
+        assert_eq!(out.len(), 3, "nothing was overwritten in the full buffer");
+    }
+
     mod copy_new {
         use crate::{
-            messages::{Message, MessageCopyState, MessageRingBuffer},
+            messages::{Message, MessageCopyState, MessageLevel, MessageRingBuffer},
             tree::tests::message_buffer::{assert_messages, push},
         };
+        use proptest::prelude::*;
+        use std::time::Duration;
 
         #[test]
         fn without_state() {
@@ -111,5 +204,1047 @@ mod message_buffer {
             buf.copy_new(&mut out, state);
             assert_messages(&out, &["2", "3"]);
         }
+
+        #[test]
+        fn state_of_another_buffer_is_ignored() {
+            let mut other = MessageRingBuffer::with_capacity(2);
+            push(&mut other, "old");
+            let state = other.copy_new(&mut Vec::new(), None);
+
+            let mut buf = MessageRingBuffer::with_capacity(2);
+            push(&mut buf, "one");
+            push(&mut buf, "two");
+            let mut out = Vec::new();
+            buf.copy_new(&mut out, Some(state));
+            assert_messages(&out, &["one", "two"]);
+        }
+
+        #[test]
+        fn overwritten_messages_are_copied_if_retained() {
+            let mut buf = MessageRingBuffer::with_capacity_and_retention(2, 1);
+            let mut out = Vec::new();
+            let state = buf.copy_new(&mut out, None);
+            buf.push_overwrite(MessageLevel::Failure, "test", "failed");
+            push(&mut buf, "one");
+            push(&mut buf, "two");
+            let state = buf.copy_new(&mut out, Some(state));
+            assert_messages(&out, &["failed", "one", "two"]);
+            assert_eq!(out.iter().map(|msg| msg.seq).collect::<Vec<_>>(), [0, 1, 2]);
+
+            push(&mut buf, "three");
+            buf.copy_new(&mut out, Some(state));
+            assert_messages(&out, &["three"]);
+        }
+
+        #[test]
+        fn buffers_without_capacity_only_keep_retained_messages() {
+            let mut buf = MessageRingBuffer::with_capacity_and_retention(0, 1);
+            let mut out = Vec::new();
+            let state = buf.copy_new(&mut out, None);
+            push(&mut buf, "dropped");
+            buf.push_overwrite(MessageLevel::Success, "test", "done");
+            buf.copy_new(&mut out, Some(state));
+            assert_messages(&out, &["done"]);
+            assert_eq!(out[0].seq, 1);
+        }
+
+        #[derive(Debug, Clone)]
+        enum Op {
+            Push { failure: bool, text: u8 },
+            Copy { consumer: usize },
+        }
+
+        fn op() -> impl Strategy<Value = Op> {
+            prop_oneof![
+                3 => (proptest::bool::weighted(0.2), 0..4u8).prop_map(|(failure, text)| Op::Push { failure, text }),
+                2 => (0..3usize).prop_map(|consumer| Op::Copy { consumer }),
+            ]
+        }
+
+        proptest! {
+            /// Push messages into buffers of any shape while consumers copy new messages at any time,
+            /// and check each copy against what the sequence numbers say it should contain.
+            #[test]
+            fn consumers_see_each_message_once_unless_they_fall_behind(
+                capacity in 0..5usize,
+                retention in 0..3usize,
+                dedupe in proptest::bool::weighted(0.3),
+                ops in proptest::collection::vec(op(), 0..50),
+            ) {
+                let mut buf = MessageRingBuffer::with_capacity_and_retention(capacity, retention);
+                if dedupe {
+                    buf.set_dedupe_window(Some(Duration::from_secs(3600)));
+                }
+                let mut consumers: Vec<Option<MessageCopyState>> = vec![None; 3];
+                let (mut out, mut all) = (Vec::new(), Vec::new());
+                for op in ops {
+                    let consumer = match op {
+                        Op::Push { failure, text } => {
+                            let level = if failure {
+                                MessageLevel::Failure
+                            } else {
+                                MessageLevel::Info
+                            };
+                            buf.push_overwrite(level, "test", text.to_string());
+                            continue;
+                        }
+                        Op::Copy { consumer } => &mut consumers[consumer],
+                    };
+                    let previous = *consumer;
+                    let state = buf.copy_new(&mut out, previous);
+                    *consumer = Some(state);
+
+                    buf.copy_all(&mut all);
+                    let seqs: Vec<_> = out.iter().map(|msg| msg.seq).collect();
+                    prop_assert!(
+                        seqs.windows(2).all(|w| w[0] < w[1]),
+                        "ordered without duplicates: {:?}",
+                        seqs
+                    );
+                    prop_assert!(seqs.last().is_none_or(|&last| last < state.next_seq()));
+                    let Some(previous) = previous else {
+                        prop_assert_eq!(&out, &all, "a consumer without state sees everything");
+                        continue;
+                    };
+                    let expected: Vec<_> = all
+                        .iter()
+                        .filter(|msg| msg.seq >= previous.next_seq())
+                        .cloned()
+                        .collect();
+                    prop_assert_eq!(&out, &expected, "exactly the messages stored since the previous copy");
+                    let stored = state.next_seq() - previous.next_seq();
+                    if stored > 0 && capacity > 0 {
+                        prop_assert_eq!(seqs.last(), Some(&(state.next_seq() - 1)), "the newest message is seen");
+                    }
+                }
+            }
+        }
+    }
+}
+
+mod item {
+    use crate::{
+        progress::{ChildOrder, Task},
+        tree::Root,
+    };
+
+    fn names(root: &Root) -> Vec<String> {
+        let mut out = Vec::new();
+        root.sorted_snapshot(&mut out);
+        out.into_iter().map(|(_, task)| task.name).collect()
+    }
+
+    #[test]
+    fn children_can_be_ordered_by_their_parent() {
+        let root = Root::new();
+        let mut parent = root.add_child("parent");
+        let mut b = parent.add_child("b");
+        let _b_child = b.add_child("b-child");
+        let a = parent.add_child("a");
+        let _other = root.add_child("other");
+        assert_eq!(
+            names(&root),
+            ["parent", "b", "b-child", "a", "other"],
+            "creation order by default"
+        );
+
+        parent.set_child_order(ChildOrder::Name);
+        assert_eq!(
+            names(&root),
+            ["parent", "a", "b", "b-child", "other"],
+            "children move along with their descendants"
+        );
+
+        parent.set_child_order(ChildOrder::Rank);
+        a.set_rank(1);
+        b.set_rank(-1);
+        assert_eq!(names(&root), ["parent", "b", "b-child", "a", "other"]);
+    }
+
+    #[test]
+    fn deeply_nested_tasks_keep_their_level() {
+        let root = Root::new();
+        let mut items = vec![root.add_child("level 1")];
+        for level in 2..=300 {
+            let child = items.last_mut().expect("non-empty").add_child(format!("level {level}"));
+            items.push(child);
+        }
+        let mut out = Vec::new();
+        root.sorted_snapshot(&mut out);
+        assert_eq!(out.len(), 300, "no task replaces another one");
+        assert_eq!(
+            out.iter().map(|(key, _)| key.level()).collect::<Vec<_>>(),
+            (1..=300).collect::<Vec<_>>(),
+            "each child is nested below its parent"
+        );
+    }
+
+    #[test]
+    fn ticks_animate_without_changing_the_step() {
+        let root = Root::new();
+        let item = root.add_child("scan");
+        item.tick();
+        item.tick();
+        let mut out = Vec::new();
+        root.sorted_snapshot(&mut out);
+        let progress = out[0].1.progress.as_ref().expect("ticking initializes the progress");
+        assert_eq!(progress.step.load(std::sync::atomic::Ordering::SeqCst), 0);
+        assert_eq!(progress.max(), None, "it's unbounded");
+        assert_eq!(progress.animation_step(), 2);
+
+        item.init(Some(10), None);
+        item.inc();
+        item.tick();
+        root.sorted_snapshot(&mut out);
+        assert_eq!(out[0].1.progress.as_ref().map(|p| p.animation_step()), Some(2));
+    }
+
+    #[cfg(not(feature = "minimal"))]
+    #[test]
+    fn messages_know_the_key_and_id_of_their_origin() {
+        let root = Root::new();
+        let mut item = root.add_child_with_id("task", *b"TASK");
+        item.info("hello");
+
+        let mut tasks = Vec::new();
+        root.sorted_snapshot(&mut tasks);
+        let mut messages = Vec::new();
+        root.copy_messages(&mut messages);
+        let origin = &messages[0].origin;
+        assert_eq!(origin.key, tasks[0].0);
+        assert_eq!(&origin.id, b"TASK");
+        assert_eq!(origin.to_string(), "task", "it displays as the name");
+    }
+
+    #[test]
+    fn markers_record_the_step_they_were_set_at() {
+        let root = Root::new();
+        let item = root.add_child("task");
+        item.init(Some(10), None);
+        item.set(3);
+        item.mark("headers done");
+        item.set(7);
+        item.mark("bodies done");
+
+        let mut out = Vec::new();
+        root.sorted_snapshot(&mut out);
+        let markers: Vec<_> = out[0].1.markers.iter().map(|m| (m.step, m.label.as_str())).collect();
+        assert_eq!(markers, [(3, "headers done"), (7, "bodies done")]);
+    }
+
+    #[cfg(not(feature = "minimal"))]
+    #[test]
+    fn measured_scopes_send_a_message_and_accumulate_per_label() {
+        let root = Root::new();
+        let item = root.add_child("task");
+        drop(item.measure("resolve deltas"));
+        drop(item.measure("resolve deltas").accumulate());
+        drop(item.measure("resolve deltas").accumulate());
+        drop(item.measure("checkout").accumulate());
+
+        let mut out = Vec::new();
+        root.sorted_snapshot(&mut out);
+        let timings: Vec<_> = out[0].1.timings.iter().map(|t| (t.label.as_str(), t.count)).collect();
+        assert_eq!(timings, [("resolve deltas", 2), ("checkout", 1)]);
+
+        let mut messages = Vec::new();
+        root.copy_messages(&mut messages);
+        assert_eq!(messages.len(), 4, "each scope sends a message");
+        assert!(messages[0].message.starts_with("resolve deltas took "));
+    }
+
+    #[cfg(not(feature = "minimal"))]
+    #[test]
+    fn renames_within_the_debounce_window_are_coalesced() {
+        let root: std::sync::Arc<Root> = crate::tree::root::Options {
+            name_debounce: Some(std::time::Duration::from_millis(50)),
+            ..Default::default()
+        }
+        .into();
+        let item = root.add_child("task");
+        item.set_name("one");
+        item.set_name("two");
+        item.set_name("three");
+        assert_eq!(names(&root), ["one"], "the first rename is visible immediately");
+        assert_eq!(item.name().as_deref(), Some("three"), "the latest name is known though");
+
+        std::thread::sleep(std::time::Duration::from_millis(60));
+        assert_eq!(names(&root), ["three"], "the latest name shows once the window passed");
+    }
+
+    #[cfg(not(feature = "minimal"))]
+    #[test]
+    fn events_are_emitted_for_changes_to_the_tree() {
+        use std::sync::{Arc, Mutex};
+
+        use crate::tree::Event;
+
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let root: Arc<Root> = crate::tree::root::Options {
+            on_event: Some(Arc::new({
+                let events = Arc::clone(&events);
+                move |event: Event<'_>| {
+                    let event = match event {
+                        Event::ChildAdded { name, .. } => format!("added {name}"),
+                        Event::ChildRemoved { .. } => "removed".into(),
+                        Event::Init { max, .. } => format!("init {max:?}"),
+                        Event::StateChanged { state, .. } => format!("state {state:?}"),
+                        Event::Message { origin, message, .. } => format!("message {origin}: {message}"),
+                    };
+                    events.lock().unwrap().push(event);
+                }
+            })),
+            ..Default::default()
+        }
+        .into();
+        let mut item = root.add_child("task");
+        item.init(Some(10), None);
+        item.blocked("waiting", None);
+        item.running();
+        item.info("hello");
+        drop(item);
+
+        assert_eq!(
+            *events.lock().unwrap(),
+            [
+                "added task",
+                "init Some(10)",
+                "state Blocked(\"waiting\", None)",
+                "state Running",
+                "message task: hello",
+                "removed"
+            ]
+        );
+    }
+
+    #[cfg(not(feature = "minimal"))]
+    #[test]
+    fn failures_are_escalated_to_all_ancestors_if_configured() {
+        for escalate_failures in [false, true] {
+            let root: std::sync::Arc<Root> = crate::tree::root::Options {
+                escalate_failures,
+                ..Default::default()
+            }
+            .into();
+            let mut parent = root.add_child("parent");
+            let mut child = parent.add_child("child");
+            let mut grandchild = child.add_child("grandchild");
+            let _sibling = root.add_child("sibling");
+            grandchild.info("still fine");
+            grandchild.fail("oops");
+
+            let mut out = Vec::new();
+            root.sorted_snapshot(&mut out);
+            let flagged: Vec<_> = out
+                .iter()
+                .filter(|(_, task)| task.failed_descendant)
+                .map(|(_, task)| task.name.as_str())
+                .collect();
+            if escalate_failures {
+                assert_eq!(flagged, ["parent", "child"]);
+            } else {
+                assert!(flagged.is_empty(), "nothing is escalated by default");
+            }
+        }
+    }
+
+    #[test]
+    fn tasks_are_overdue_once_they_exceed_their_expected_duration() {
+        use std::time::{Duration, SystemTime};
+
+        let root = Root::new();
+        let item = root.add_child("task");
+        let mut out = Vec::new();
+        root.sorted_snapshot(&mut out);
+        assert_eq!(
+            out[0].1.overdue_by(SystemTime::now()),
+            None,
+            "nothing is expected by default"
+        );
+
+        item.expect_duration(Duration::from_secs(10));
+        root.sorted_snapshot(&mut out);
+        let task = &out[0].1;
+        let (_, since) = task.expected_duration.expect("set");
+        assert_eq!(task.overdue_by(since + Duration::from_secs(10)), None, "within budget");
+        assert_eq!(
+            task.overdue_by(since + Duration::from_secs(12)),
+            Some(Duration::from_secs(2))
+        );
+    }
+
+    fn note_of(root: &Root) -> Option<String> {
+        let mut out = Vec::new();
+        root.sorted_snapshot(&mut out);
+        out.into_iter().next().and_then(|(_, Task { note, .. })| note)
+    }
+
+    #[test]
+    fn notes_are_cleared_when_running_or_done() {
+        let root = Root::new();
+        let mut item = root.add_child("task");
+        item.init(None, None);
+        assert_eq!(note_of(&root), None);
+
+        item.set_note("file.txt");
+        assert_eq!(note_of(&root).as_deref(), Some("file.txt"));
+        item.running();
+        assert_eq!(note_of(&root), None, "running() clears the note");
+
+        item.set_note("other.txt");
+        item.done("finished");
+        assert_eq!(note_of(&root), None, "done() clears the note as well");
+    }
+
+    #[test]
+    fn new_attempts_reset_progress_and_are_shown_along_with_the_name() {
+        let root = Root::new();
+        let task = root.add_child("download");
+        task.init(Some(10), None);
+        task.set(7);
+        assert!(
+            !root.format_tree().contains("attempt"),
+            "the first attempt isn't pointed out"
+        );
+
+        assert_eq!(task.new_attempt(), 2);
+        assert_eq!(task.new_attempt(), 3);
+        assert_eq!(task.step(), Some(0), "progress starts over");
+        let mut out = Vec::new();
+        root.sorted_snapshot(&mut out);
+        assert_eq!(out[0].1.retries, 2);
+        assert_eq!(out[0].1.attempt(), 3);
+        assert_eq!(root.format_tree(), "download (attempt 3) 0/10\n");
+    }
+
+    #[test]
+    fn tasks_know_when_they_were_created_and_finished() {
+        use std::time::{Duration, SystemTime};
+
+        let before = SystemTime::now();
+        let root = Root::new();
+        let mut item = root.add_child("task");
+        let task = &root.snapshot().tasks[0].1;
+        assert!(task.created_at.expect("set on creation") >= before);
+        assert_eq!(task.finished_at, None);
+
+        item.fail("failed");
+        let task = &root.snapshot().tasks[0].1;
+        let finished_at = task.finished_at.expect("set when failing");
+        assert!(finished_at >= task.created_at.unwrap());
+        assert_eq!(
+            task.elapsed(finished_at + Duration::from_secs(10)),
+            finished_at.duration_since(task.created_at.unwrap()).ok(),
+            "the time taken doesn't grow once finished"
+        );
+
+        item.done("done after all");
+        assert!(root.snapshot().tasks[0].1.finished_at >= Some(finished_at));
+    }
+
+    #[test]
+    fn tasks_finished_through_the_progress_trait_know_when_they_finished() {
+        use crate::Progress;
+
+        let root = Root::new();
+        let done = root.add_child("done");
+        let failed = root.add_child("failed");
+        (&done as &dyn Progress).done("done".into());
+        (&failed as &dyn Progress).fail("failed".into());
+
+        let mut out = Vec::new();
+        root.sorted_snapshot(&mut out);
+        assert_eq!(out.len(), 2);
+        assert!(
+            out.iter()
+                .all(|(_, task)| task.finished_at >= task.created_at && task.finished_at.is_some()),
+            "{out:?}"
+        );
+    }
+
+    #[cfg(not(feature = "minimal"))]
+    #[test]
+    fn equal_units_are_shared_between_tasks() {
+        use std::sync::Arc;
+
+        use crate::unit::{self, display, DisplayValue};
+
+        struct Stateless;
+        impl DisplayValue for Stateless {
+            fn dyn_hash(&self, state: &mut dyn std::hash::Hasher) {
+                state.write(&[])
+            }
+            fn display_unit(&self, w: &mut dyn std::fmt::Write, _value: usize) -> std::fmt::Result {
+                w.write_str("stateless")
+            }
+        }
+
+        let root = Root::new();
+        let units = [
+            Some(unit::label("items")),
+            Some(unit::label("items")),
+            Some(unit::label_and_mode("items", display::Mode::with_percentage())),
+            Some(unit::dynamic(unit::Range::new("steps"))),
+            Some(unit::dynamic(unit::Range::new("steps"))),
+            Some(unit::dynamic(Stateless)),
+            Some(unit::dynamic(Stateless)),
+        ];
+        let _tasks: Vec<_> = units
+            .into_iter()
+            .enumerate()
+            .map(|(idx, unit)| {
+                let task = root.add_child(idx.to_string());
+                task.init(None, unit);
+                task
+            })
+            .collect();
+        let mut tasks = Vec::new();
+        root.sorted_snapshot(&mut tasks);
+        let units: Vec<_> = tasks
+            .iter()
+            .map(|(_, task)| task.progress.as_ref().and_then(|p| p.unit.clone()).expect("unit set"))
+            .collect();
+
+        assert!(Arc::ptr_eq(&units[0], &units[1]), "equal labels are interned");
+        assert!(
+            !Arc::ptr_eq(&units[0], &units[2]),
+            "the display mode is part of the unit"
+        );
+        assert!(
+            !Arc::ptr_eq(&units[3], &units[4]),
+            "dynamic labels with state can't be compared and are never shared"
+        );
+        assert!(
+            Arc::ptr_eq(&units[5], &units[6]),
+            "dynamic labels without state are all the same"
+        );
+    }
+
+    #[cfg(feature = "minimal")]
+    #[test]
+    fn minimal_ignores_messages_names_and_units_but_counts_steps() {
+        let root = Root::new();
+        let item = root.add_child("task");
+        item.init(Some(10), Some("files".into()));
+        item.set_name("renamed");
+        item.message(crate::messages::MessageLevel::Info, "hello");
+        item.inc_by(3);
+
+        assert_eq!(names(&root), ["task"]);
+        assert!(item.unit().is_none());
+        assert_eq!(item.max(), Some(10));
+        assert_eq!(item.step(), Some(3));
+        let mut messages = Vec::new();
+        root.copy_messages(&mut messages);
+        assert!(messages.is_empty());
+    }
+}
+
+mod root {
+    use crate::{
+        progress::State,
+        tree::{ProgressEvent, Root, SubscriptionStats, TaskCounts},
+    };
+
+    #[test]
+    fn named_root_is_shown_as_first_entry_at_level_zero() {
+        let root = Root::new();
+        let _child = root.add_child("task");
+        assert_eq!(root.name(), None);
+        assert_eq!(root.num_tasks(), 1);
+
+        root.set_name("app");
+        root.set_name("my app");
+        assert_eq!(root.name().as_deref(), Some("my app"));
+
+        let mut out = Vec::new();
+        root.sorted_snapshot(&mut out);
+        assert_eq!(out.len(), 2, "the root is added once");
+        assert_eq!(out[0].0.level(), 0);
+        assert_eq!(out[0].1.name, "my app");
+        assert!(out[0].1.progress.is_none(), "it's an organizational unit");
+    }
+
+    #[cfg(not(feature = "minimal"))]
+    #[test]
+    fn messages_dropped_by_the_rate_limit_are_counted_but_failures_pass() {
+        use std::{sync::Arc, time::Duration};
+
+        use crate::messages::RateLimit;
+
+        let root: Arc<Root> = crate::tree::root::Options {
+            message_rate_limit: Some(RateLimit {
+                messages: 1,
+                period: Duration::from_secs(60),
+            }),
+            ..Default::default()
+        }
+        .into();
+        let mut item = root.add_child("task");
+        item.info("first");
+        item.info("second");
+        item.fail("broken");
+        assert_eq!(root.suppressed_messages(), 1);
+        assert_eq!(
+            crate::Root::suppressed_messages(&root),
+            1,
+            "the trait forwards the count"
+        );
+
+        let mut messages = Vec::new();
+        root.copy_messages(&mut messages);
+        assert_eq!(
+            messages.iter().map(|m| m.message.as_str()).collect::<Vec<_>>(),
+            ["first", "broken"]
+        );
+    }
+
+    #[test]
+    fn memory_estimate_grows_with_tasks_and_messages() {
+        let root = Root::new();
+        let empty = root.memory_estimate();
+        assert!(empty >= std::mem::size_of::<Root>());
+
+        let mut tasks: Vec<_> = (0..100).map(|idx| root.add_child(format!("task {idx}"))).collect();
+        let with_tasks = root.memory_estimate();
+        assert!(
+            with_tasks - empty >= 100 * std::mem::size_of::<(crate::progress::Key, crate::progress::Task)>(),
+            "each task is accounted for"
+        );
+
+        tasks[0].info("a message that takes some space");
+        assert!(root.memory_estimate() >= with_tasks);
+        drop(tasks);
+        assert!(root.memory_estimate() < with_tasks, "dropped tasks don't count anymore");
+    }
+
+    #[test]
+    fn for_each_task_visits_all_tasks_optionally_in_order() {
+        let root = Root::new();
+        let mut a = root.add_child("a");
+        let _b = root.add_child("b");
+        let _a1 = a.add_child("a1");
+
+        let mut names = Vec::new();
+        root.for_each_task(|_, task| names.push(task.name.clone()));
+        names.sort();
+        assert_eq!(names, ["a", "a1", "b"]);
+
+        let mut sorted = Vec::new();
+        root.for_each_task_sorted(|_, task| sorted.push(task.name.clone()));
+        assert_eq!(sorted, ["a", "a1", "b"], "in order of hierarchy");
+
+        drop(a);
+        let _c = root.add_child("c");
+        sorted.clear();
+        root.for_each_task_sorted(|_, task| sorted.push(task.name.clone()));
+        assert_eq!(
+            sorted,
+            ["a1", "b", "c"],
+            "the cached order is updated once tasks change"
+        );
+    }
+
+    #[cfg(not(feature = "minimal"))]
+    #[test]
+    fn format_tree_shows_indented_tasks_with_progress_and_state() {
+        let root = Root::with_parent_name("app");
+        let mut task = root.add_child("task");
+        task.init(Some(10), None);
+        task.set(3);
+        task.set_note("file.txt");
+        let sub = task.add_child("sub-task");
+        sub.init(None, Some(crate::unit::label("items")));
+        sub.set(5);
+        sub.blocked("waiting", None);
+        let _unbounded = root.add_child("organizer");
+
+        assert_eq!(
+            root.format_tree(),
+            "app\n  task 3/10 (file.txt)\n    sub-task 5 items [blocked: waiting]\n  organizer\n"
+        );
+        assert_eq!(root.snapshot().tasks.len(), 4);
+    }
+
+    #[test]
+    fn clock_can_be_paused_and_resumed() {
+        let root = Root::new();
+        assert!(!root.is_clock_paused());
+        root.pause_clock();
+        assert!(crate::Root::is_clock_paused(&root), "the trait forwards to the tree");
+        assert!(root.deep_clone().is_clock_paused(), "the state is cloned as well");
+        root.resume_clock();
+        assert!(!root.is_clock_paused());
+    }
+
+    #[test]
+    fn pausing_the_tree_halts_running_tasks_in_snapshots_and_pauses_the_clock() {
+        let root = Root::new();
+        let running = root.add_child("running");
+        running.init(Some(10), None);
+        let blocked = root.add_child("blocked");
+        blocked.init(None, None);
+        blocked.tick();
+        blocked.blocked("waiting", None);
+        let states = |root: &Root| -> Vec<_> {
+            root.snapshot()
+                .tasks
+                .into_iter()
+                .map(|(_, task)| task.progress.expect("initialized").state)
+                .collect()
+        };
+
+        root.pause();
+        assert!(root.is_paused());
+        assert!(root.is_clock_paused(), "time isn't accounted for while paused");
+        assert!(root.deep_clone().is_paused(), "the state is cloned as well");
+        assert_eq!(
+            states(&root),
+            [
+                State::Halted("paused".into(), None),
+                State::Blocked("waiting".into(), None)
+            ],
+            "only running tasks are shown as paused"
+        );
+
+        root.resume();
+        assert!(!root.is_clock_paused());
+        assert_eq!(states(&root), [State::Running, State::Blocked("waiting".into(), None)]);
+    }
+
+    #[test]
+    fn start_time_is_provided_to_renderers() {
+        let before = std::time::SystemTime::now();
+        let root = Root::new();
+        assert!(root.started_at() >= before);
+        assert_eq!(
+            crate::Root::started_at(&root),
+            Some(root.started_at()),
+            "the trait forwards to the tree"
+        );
+        assert_eq!(
+            root.deep_clone().started_at(),
+            root.started_at(),
+            "the clone started at the same time"
+        );
+    }
+
+    #[test]
+    fn tasks_are_counted_by_state_along_with_mutations() {
+        let root = Root::new();
+        let mut task = root.add_child("task");
+        let organizer = root.add_child("organizer");
+        assert_eq!(
+            root.task_counts(),
+            TaskCounts::default(),
+            "organizational units have no state"
+        );
+
+        task.init(Some(10), None);
+        let sub = task.add_child("sub-task");
+        sub.tick();
+        sub.blocked("waiting", None);
+        assert_eq!(
+            root.task_counts(),
+            TaskCounts {
+                running: 1,
+                blocked: 1,
+                halted: 0,
+                finished: 0
+            }
+        );
+
+        let mutations = root.mutations();
+        task.set(5);
+        assert_eq!(root.mutations(), mutations, "steps aren't counted");
+        sub.halted("interruptible", None);
+        assert!(root.mutations() > mutations);
+        assert_eq!(
+            crate::Root::mutations(&root),
+            Some(root.mutations()),
+            "the trait forwards to the tree"
+        );
+
+        let clone = root.deep_clone();
+        assert_eq!(clone.task_counts(), root.task_counts(), "counts are cloned as well");
+
+        drop(sub);
+        drop(organizer);
+        assert_eq!(
+            root.task_counts(),
+            TaskCounts {
+                running: 1,
+                ..Default::default()
+            },
+            "dropped tasks don't count anymore"
+        );
+        task.init(None, None);
+        assert_eq!(
+            root.task_counts(),
+            TaskCounts::default(),
+            "tasks without progress have no state"
+        );
+    }
+
+    #[cfg(not(feature = "minimal"))]
+    #[test]
+    fn subscribers_receive_events_for_each_change() {
+        let root = Root::new();
+        let events = root.subscribe(100);
+        let mut task = root.add_child("task");
+        task.init(Some(10), None);
+        task.inc();
+        task.set(5);
+        task.blocked("waiting", None);
+        task.info("hello");
+        let key = task.key.clone();
+        drop(task);
+
+        let events: Vec<_> = events.try_iter().collect();
+        assert_eq!(events.len(), 6, "{events:?}");
+        assert!(matches!(&events[0], ProgressEvent::ChildAdded { name, .. } if name == "task"));
+        assert!(matches!(
+            &events[1],
+            ProgressEvent::Init {
+                max: Some(10),
+                unit: None,
+                ..
+            }
+        ));
+        assert!(
+            matches!(&events[2], ProgressEvent::ValueChanged { step: 5, .. }),
+            "step changes are merged"
+        );
+        assert!(matches!(
+            &events[3],
+            ProgressEvent::StateChanged {
+                state: State::Blocked(reason, None),
+                ..
+            } if reason == "waiting"
+        ));
+        assert!(matches!(&events[4], ProgressEvent::Message { message, .. } if message == "hello"));
+        assert!(matches!(&events[5], ProgressEvent::ChildRemoved { key: removed } if *removed == key));
+    }
+
+    #[test]
+    fn subscribers_drop_events_they_have_no_room_for_and_are_removed_once_gone() {
+        let root = Root::new();
+        let events = root.subscribe(1);
+        let task = root.add_child("task");
+        task.init(Some(10), None);
+        assert_eq!(events.try_iter().count(), 1, "the buffer was full");
+        assert_eq!(
+            events.stats(),
+            SubscriptionStats {
+                coalesced: 0,
+                dropped: 1
+            }
+        );
+
+        drop(events);
+        task.set(2);
+        assert!(
+            !root.changes.has_subscribers(),
+            "dropped subscriptions are removed on the next event"
+        );
+    }
+
+    #[cfg(not(feature = "minimal"))]
+    #[test]
+    fn subscribers_receive_the_latest_step_of_each_task_and_all_messages() {
+        let root = Root::new();
+        let events = root.subscribe(3);
+        let mut a = root.add_child("a");
+        let b = root.add_child("b");
+        for step in 1..=10 {
+            a.set(step);
+        }
+        b.set(1);
+        a.info("kept even though the buffer is full");
+        b.set(2);
+
+        assert_eq!(
+            events.stats(),
+            SubscriptionStats {
+                coalesced: 9,
+                dropped: 2
+            }
+        );
+        let received: Vec<_> = events.try_iter().collect();
+        assert_eq!(received.len(), 4, "{received:?}");
+        assert!(matches!(&received[2], ProgressEvent::ValueChanged { step: 10, .. }));
+        assert!(matches!(&received[3], ProgressEvent::Message { .. }));
+
+        a.inc();
+        assert!(matches!(
+            events.try_recv(),
+            Some(ProgressEvent::ValueChanged { step: 11, .. })
+        ));
+        a.inc();
+        assert!(
+            matches!(events.try_recv(), Some(ProgressEvent::ValueChanged { step: 12, .. })),
+            "received steps aren't merged into"
+        );
+    }
+
+    #[test]
+    fn subscriptions_end_once_the_tree_is_gone() {
+        let root = Root::new();
+        let events = root.subscribe(10);
+        let task = root.add_child("task");
+        let worker = std::thread::spawn(move || {
+            std::thread::sleep(std::time::Duration::from_millis(10));
+            drop(task);
+            drop(root);
+        });
+        let received: Vec<_> = std::iter::from_fn(|| events.recv()).collect();
+        assert!(matches!(&received[0], ProgressEvent::ChildAdded { .. }));
+        assert!(matches!(&received[1], ProgressEvent::ChildRemoved { .. }));
+        assert!(events.recv().is_none(), "the subscription stays at its end");
+        worker.join().unwrap();
+    }
+
+    #[test]
+    fn waiting_until_empty_returns_once_all_tasks_are_dropped() {
+        let root = Root::with_parent_name("app");
+        root.wait_until_empty();
+
+        let mut task = root.add_child("task");
+        let sub = task.add_child("sub-task");
+        let worker = std::thread::spawn(move || {
+            std::thread::sleep(std::time::Duration::from_millis(10));
+            drop(sub);
+            drop(task);
+        });
+        root.wait_until_empty();
+        assert_eq!(root.num_tasks(), 1, "only the root name remains");
+        worker.join().unwrap();
+    }
+
+    #[test]
+    fn tasks_can_be_found_by_the_path_of_their_ids() {
+        let root = Root::with_parent_name("app");
+        let mut fetch = root.add_child_with_id("fetch", *b"FTCH");
+        let _index = fetch.add_child_with_id("index", *b"INDX");
+        let mut build = root.add_child_with_id("build", *b"BILD");
+        let _build_index = build.add_child_with_id("index of build", *b"INDX");
+        let _second_fetch = root.add_child_with_id("fetch again", *b"FTCH");
+
+        let (key, task) = root.find_by_id(&[*b"BILD", *b"INDX"]).expect("present");
+        assert_eq!(task.name, "index of build");
+        assert_eq!(key.level(), 2);
+        assert_eq!(
+            root.find_by_id(&[*b"FTCH"]).map(|(_, task)| task.name).as_deref(),
+            Some("fetch"),
+            "the first match wins"
+        );
+        assert!(root.find_by_id(&[*b"INDX"]).is_none(), "paths start at the top level");
+        assert!(root.find_by_id(&[*b"FTCH", *b"BILD"]).is_none());
+        assert!(root.find_by_id(&[]).is_none());
+
+        let names: Vec<_> = root.tasks_with_id(*b"INDX").map(|(_, task)| task.name).collect();
+        assert_eq!(names, ["index", "index of build"]);
+        assert_eq!(root.tasks_with_id(*b"NONE").count(), 0);
+    }
+
+    #[test]
+    fn finished_tasks_can_be_retained_until_they_are_pruned() {
+        let root: std::sync::Arc<Root> = crate::tree::root::Options {
+            retain_finished: true,
+            ..Default::default()
+        }
+        .into();
+        let mut done = root.add_child("done");
+        done.init(Some(10), None);
+        let mut failed = root.add_child("failed");
+        failed.init(None, None);
+        failed.tick();
+        let organizer = root.add_child("organizer");
+
+        done.done("all good");
+        failed.fail("oh no");
+        drop(done);
+        drop(failed);
+        drop(organizer);
+
+        let mut out = Vec::new();
+        root.sorted_snapshot(&mut out);
+        let states: Vec<_> = out
+            .iter()
+            .map(|(_, task)| (task.name.as_str(), task.progress.as_ref().map(|p| p.state.clone())))
+            .collect();
+        assert_eq!(
+            states,
+            vec![
+                ("done", Some(State::Finished(true))),
+                ("failed", Some(State::Finished(false))),
+                ("organizer", None)
+            ]
+        );
+        assert!(out.iter().all(|(_, task)| task.finished_at.is_some()));
+        assert_eq!(
+            root.task_counts(),
+            TaskCounts {
+                finished: 2,
+                ..Default::default()
+            }
+        );
+        root.wait_until_empty();
+
+        root.prune_finished();
+        assert_eq!(root.num_tasks(), 0);
+        assert_eq!(root.task_counts(), TaskCounts::default());
+    }
+
+    #[test]
+    fn tasks_finished_through_the_progress_trait_are_retained_with_their_outcome() {
+        use crate::{BoxedDynNestedProgress, Progress};
+
+        let root: std::sync::Arc<Root> = crate::tree::root::Options {
+            retain_finished: true,
+            ..Default::default()
+        }
+        .into();
+        let done = root.add_child("done");
+        done.init(Some(10), None);
+        let failed = root.add_child("failed");
+        failed.init(Some(10), None);
+        let mut boxed = BoxedDynNestedProgress::new(root.add_child("boxed"));
+        boxed.init(Some(10), None);
+
+        (&done as &dyn Progress).done("all good".into());
+        (&failed as &dyn Progress).fail("oh no".into());
+        boxed.fail("oh no".into());
+        drop((done, failed, boxed));
+
+        let mut out = Vec::new();
+        root.sorted_snapshot(&mut out);
+        let states: Vec<_> = out
+            .iter()
+            .map(|(_, task)| (task.name.as_str(), task.progress.as_ref().map(|p| p.state.clone())))
+            .collect();
+        assert_eq!(
+            states,
+            vec![
+                ("done", Some(State::Finished(true))),
+                ("failed", Some(State::Finished(false))),
+                ("boxed", Some(State::Finished(false)))
+            ]
+        );
+    }
+
+    #[cfg(feature = "progress-tree-async")]
+    #[test]
+    fn waiting_until_empty_asynchronously_returns_once_all_tasks_are_dropped() {
+        let root = Root::new();
+        let task = root.add_child("task");
+        let until_empty = root.wait_until_empty_async();
+        let worker = std::thread::spawn(move || {
+            std::thread::sleep(std::time::Duration::from_millis(10));
+            drop(task);
+        });
+        futures::executor::block_on(until_empty);
+        assert_eq!(root.num_tasks(), 0);
+        worker.join().unwrap();
     }
 }
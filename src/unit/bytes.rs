@@ -1,6 +1,9 @@
 use std::fmt;
 
-use crate::{progress::Step, unit::DisplayValue};
+use crate::{
+    progress::Step,
+    unit::{display, DisplayValue},
+};
 
 /// A marker for formatting numbers as bytes in renderers.
 #[derive(Copy, Clone, Default, Eq, PartialEq, Ord, PartialOrd, Debug)]
@@ -32,3 +35,58 @@ impl DisplayValue for Bytes {
         Ok(())
     }
 }
+
+/// A marker for formatting numbers as bytes in renderers, while showing throughput in bits per second like network tools do.
+///
+/// For example, a transfer of 1.5MB per second is shown as `|12.0Mb/s|`.
+#[derive(Copy, Clone, Default, Eq, PartialEq, Ord, PartialOrd, Debug)]
+pub struct BytesAsBits;
+
+impl BytesAsBits {
+    fn format_bits(w: &mut dyn fmt::Write, bytes: Step) -> fmt::Result {
+        const UNITS: [&str; 6] = ["b", "kb", "Mb", "Gb", "Tb", "Pb"];
+        let bits = bytes as f64 * 8.0;
+        let exponent = ((bits.max(1.0).log10() / 3.0).floor() as usize).min(UNITS.len() - 1);
+        match exponent {
+            0 => write!(w, "{}{}", bits, UNITS[0]),
+            _ => write!(w, "{:.1}{}", bits / 1000_f64.powi(exponent as i32), UNITS[exponent]),
+        }
+    }
+}
+
+impl DisplayValue for BytesAsBits {
+    fn display_current_value(&self, w: &mut dyn fmt::Write, value: Step, _upper: Option<Step>) -> fmt::Result {
+        Bytes::format_bytes(w, value)
+    }
+    fn display_upper_bound(&self, w: &mut dyn fmt::Write, upper_bound: Step, _value: Step) -> fmt::Result {
+        Bytes::format_bytes(w, upper_bound)
+    }
+
+    fn dyn_hash(&self, state: &mut dyn std::hash::Hasher) {
+        state.write(&[8])
+    }
+
+    fn display_unit(&self, _w: &mut dyn fmt::Write, _value: Step) -> fmt::Result {
+        Ok(())
+    }
+
+    fn display_throughput(&self, w: &mut dyn fmt::Write, throughput: &display::Throughput) -> fmt::Result {
+        let (fraction, unit) = self.fraction_and_time_unit(throughput.timespan);
+        let display_rate = |w: &mut dyn fmt::Write, value: Step| -> fmt::Result {
+            Self::format_bits(w, value)?;
+            w.write_char('/')?;
+            if let Some(fraction) = fraction {
+                write!(w, "{}", fraction)?;
+            }
+            w.write_str(unit)
+        };
+        w.write_char('|')?;
+        display_rate(w, throughput.value_change_in_timespan)?;
+        if let Some(peak) = throughput.peak_value_change_in_timespan {
+            w.write_str(" (peak ")?;
+            display_rate(w, peak)?;
+            w.write_char(')')?;
+        }
+        w.write_char('|')
+    }
+}
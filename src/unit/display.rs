@@ -1,4 +1,4 @@
-use std::fmt::{self, Write};
+use std::fmt;
 
 use crate::{
     progress::Step,
@@ -7,6 +7,7 @@ use crate::{
 
 /// The location at which [`Throughput`] or [`UnitDisplays`][UnitDisplay] should be placed.
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[allow(missing_docs)]
 pub enum Location {
     BeforeValue,
@@ -20,6 +21,8 @@ pub struct Throughput {
     pub value_change_in_timespan: Step,
     /// The amount of time passed between the previous and the current value.
     pub timespan: std::time::Duration,
+    /// The highest value change within `timespan` observed so far, if known.
+    pub peak_value_change_in_timespan: Option<Step>,
 }
 
 impl Throughput {
@@ -28,16 +31,33 @@ impl Throughput {
         Throughput {
             value_change_in_timespan,
             timespan,
+            peak_value_change_in_timespan: None,
         }
     }
+
+    /// Set the highest value change within our timespan observed so far to `peak`.
+    pub fn with_peak(mut self, peak: Step) -> Self {
+        self.peak_value_change_in_timespan = Some(peak);
+        self
+    }
 }
 
 /// A way to display a [Unit].
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Mode {
     location: Location,
     percent: bool,
-    throughput: bool,
+    throughput: ShowThroughput,
+}
+
+/// How much of the throughput to show, ordered from least to most.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+enum ShowThroughput {
+    Nothing,
+    Current,
+    CurrentAndPeak,
 }
 
 impl Mode {
@@ -50,7 +70,7 @@ impl Mode {
     }
 
     fn throughput_location(&self) -> Option<Location> {
-        if self.throughput {
+        if self.throughput != ShowThroughput::Nothing {
             Some(self.location)
         } else {
             None
@@ -64,7 +84,7 @@ impl Mode {
     pub fn with_percentage() -> Self {
         Mode {
             percent: true,
-            throughput: false,
+            throughput: ShowThroughput::Nothing,
             location: Location::AfterUnit,
         }
     }
@@ -72,7 +92,7 @@ impl Mode {
     pub fn with_throughput() -> Self {
         Mode {
             percent: false,
-            throughput: true,
+            throughput: ShowThroughput::Current,
             location: Location::AfterUnit,
         }
     }
@@ -83,7 +103,14 @@ impl Mode {
     }
     /// Turn on throughput display on the current instance.
     pub fn and_throughput(mut self) -> Self {
-        self.throughput = true;
+        self.throughput = self.throughput.max(ShowThroughput::Current);
+        self
+    }
+    /// Turn on throughput display along with the highest throughput seen so far, like `|5/s (peak 20/s)|`.
+    ///
+    /// This makes it easy to see if an operation slowed down.
+    pub fn and_peak_throughput(mut self) -> Self {
+        self.throughput = ShowThroughput::CurrentAndPeak;
         self
     }
     /// Change the display location to show up in front of the value.
@@ -135,8 +162,9 @@ impl<'a> UnitDisplay<'a> {
     }
 }
 
-impl<'a> fmt::Display for UnitDisplay<'a> {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+impl<'a> UnitDisplay<'a> {
+    /// Write what's configured to be displayed to `f`, which allows reusing buffers instead of allocating new strings.
+    pub fn write_to(&self, f: &mut dyn fmt::Write) -> fmt::Result {
         let unit: &dyn DisplayValue = self.parent.as_display_value();
         let mode = self.parent.mode;
 
@@ -145,15 +173,24 @@ impl<'a> fmt::Display for UnitDisplay<'a> {
                 .map(|location| (location, ((self.current_value as f64 / upper as f64) * 100.0).floor()))
         });
         let throughput_and_location = self.throughput.as_ref().and_then(|throughput| {
-            mode.and_then(|m| m.throughput_location())
-                .map(|location| (location, throughput))
+            mode.and_then(|m| {
+                m.throughput_location().map(|location| {
+                    let throughput = Throughput {
+                        peak_value_change_in_timespan: throughput
+                            .peak_value_change_in_timespan
+                            .filter(|_| m.throughput == ShowThroughput::CurrentAndPeak),
+                        ..throughput.clone()
+                    };
+                    (location, throughput)
+                })
+            })
         });
         if self.display.values() {
             if let Some((Location::BeforeValue, fraction)) = percent_location_and_fraction {
                 unit.display_percentage(f, fraction)?;
                 f.write_char(' ')?;
             }
-            if let Some((Location::BeforeValue, throughput)) = throughput_and_location {
+            if let Some((Location::BeforeValue, throughput)) = &throughput_and_location {
                 unit.display_throughput(f, throughput)?;
                 f.write_char(' ')?;
             }
@@ -164,21 +201,18 @@ impl<'a> fmt::Display for UnitDisplay<'a> {
             }
         }
         if self.display.unit() {
-            let mut buf = String::with_capacity(10);
-            if self.display.values() {
-                buf.write_char(' ')?;
-            }
-            unit.display_unit(&mut buf, self.current_value)?;
-            if buf.len() > 1 {
-                // did they actually write a unit?
-                f.write_str(&buf)?;
-            }
+            // Only separate the unit from the values if they actually write a unit.
+            let mut unit_writer = PrefixOnFirstWrite {
+                inner: &mut *f,
+                prefix: self.display.values().then_some(' '),
+            };
+            unit.display_unit(&mut unit_writer, self.current_value)?;
 
             if let Some((Location::AfterUnit, fraction)) = percent_location_and_fraction {
                 f.write_char(' ')?;
                 unit.display_percentage(f, fraction)?;
             }
-            if let Some((Location::AfterUnit, throughput)) = throughput_and_location {
+            if let Some((Location::AfterUnit, throughput)) = &throughput_and_location {
                 f.write_char(' ')?;
                 unit.display_throughput(f, throughput)?;
             }
@@ -186,3 +220,27 @@ impl<'a> fmt::Display for UnitDisplay<'a> {
         Ok(())
     }
 }
+
+impl<'a> fmt::Display for UnitDisplay<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_to(f)
+    }
+}
+
+/// Writes `prefix` to `inner` right before the first non-empty string, and nothing if nothing else is written.
+struct PrefixOnFirstWrite<'a> {
+    inner: &'a mut dyn fmt::Write,
+    prefix: Option<char>,
+}
+
+impl fmt::Write for PrefixOnFirstWrite<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        if s.is_empty() {
+            return Ok(());
+        }
+        if let Some(prefix) = self.prefix.take() {
+            self.inner.write_char(prefix)?;
+        }
+        self.inner.write_str(s)
+    }
+}
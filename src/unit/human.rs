@@ -4,6 +4,75 @@ pub use human_format::{Formatter, Scales};
 
 use crate::{progress::Step, unit::DisplayValue};
 
+/// Returns a formatter for compact numbers like `1.2k` or `3.4M`, with a single decimal and without a space before the scale.
+pub fn compact() -> Formatter {
+    let mut formatter = Formatter::new();
+    formatter.with_decimals(1).with_separator("");
+    formatter
+}
+
+/// Returns scales grouping digits by ten thousands as common in East Asia, as in `1.2万` or `3.4亿`.
+///
+/// Use them with [`Formatter::with_scales()`].
+pub fn myriad_scales() -> Scales {
+    let mut scales = Scales::new();
+    scales.with_base(10_000).with_suffixes(vec!["", "万", "亿", "兆", "京"]);
+    scales
+}
+
+/// How the digits of numbers formatted by [`Human`] are separated, to follow local conventions.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct Separators {
+    /// The separator between groups of digits of the integer part, like the thousands separator `,` in `1,234`,
+    /// or `None` to not group digits.
+    pub group: Option<char>,
+    /// The amount of digits per group, like `3` in `1,234` or `4` in `1,2345`.
+    pub group_size: usize,
+    /// The separator between the integer part and the decimals, like `.` in `1.5` or `,` in `1,5`.
+    pub decimal: char,
+}
+
+impl Default for Separators {
+    fn default() -> Self {
+        Separators {
+            group: None,
+            group_size: 3,
+            decimal: '.',
+        }
+    }
+}
+
+impl Separators {
+    /// Write the number at the beginning of `formatted` to `w` with our separators, followed by everything else as is.
+    fn write(&self, w: &mut dyn fmt::Write, formatted: &str) -> fmt::Result {
+        let number_len = formatted
+            .find(|c: char| !(c.is_ascii_digit() || c == '.' || c == '-'))
+            .unwrap_or(formatted.len());
+        let (number, rest) = formatted.split_at(number_len);
+        let (integer, decimals) = match number.split_once('.') {
+            Some((integer, decimals)) => (integer, Some(decimals)),
+            None => (number, None),
+        };
+        let digits = integer.trim_start_matches('-');
+        w.write_str(&integer[..integer.len() - digits.len()])?;
+        for (idx, digit) in digits.char_indices() {
+            let remaining = digits.len() - idx;
+            if let Some(group) = self
+                .group
+                .filter(|_| idx > 0 && self.group_size > 0 && remaining % self.group_size == 0)
+            {
+                w.write_char(group)?;
+            }
+            w.write_char(digit)?;
+        }
+        if let Some(decimals) = decimals {
+            w.write_char(self.decimal)?;
+            w.write_str(decimals)?;
+        }
+        w.write_str(rest)
+    }
+}
+
 /// A helper for formatting numbers in a format easily read by humans in renderers, as in `2.54 million objects`
 #[derive(Debug)]
 pub struct Human {
@@ -11,19 +80,31 @@ pub struct Human {
     pub name: &'static str,
     /// The formatter to format the actual numbers.
     pub formatter: Formatter,
+    /// The separators to use within the numbers produced by `formatter`.
+    pub separators: Separators,
 }
 
 impl Human {
     /// A convenience method to create a new new instance and its `formatter` and `name` fields.
     pub fn new(formatter: Formatter, name: &'static str) -> Self {
-        Human { name, formatter }
+        Human {
+            name,
+            formatter,
+            separators: Separators::default(),
+        }
+    }
+    /// Create a new instance formatting numbers [compactly][compact()], as in `1.2k objects`.
+    pub fn compact(name: &'static str) -> Self {
+        Human::new(compact(), name)
+    }
+    /// Use `separators` within numbers, as in `1.234,5 objects`.
+    pub fn with_separators(mut self, separators: Separators) -> Self {
+        self.separators = separators;
+        self
     }
     fn format_bytes(&self, w: &mut dyn fmt::Write, value: Step) -> fmt::Result {
         let string = self.formatter.format(value as f64);
-        for token in string.split(' ') {
-            w.write_str(token)?;
-        }
-        Ok(())
+        self.separators.write(w, &string.replace(' ', ""))
     }
 }
 
@@ -45,3 +126,92 @@ impl DisplayValue for Human {
         w.write_str(self.name)
     }
 }
+
+/// A unit which formats the integers written by another unit with a [`Formatter`], keeping everything else as is.
+///
+/// This makes [compact numbers][compact()] available to units like [`Range`][crate::unit::Range] or plain labels,
+/// as in `2.0k of 3.4M steps`. Values written as anything but an integer, like `1.5KB`, are left untouched.
+#[derive(Debug)]
+pub struct Compact<T> {
+    /// The unit whose integers to format.
+    pub inner: T,
+    /// The formatter for integers of `1000` and more, smaller ones are written as they are.
+    pub formatter: Formatter,
+}
+
+impl<T: DisplayValue> Compact<T> {
+    /// Format the integers written by `inner` [compactly][compact()].
+    pub fn new(inner: T) -> Self {
+        Compact::with_formatter(inner, compact())
+    }
+    /// Format the integers written by `inner` with `formatter`.
+    pub fn with_formatter(inner: T, formatter: Formatter) -> Self {
+        Compact { inner, formatter }
+    }
+    fn write_number(
+        &self,
+        w: &mut dyn fmt::Write,
+        write_inner: impl Fn(&mut dyn fmt::Write) -> fmt::Result,
+    ) -> fmt::Result {
+        let mut integer = Integer::default();
+        match write_inner(&mut integer).ok().and(integer.0) {
+            Some(value) if value < 1000 => write!(w, "{value}"),
+            Some(value) => w.write_str(&self.formatter.format(value as f64)),
+            None => write_inner(w),
+        }
+    }
+}
+
+/// Parses the decimal integer written to it, and fails on anything else.
+#[derive(Default)]
+struct Integer(Option<Step>);
+
+impl fmt::Write for Integer {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for byte in s.bytes() {
+            let digit = match byte {
+                b'0'..=b'9' => (byte - b'0') as Step,
+                _ => return Err(fmt::Error),
+            };
+            self.0 = Some(
+                self.0
+                    .unwrap_or_default()
+                    .checked_mul(10)
+                    .and_then(|value| value.checked_add(digit))
+                    .ok_or(fmt::Error)?,
+            );
+        }
+        Ok(())
+    }
+}
+
+impl<T: DisplayValue> DisplayValue for Compact<T> {
+    fn display_current_value(&self, w: &mut dyn fmt::Write, value: Step, upper: Option<Step>) -> fmt::Result {
+        self.write_number(w, |w| self.inner.display_current_value(w, value, upper))
+    }
+
+    fn separator(&self, w: &mut dyn fmt::Write, value: Step, upper: Option<Step>) -> fmt::Result {
+        self.inner.separator(w, value, upper)
+    }
+
+    fn display_upper_bound(&self, w: &mut dyn fmt::Write, upper_bound: Step, value: Step) -> fmt::Result {
+        self.write_number(w, |w| self.inner.display_upper_bound(w, upper_bound, value))
+    }
+
+    fn dyn_hash(&self, state: &mut dyn Hasher) {
+        self.inner.dyn_hash(state);
+        state.write(b"compact");
+    }
+
+    fn display_unit(&self, w: &mut dyn fmt::Write, value: Step) -> fmt::Result {
+        self.inner.display_unit(w, value)
+    }
+
+    fn display_percentage(&self, w: &mut dyn fmt::Write, percentage: f64) -> fmt::Result {
+        self.inner.display_percentage(w, percentage)
+    }
+
+    fn fraction_and_time_unit(&self, timespan: std::time::Duration) -> (Option<f64>, &'static str) {
+        self.inner.fraction_and_time_unit(timespan)
+    }
+}
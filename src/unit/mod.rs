@@ -1,11 +1,16 @@
-use std::{fmt, ops::Deref, sync::Arc};
+use std::{
+    any::TypeId,
+    fmt,
+    ops::Deref,
+    sync::{Arc, Mutex, PoisonError},
+};
 
 use crate::progress::Step;
 
 #[cfg(feature = "unit-bytes")]
 mod bytes;
 #[cfg(feature = "unit-bytes")]
-pub use bytes::Bytes;
+pub use bytes::{Bytes, BytesAsBits};
 
 #[cfg(feature = "unit-duration")]
 mod duration;
@@ -93,7 +98,7 @@ pub fn label_and_mode(label: &'static str, mode: display::Mode) -> Unit {
 /// Returns a unit that is a dynamic `label`.
 pub fn dynamic(label: impl DisplayValue + Send + Sync + 'static) -> Unit {
     Unit {
-        kind: Kind::Dynamic(Arc::new(label)),
+        kind: Kind::Dynamic(shared_label(label)),
         mode: None,
     }
 }
@@ -101,13 +106,103 @@ pub fn dynamic(label: impl DisplayValue + Send + Sync + 'static) -> Unit {
 /// Returns a unit that is a dynamic `label` along with information on where to display a fraction and throughput.
 pub fn dynamic_and_mode(label: impl DisplayValue + Send + Sync + 'static, mode: display::Mode) -> Unit {
     Unit {
-        kind: Kind::Dynamic(Arc::new(label)),
+        kind: Kind::Dynamic(shared_label(label)),
         mode: Some(mode),
     }
 }
 
+/// Return `label` as trait object, which is the same instance for all labels of a type without state.
+fn shared_label<T: DisplayValue + Send + Sync + 'static>(label: T) -> Arc<dyn DisplayValue + Send + Sync> {
+    type Shared = Arc<dyn DisplayValue + Send + Sync>;
+    static STATELESS: Mutex<Vec<(TypeId, Shared)>> = Mutex::new(Vec::new());
+    if std::mem::size_of::<T>() != 0 {
+        return Arc::new(label);
+    }
+    let mut stateless = STATELESS.lock().unwrap_or_else(PoisonError::into_inner);
+    if let Some((_, shared)) = stateless.iter().find(|(type_id, _)| *type_id == TypeId::of::<T>()) {
+        return Arc::clone(shared);
+    }
+    let shared: Shared = Arc::new(label);
+    stateless.push((TypeId::of::<T>(), Arc::clone(&shared)));
+    shared
+}
+
+/// A `Unit` as it is serialized.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename = "Unit")]
+struct SerializedUnit<'a> {
+    label: std::borrow::Cow<'a, str>,
+    mode: Option<display::Mode>,
+}
+
+/// Serialize the label and display mode, where dynamic labels are serialized as the unit they display.
+///
+/// This means that dynamic labels which format values, like `Bytes`, lose their formatting.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Unit {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let label = match self.kind {
+            Kind::Label(label) => label.into(),
+            Kind::Dynamic(ref label) => {
+                let mut buf = String::new();
+                label.display_unit(&mut buf, 0).map_err(serde::ser::Error::custom)?;
+                buf.into()
+            }
+        };
+        SerializedUnit { label, mode: self.mode }.serialize(serializer)
+    }
+}
+
+/// Deserialize as dynamic label owning its string, as only labels known at compile time can be static.
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Unit {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let SerializedUnit { label, mode } = SerializedUnit::deserialize(deserializer)?;
+        Ok(Unit {
+            kind: Kind::Dynamic(Arc::new(label.into_owned())),
+            mode,
+        })
+    }
+}
+
 /// Display and utilities
 impl Unit {
+    /// Return `self` in shared form, which is the same instance for all equal static labels and stateless dynamic
+    /// labels like `Bytes`, so tasks using the same unit don't each hold their own copy of it.
+    #[cfg_attr(not(feature = "progress-tree"), allow(dead_code))]
+    pub(crate) fn into_shared(self) -> Arc<Unit> {
+        static INTERNED: Mutex<Vec<Arc<Unit>>> = Mutex::new(Vec::new());
+        if matches!(&self.kind, Kind::Dynamic(label) if std::mem::size_of_val(&**label) != 0) {
+            return Arc::new(self);
+        }
+        let mut interned = INTERNED.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some(unit) = interned.iter().find(|unit| unit.is_interchangeable_with(&self)) {
+            return Arc::clone(unit);
+        }
+        let unit = Arc::new(self);
+        interned.push(Arc::clone(&unit));
+        unit
+    }
+
+    fn is_interchangeable_with(&self, other: &Unit) -> bool {
+        self.mode == other.mode
+            && match (&self.kind, &other.kind) {
+                (Kind::Label(lhs), Kind::Label(rhs)) => lhs == rhs,
+                (Kind::Dynamic(lhs), Kind::Dynamic(rhs)) => Arc::ptr_eq(lhs, rhs),
+                _ => false,
+            }
+    }
+
+    /// Return the approximate amount of bytes the label of this unit occupies on the heap.
+    #[cfg(feature = "progress-tree")]
+    pub(crate) fn heap_size(&self) -> usize {
+        match &self.kind {
+            Kind::Label(_) => 0,
+            Kind::Dynamic(value) => 2 * std::mem::size_of::<usize>() + std::mem::size_of_val(&**value),
+        }
+    }
+
     /// Create a representation of `self` implementing [`Display`][std::fmt::Display] in configurable fashion.
     ///
     /// * `current_value` is the progress value to display.
@@ -130,6 +225,18 @@ impl Unit {
         }
     }
 
+    /// Write `current_value` along with `upper_bound` and `throughput` to `out` as [`display()`][Unit::display()] would,
+    /// but without allocating, so renderers can reuse their buffers across frames.
+    pub fn write_to(
+        &self,
+        out: &mut dyn fmt::Write,
+        current_value: Step,
+        upper_bound: Option<Step>,
+        throughput: impl Into<Option<display::Throughput>>,
+    ) -> fmt::Result {
+        self.display(current_value, upper_bound, throughput).write_to(out)
+    }
+
     /// Return `self` as trait object implementing `DisplayValue`.
     pub fn as_display_value(&self) -> &dyn DisplayValue {
         match self.kind {
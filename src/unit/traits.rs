@@ -44,14 +44,23 @@ pub trait DisplayValue {
     /// Emit the `throughput` of an operation to `w`.
     fn display_throughput(&self, w: &mut dyn fmt::Write, throughput: &display::Throughput) -> fmt::Result {
         let (fraction, unit) = self.fraction_and_time_unit(throughput.timespan);
+        let display_rate = |w: &mut dyn fmt::Write, value: Step| -> fmt::Result {
+            self.display_current_value(w, value, None)?;
+            w.write_char('/')?;
+            match fraction {
+                Some(fraction) => w.write_fmt(format_args!("{}", fraction)),
+                None => Ok(()),
+            }?;
+            w.write_str(unit)
+        };
         w.write_char('|')?;
-        self.display_current_value(w, throughput.value_change_in_timespan, None)?;
-        w.write_char('/')?;
-        match fraction {
-            Some(fraction) => w.write_fmt(format_args!("{}", fraction)),
-            None => Ok(()),
-        }?;
-        w.write_fmt(format_args!("{}|", unit))
+        display_rate(w, throughput.value_change_in_timespan)?;
+        if let Some(peak) = throughput.peak_value_change_in_timespan {
+            w.write_str(" (peak ")?;
+            display_rate(w, peak)?;
+            w.write_char(')')?;
+        }
+        w.write_char('|')
     }
 
     /// Given a `timespan`, return a fraction of the timespan based on the given unit, i.e. `(possible fraction, unit`).
@@ -91,3 +100,13 @@ impl DisplayValue for &'static str {
         w.write_fmt(format_args!("{}", self))
     }
 }
+
+impl DisplayValue for String {
+    fn dyn_hash(&self, state: &mut dyn Hasher) {
+        state.write(self.as_bytes())
+    }
+
+    fn display_unit(&self, w: &mut dyn fmt::Write, _value: usize) -> fmt::Result {
+        w.write_str(self)
+    }
+}
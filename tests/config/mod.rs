@@ -0,0 +1,14 @@
+use prodash::tree::root::Options;
+
+#[test]
+fn partial_tree_options_use_defaults_for_missing_fields() {
+    let opts: Options = prodash::config::from_str("message_buffer_capacity = 5").unwrap();
+    assert_eq!(opts.message_buffer_capacity, 5);
+    assert_eq!(opts.initial_capacity, Options::default().initial_capacity);
+}
+
+#[test]
+fn invalid_input_is_reported_as_invalid_data() {
+    let err = prodash::config::from_str::<Options>("message_buffer_capacity = 'many'").unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+}
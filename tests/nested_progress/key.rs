@@ -5,6 +5,53 @@ fn size_in_memory() {
     assert_eq!(std::mem::size_of::<Key>(), 24);
 }
 
+mod ancestry {
+    use prodash::progress::Key;
+
+    #[test]
+    fn parents_and_ancestors_lead_up_to_the_root() {
+        let root = Key::default();
+        let parent = root.add_child(1);
+        let child = parent.add_child(2);
+        let grandchild = child.add_child(3);
+
+        assert_eq!(root.parent(), None, "the root has no parent");
+        assert_eq!(parent.parent(), Some(root.clone()));
+        assert_eq!(grandchild.parent(), Some(child.clone()));
+        assert_eq!(
+            grandchild.ancestors().collect::<Vec<_>>(),
+            [child, parent, root.clone()]
+        );
+        assert_eq!(root.ancestors().count(), 0);
+    }
+
+    #[test]
+    fn ancestors_are_recognized_across_levels() {
+        let root = Key::default();
+        let parent = root.add_child(1);
+        let child = parent.add_child(2);
+        let other_child = root.add_child(2).add_child(2);
+
+        assert!(root.is_ancestor_of(&child));
+        assert!(parent.is_ancestor_of(&child));
+        assert!(!child.is_ancestor_of(&child), "keys are no ancestors of themselves");
+        assert!(!child.is_ancestor_of(&parent));
+        assert!(!parent.is_ancestor_of(&other_child), "the path needs to match as well");
+    }
+
+    #[test]
+    fn hierarchies_can_be_deeper_than_what_is_stored_inline() {
+        let root = Key::default();
+        let deepest = (0..20).fold(root.clone(), |key, id| key.add_child(id));
+
+        assert_eq!(deepest.level(), 20);
+        assert_eq!(deepest.ancestors().count(), 20);
+        assert!(root.add_child(0).is_ancestor_of(&deepest));
+        assert!(!root.add_child(1).is_ancestor_of(&deepest));
+        assert_eq!(deepest.ancestors().last(), Some(root));
+    }
+}
+
 mod adjacency {
     use prodash::progress::{
         key::{Adjacency, SiblingLocation::*},
@@ -13,7 +60,7 @@ mod adjacency {
 
     fn to_kv(keys: &[Key]) -> Vec<(Key, Task)> {
         let mut v: Vec<_> = keys.iter().map(|k| (k.to_owned(), Task::default())).collect();
-        v.sort_by_key(|v| v.0);
+        v.sort_by(|lhs, rhs| lhs.0.cmp(&rhs.0));
         v
     }
 
@@ -27,10 +74,10 @@ mod adjacency {
         let p2 = r.add_child(2);
         to_kv(
             &[
-                p1,
+                p1.clone(),
                 p1.add_child(1),
                 p1.add_child(2),
-                p2,
+                p2.clone(),
                 p2.add_child(1),
                 p2.add_child(2),
             ][..],
@@ -41,7 +88,7 @@ mod adjacency {
         let r = Key::default();
         let p1 = r.add_child(1);
         let p2 = p1.add_child(2);
-        to_kv(&[p1, p2, p2.add_child(1)][..])
+        to_kv(&[p1, p2.clone(), p2.add_child(1)][..])
     }
 
     fn root_with_three_levels_two_siblings_on_level_2() -> Vec<(Key, Task)> {
@@ -49,7 +96,7 @@ mod adjacency {
         let p1 = r.add_child(1);
         let p11 = p1.add_child(1);
         let p12 = p1.add_child(2);
-        to_kv(&[p1, p11, p11.add_child(1), p12, p12.add_child(1)][..])
+        to_kv(&[p1, p11.clone(), p11.add_child(1), p12.clone(), p12.add_child(1)][..])
     }
 
     #[test]
@@ -57,11 +104,11 @@ mod adjacency {
         let entries = root_with_two_children();
         assert_eq!(
             Key::adjacency(&entries, 0),
-            Adjacency(AboveAndBelow, NotFound, NotFound, NotFound, NotFound, NotFound)
+            Adjacency::from([AboveAndBelow, NotFound, NotFound, NotFound, NotFound, NotFound])
         );
         assert_eq!(
             Key::adjacency(&entries, 1),
-            Adjacency(Above, NotFound, NotFound, NotFound, NotFound, NotFound)
+            Adjacency::from([Above, NotFound, NotFound, NotFound, NotFound, NotFound])
         );
     }
 
@@ -70,30 +117,30 @@ mod adjacency {
         let entries = root_with_two_children_with_two_children();
         assert_eq!(
             Key::adjacency(&entries, 0),
-            Adjacency(AboveAndBelow, NotFound, NotFound, NotFound, NotFound, NotFound)
+            Adjacency::from([AboveAndBelow, NotFound, NotFound, NotFound, NotFound, NotFound])
         );
         {
             assert_eq!(
                 Key::adjacency(&entries, 1),
-                Adjacency(AboveAndBelow, AboveAndBelow, NotFound, NotFound, NotFound, NotFound)
+                Adjacency::from([AboveAndBelow, AboveAndBelow, NotFound, NotFound, NotFound, NotFound])
             );
             assert_eq!(
                 Key::adjacency(&entries, 2),
-                Adjacency(AboveAndBelow, Above, NotFound, NotFound, NotFound, NotFound)
+                Adjacency::from([AboveAndBelow, Above, NotFound, NotFound, NotFound, NotFound])
             );
         }
         assert_eq!(
             Key::adjacency(&entries, 3),
-            Adjacency(Above, NotFound, NotFound, NotFound, NotFound, NotFound)
+            Adjacency::from([Above, NotFound, NotFound, NotFound, NotFound, NotFound])
         );
         {
             assert_eq!(
                 Key::adjacency(&entries, 4),
-                Adjacency(NotFound, AboveAndBelow, NotFound, NotFound, NotFound, NotFound)
+                Adjacency::from([NotFound, AboveAndBelow, NotFound, NotFound, NotFound, NotFound])
             );
             assert_eq!(
                 Key::adjacency(&entries, 5),
-                Adjacency(NotFound, Above, NotFound, NotFound, NotFound, NotFound)
+                Adjacency::from([NotFound, Above, NotFound, NotFound, NotFound, NotFound])
             );
         }
     }
@@ -103,17 +150,17 @@ mod adjacency {
         let entries = root_with_three_levels();
         assert_eq!(
             Key::adjacency(&entries, 0),
-            Adjacency(Above, NotFound, NotFound, NotFound, NotFound, NotFound)
+            Adjacency::from([Above, NotFound, NotFound, NotFound, NotFound, NotFound])
         );
         {
             assert_eq!(
                 Key::adjacency(&entries, 1),
-                Adjacency(NotFound, Above, NotFound, NotFound, NotFound, NotFound)
+                Adjacency::from([NotFound, Above, NotFound, NotFound, NotFound, NotFound])
             );
             {
                 assert_eq!(
                     Key::adjacency(&entries, 2),
-                    Adjacency(NotFound, NotFound, Above, NotFound, NotFound, NotFound)
+                    Adjacency::from([NotFound, NotFound, Above, NotFound, NotFound, NotFound])
                 );
             }
         }
@@ -125,34 +172,46 @@ mod adjacency {
         {
             assert_eq!(
                 Key::adjacency(&entries, 0),
-                Adjacency(Above, NotFound, NotFound, NotFound, NotFound, NotFound)
+                Adjacency::from([Above, NotFound, NotFound, NotFound, NotFound, NotFound])
             );
             {
                 assert_eq!(
                     Key::adjacency(&entries, 1),
-                    Adjacency(NotFound, AboveAndBelow, NotFound, NotFound, NotFound, NotFound)
+                    Adjacency::from([NotFound, AboveAndBelow, NotFound, NotFound, NotFound, NotFound])
                 );
                 {
                     assert_eq!(
                         Key::adjacency(&entries, 2),
-                        Adjacency(NotFound, AboveAndBelow, Above, NotFound, NotFound, NotFound)
+                        Adjacency::from([NotFound, AboveAndBelow, Above, NotFound, NotFound, NotFound])
                     );
                 }
 
                 assert_eq!(
                     Key::adjacency(&entries, 3),
-                    Adjacency(NotFound, Above, NotFound, NotFound, NotFound, NotFound)
+                    Adjacency::from([NotFound, Above, NotFound, NotFound, NotFound, NotFound])
                 );
                 {
                     assert_eq!(
                         Key::adjacency(&entries, 4),
-                        Adjacency(NotFound, NotFound, Above, NotFound, NotFound, NotFound)
+                        Adjacency::from([NotFound, NotFound, Above, NotFound, NotFound, NotFound])
                     );
                 }
             }
         }
     }
 
+    #[test]
+    fn deeply_nested_siblings() {
+        let parent = (0..10).fold(Key::default(), |key, id| key.add_child(id));
+        let entries = to_kv(&[parent.clone(), parent.add_child(1), parent.add_child(2)][..]);
+        let mut expected = [NotFound; 11];
+        expected[10] = AboveAndBelow;
+        assert_eq!(Key::adjacency(&entries, 1), Adjacency::from(expected));
+        expected[10] = Above;
+        assert_eq!(Key::adjacency(&entries, 2), Adjacency::from(expected));
+        assert_eq!(Key::adjacency(&entries, 2).level(), 11);
+    }
+
     #[test]
     fn orphaned_child_node() {
         let mut entries = root_with_two_children();
@@ -160,18 +219,18 @@ mod adjacency {
             1,
             (Key::default().add_child(0).add_child(0).add_child(1), Task::default()),
         );
-        entries.sort_by_key(|v| v.0);
+        entries.sort_by(|lhs, rhs| lhs.0.cmp(&rhs.0));
         assert_eq!(
             Key::adjacency(&entries, 0),
-            Adjacency(AboveAndBelow, NotFound, NotFound, NotFound, NotFound, NotFound),
+            Adjacency::from([AboveAndBelow, NotFound, NotFound, NotFound, NotFound, NotFound]),
         );
         assert_eq!(
             Key::adjacency(&entries, 1),
-            Adjacency(AboveAndBelow, NotFound, NotFound, NotFound, NotFound, NotFound)
+            Adjacency::from([AboveAndBelow, NotFound, NotFound, NotFound, NotFound, NotFound])
         );
         assert_eq!(
             Key::adjacency(&entries, 2),
-            Adjacency(Above, NotFound, NotFound, NotFound, NotFound, NotFound)
+            Adjacency::from([Above, NotFound, NotFound, NotFound, NotFound, NotFound])
         );
     }
 }
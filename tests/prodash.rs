@@ -1,3 +1,10 @@
+#[cfg(feature = "config")]
+mod config;
 mod nested_progress;
 mod progress;
+mod render;
+#[cfg(feature = "testing")]
+mod testing;
+#[cfg(feature = "humantime")]
+mod time;
 mod unit;
@@ -1,4 +1,4 @@
-use prodash::Progress;
+use prodash::{Count, Progress, Root};
 
 #[test]
 fn dyn_safe() {
@@ -24,3 +24,451 @@ fn thread_safe() {
     needs_send_sync(child_of_child);
     needs_send_sync(child);
 }
+
+#[cfg(not(feature = "minimal"))]
+#[test]
+fn format_throughput() {
+    let root = prodash::tree::Root::new();
+    let child = root.add_child("hello");
+    child.init(None, Some("files".into()));
+    child.inc_by(5);
+    let msg = child.format_throughput(std::time::Instant::now());
+    assert!(msg.starts_with("done 5 files in "), "{msg}");
+    assert!(msg.ends_with(" files/s)"), "{msg}");
+}
+
+#[test]
+fn throughput_sampler_updates_rates_without_renderer() {
+    let root = prodash::tree::Root::new();
+    let child = root.add_child("task");
+    child.init(Some(1000), None);
+    let sampler = prodash::ThroughputSampler::spawn(root.downgrade(), std::time::Duration::from_millis(20)).unwrap();
+    let mut snapshot = Vec::new();
+    root.sorted_snapshot(&mut snapshot);
+    let key = snapshot[0].0.clone();
+    for _ in 0..75 {
+        child.inc_by(10);
+        std::thread::sleep(std::time::Duration::from_millis(20));
+    }
+    sampler.shutdown_and_wait();
+    assert!(root.rate_for(&key).expect("task exists") > 0.0);
+}
+
+#[test]
+fn throughput_estimates_the_remaining_time_of_bounded_tasks() {
+    let root = prodash::tree::Root::new();
+    let bounded = root.add_child("bounded");
+    bounded.init(Some(1000), None);
+    let unbounded = root.add_child("unbounded");
+    unbounded.init(None, None);
+    unbounded.tick();
+    let mut snapshot = Vec::new();
+    root.sorted_snapshot(&mut snapshot);
+
+    let mut throughput = prodash::Throughput::default();
+    for _ in 0..15 {
+        bounded.inc_by(10);
+        unbounded.inc_by(10);
+        throughput.update_elapsed();
+        for (key, task) in &snapshot {
+            throughput.update_and_get(key, task.progress.as_ref());
+        }
+        std::thread::sleep(std::time::Duration::from_millis(100));
+    }
+
+    let eta = throughput.eta(&snapshot[0].0).expect("the throughput is known");
+    assert!(
+        eta > std::time::Duration::from_secs(1) && eta < std::time::Duration::from_secs(60),
+        "about 850 steps are left at about 100 steps per second, but got {eta:?}"
+    );
+    assert_eq!(throughput.eta(&snapshot[1].0), None, "unbounded tasks are never done");
+}
+
+#[test]
+fn render_control_is_shared_between_clones() {
+    let control = prodash::render::Control::new();
+    let remote = control.clone();
+    let signal = control.interrupt_signal();
+
+    remote.pause();
+    remote.set_level_filter(Some(1..=2));
+    remote.set_frames_per_second(Some(0.5));
+    assert!(control.is_paused());
+    assert_eq!(control.level_filter(), Some(1..=2));
+    assert_eq!(control.frames_per_second(), Some(0.5));
+
+    remote.request_interrupt();
+    assert!(
+        signal.load(std::sync::atomic::Ordering::SeqCst),
+        "the signal acts as cancellation token"
+    );
+
+    remote.resume();
+    remote.set_level_filter(None);
+    remote.set_frames_per_second(Some(0.0));
+    assert!(!control.is_paused());
+    assert_eq!(control.level_filter(), None);
+    assert_eq!(
+        control.frames_per_second(),
+        None,
+        "frame rates that aren't positive are ignored"
+    );
+}
+
+#[test]
+fn boxed_and_shared_progress_forward_to_the_inner_implementation() {
+    fn use_progress(mut progress: impl prodash::NestedProgress) {
+        progress.init(Some(10), None);
+        progress.inc_by(3);
+        let _child = progress.add_child("child");
+    }
+    let root = prodash::tree::Root::new();
+    let boxed = Box::new(root.add_child("boxed"));
+    let boxed_counter = boxed.counter();
+    use_progress(boxed);
+    assert_eq!(boxed_counter.load(std::sync::atomic::Ordering::SeqCst), 3);
+
+    let shared = prodash::progress::Shared::new(root.add_child("shared"));
+    use_progress(shared.clone());
+    assert_eq!(shared.step(), 3, "clones share the same progress");
+    assert_eq!(shared.lock().max(), Some(10));
+}
+
+#[test]
+fn phases_map_their_steps_onto_one_progress() {
+    let root = prodash::tree::Root::new();
+    let mut phases = prodash::progress::Phases::new(root.add_child("clone"), [("resolve", 1), ("download", 3)]);
+    assert_eq!(phases.inner().max(), Some(400));
+    assert!(!phases.enter("unknown", None), "unknown phases can't be entered");
+
+    assert!(phases.enter("resolve", None));
+    phases.set(10);
+    assert_eq!(
+        phases.inner().step(),
+        Some(0),
+        "without a maximum, it stays at the start"
+    );
+
+    assert!(phases.enter("download", Some(10)));
+    assert_eq!(phases.current(), Some("download"));
+    phases.inc_by(5);
+    assert_eq!(phases.inner().step(), Some(250));
+    phases.inc_by(100);
+    assert_eq!(
+        phases.inner().step(),
+        Some(400),
+        "steps beyond the maximum stay within the phase"
+    );
+
+    #[cfg(not(feature = "minimal"))]
+    assert_eq!(phases.inner().name().as_deref(), Some("clone: download"));
+    phases.finish();
+    assert_eq!(phases.into_inner().step(), Some(400));
+}
+
+#[test]
+fn discovery_keeps_the_step_once_the_total_is_known() {
+    let root = prodash::tree::Root::new();
+    let mut discovery = prodash::progress::Discovery::new(root.add_child("scan"), "files");
+    discovery.inc_by(3);
+    assert!(discovery.is_discovering());
+    assert_eq!(discovery.total(), None);
+
+    discovery.set_total(10);
+    assert_eq!(discovery.total(), Some(10));
+    assert_eq!(
+        discovery.step(),
+        3,
+        "work done while discovering counts towards the total"
+    );
+    discovery.inc();
+    discovery.set_total(20);
+    assert_eq!(discovery.into_inner().step(), Some(4));
+}
+
+#[test]
+fn walks_show_processed_and_discovered_entries_until_discovery_is_finished() {
+    let root = prodash::tree::Root::new();
+    let mut walk = prodash::progress::Walk::new(root.add_child("index"), "files");
+    walk.discover_by(10);
+    walk.inc_by(3);
+    assert!(walk.is_discovering());
+    assert_eq!(walk.inner().max(), None, "it's unbounded while scanning");
+    #[cfg(not(feature = "minimal"))]
+    assert_eq!(
+        format!("{}", walk.inner().unit().expect("set").display(3, None, None)),
+        "3/10 files (still scanning)"
+    );
+
+    walk.discover();
+    walk.finish_discovery();
+    assert!(!walk.is_discovering());
+    assert_eq!(walk.inner().max(), Some(11));
+    assert_eq!(walk.processed(), 3, "processed entries are kept");
+    #[cfg(not(feature = "minimal"))]
+    assert_eq!(
+        format!("{}", walk.inner().unit().expect("set").display(3, Some(11), None)),
+        "3/11 files"
+    );
+}
+
+#[test]
+fn scaled_progress_adds_up_increments_too_small_to_show() {
+    let root = prodash::tree::Root::new();
+    let mut scaled = prodash::progress::Scaled::new(root.add_child("work"), 1, 1000);
+    scaled.init(Some(10_000), None);
+    assert_eq!(scaled.inner().max(), Some(10));
+    assert_eq!(scaled.max(), Some(10_000));
+
+    for _ in 0..1500 {
+        scaled.inc();
+    }
+    assert_eq!(scaled.step(), 1500, "steps are tracked unscaled");
+    assert_eq!(scaled.inner().step(), Some(1));
+
+    assert_eq!(scaled.set_max(Some(20_000)), Some(10_000));
+    scaled.set(5000);
+    assert_eq!(scaled.into_inner().step(), Some(5));
+}
+
+#[cfg(not(feature = "minimal"))]
+#[test]
+fn message_levels_can_be_mapped_for_the_progress_and_its_children() {
+    use prodash::{messages::MessageLevel, NestedProgress};
+
+    let root = prodash::tree::Root::new();
+    let mut progress = root.add_child("fetch").map_message_level(|level, message| match level {
+        MessageLevel::Failure if message.contains("retrying") => MessageLevel::Info,
+        MessageLevel::Info if message.contains("giving up") => MessageLevel::Failure,
+        level => level,
+    });
+    progress.fail("connection reset, retrying".into());
+    progress.info("giving up".into());
+    let child = progress.add_child("pack");
+    child.fail("timeout, retrying".into());
+    child.fail("checksum mismatch".into());
+    progress.done("fetched".into());
+
+    let mut messages = Vec::new();
+    root.copy_messages(&mut messages);
+    let levels: Vec<_> = messages.iter().map(|message| message.level).collect();
+    assert_eq!(
+        levels,
+        [
+            MessageLevel::Info,
+            MessageLevel::Failure,
+            MessageLevel::Info,
+            MessageLevel::Failure,
+            MessageLevel::Success
+        ]
+    );
+    assert_eq!(progress.into_inner().name().as_deref(), Some("fetch"));
+}
+
+#[test]
+fn max_counter_updates_the_bound_from_anywhere() {
+    let root = prodash::tree::Root::new();
+    let child = root.add_child("download");
+    child.init(Some(1), None);
+    let max = Progress::max_counter(&child).expect("trees support shared maxima");
+
+    std::thread::spawn(move || max.store(42, std::sync::atomic::Ordering::SeqCst))
+        .join()
+        .unwrap();
+    assert_eq!(child.max(), Some(42));
+    let mut tasks = Vec::new();
+    root.sorted_snapshot(&mut tasks);
+    assert_eq!(
+        tasks[0].1.progress.as_ref().and_then(|p| p.done_at),
+        Some(42),
+        "snapshots see it"
+    );
+
+    assert_eq!(child.set_max(Some(10)), Some(42));
+    assert_eq!(child.max_counter().load(std::sync::atomic::Ordering::SeqCst), 10);
+    child
+        .max_counter()
+        .store(usize::MAX, std::sync::atomic::Ordering::SeqCst);
+    assert_eq!(child.max(), None, "the largest value makes it unbounded");
+    assert!(prodash::progress::Discard.max_counter().is_none());
+}
+
+#[test]
+fn tasks_can_be_cancelled_through_their_token_in_the_tree() {
+    let root = prodash::tree::Root::new();
+    let child = root.add_child("cancel me");
+    let other = root.add_child("keep going");
+    assert!(!Progress::is_cancelled(&child));
+
+    let mut tasks = Vec::new();
+    root.sorted_snapshot(&mut tasks);
+    tasks[0].1.cancel_token.cancel();
+    assert!(
+        Progress::is_cancelled(&child),
+        "snapshots share the token with the item"
+    );
+    assert!(!other.is_cancelled(), "other tasks are unaffected");
+
+    other.cancel_token().cancel();
+    assert!(other.is_cancelled());
+    assert!(!prodash::progress::Discard.is_cancelled());
+}
+
+#[test]
+fn siblings_sharing_an_id_are_grouped_under_a_new_task() {
+    use prodash::progress::{group_by_id, GroupById};
+
+    let root = prodash::tree::Root::new();
+    let mut clone = root.add_child("clone");
+    let files: Vec<_> = (0..3)
+        .map(|idx| {
+            let mut file = clone.add_child_with_id(format!("file {idx}"), *b"FILE");
+            file.init(Some(10), None);
+            file.set(idx);
+            let chunk = file.add_child("chunk");
+            (file, chunk)
+        })
+        .collect();
+    let _other = clone.add_child_with_id("index", *b"INDX");
+
+    let mut tasks = Vec::new();
+    root.sorted_snapshot(&mut tasks);
+    group_by_id(&mut tasks, 3);
+    let names_and_levels: Vec<_> = tasks
+        .iter()
+        .map(|(key, task)| (task.name.as_str(), key.level()))
+        .collect();
+    assert_eq!(
+        names_and_levels,
+        [
+            ("clone", 1),
+            ("FILE (3)", 2),
+            ("file 0", 3),
+            ("chunk", 4),
+            ("file 1", 3),
+            ("chunk", 4),
+            ("file 2", 3),
+            ("chunk", 4),
+            ("index", 2)
+        ]
+    );
+    let group = tasks[1].1.progress.as_ref().expect("groups have progress");
+    assert_eq!(group.step.load(std::sync::atomic::Ordering::SeqCst), 3);
+    assert_eq!(group.done_at, Some(30));
+
+    let mut grouped = Vec::new();
+    GroupById::new(root.clone(), 4).sorted_snapshot(&mut grouped);
+    assert_eq!(grouped.len(), 8, "groups need to be large enough");
+    drop(files);
+}
+
+#[cfg(not(feature = "minimal"))]
+#[test]
+fn groups_have_progress_only_if_their_members_have() {
+    use prodash::progress::group_by_id;
+
+    let root = prodash::tree::Root::new();
+    let mut clone = root.add_child("clone");
+    let _without_progress: Vec<_> = (0..2)
+        .map(|idx| clone.add_child_with_id(format!("file {idx}"), *b"FILE"))
+        .collect();
+    let unbounded = clone.add_child_with_id("unbounded", *b"UNBD");
+    unbounded.init(None, Some("files".into()));
+    let bounded = clone.add_child_with_id("bounded", *b"UNBD");
+    bounded.init(Some(10), None);
+
+    let mut tasks = Vec::new();
+    root.sorted_snapshot(&mut tasks);
+    group_by_id(&mut tasks, 2);
+    let group = |name: &str| {
+        tasks
+            .iter()
+            .find(|(_, task)| task.name == name)
+            .map(|(_, task)| task.progress.as_ref().map(|p| p.done_at))
+            .expect("group exists")
+    };
+    assert_eq!(group("FILE (2)"), None, "no member has progress");
+    assert_eq!(
+        group("UNBD (2)"),
+        Some(None),
+        "a member without bound makes the group unbounded"
+    );
+}
+
+#[cfg(all(feature = "serde", not(feature = "minimal")))]
+#[test]
+fn snapshots_and_messages_can_be_serialized_and_deserialized() {
+    use std::sync::atomic::Ordering;
+
+    use prodash::{
+        messages::Message,
+        progress::{Key, State, Task},
+        unit::{self, display},
+    };
+
+    let root = prodash::tree::Root::new();
+    let mut task = root.add_child_with_id("task", *b"TASK");
+    task.init(
+        Some(10),
+        Some(unit::label_and_mode("files", display::Mode::with_percentage())),
+    );
+    task.set(3);
+    task.blocked("waiting for lock", None);
+    let _sub = task.add_child("sub-task").add_child("sub-sub-task");
+    task.info("hello".into());
+    let mut snapshot = Vec::new();
+    root.sorted_snapshot(&mut snapshot);
+    let mut messages = Vec::new();
+    root.copy_messages(&mut messages);
+
+    let serialized = serde_json::to_string(&(&snapshot, &messages)).expect("serializable");
+    let (restored, restored_messages): (Vec<(Key, Task)>, Vec<Message>) =
+        serde_json::from_str(&serialized).expect("deserializable");
+
+    assert_eq!(restored_messages, messages);
+    assert_eq!(
+        restored
+            .iter()
+            .map(|(key, task)| (key, &task.name, task.id))
+            .collect::<Vec<_>>(),
+        snapshot
+            .iter()
+            .map(|(key, task)| (key, &task.name, task.id))
+            .collect::<Vec<_>>()
+    );
+    let progress = restored[0].1.progress.as_ref().expect("progress is restored");
+    assert_eq!(progress.step.load(Ordering::Relaxed), 3);
+    assert_eq!(progress.done_at, Some(10));
+    assert_eq!(progress.state, State::Blocked("waiting for lock".into(), None));
+    assert_eq!(
+        progress
+            .unit
+            .as_ref()
+            .expect("unit")
+            .display(3, Some(10), None)
+            .to_string(),
+        "3/10 files [30%]",
+        "the label and display mode are restored"
+    );
+}
+
+#[cfg(all(feature = "serde", not(feature = "minimal")))]
+#[test]
+fn deserialized_reasons_and_labels_are_kept_as_they_are() {
+    use prodash::{progress::State, unit::Unit};
+
+    for n in 0..10_000 {
+        let reason = format!("waiting for lock {n}");
+        let state: State =
+            serde_json::from_str(&serde_json::to_string(&State::Halted(reason.clone().into(), None)).unwrap()).unwrap();
+        assert_eq!(
+            state,
+            State::Halted(reason.into(), None),
+            "no matter how many were seen"
+        );
+
+        let label = format!("files of batch {n}");
+        let unit: Unit = serde_json::from_value(serde_json::json!({ "label": label, "mode": null })).unwrap();
+        assert_eq!(unit.display(1, None, None).to_string(), format!("1 {label}"));
+    }
+}
@@ -0,0 +1,603 @@
+#[cfg(feature = "render-tui")]
+mod tui {
+    use prodash::render::{
+        tui::{
+            tui_export::{backend::TestBackend, buffer::Buffer, layout::Rect, widgets::Paragraph, Terminal},
+            Columns, Dashboard, Event, Options, Outcome, Output,
+        },
+        Control,
+    };
+
+    fn draw(width: u16, height: u16) -> Vec<String> {
+        let root = prodash::tree::Root::new();
+        let task = root.add_child("task");
+        task.init(Some(10), None);
+        let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+        let mut dashboard = Dashboard::new(Options::default());
+        terminal.draw(|frame| dashboard.draw_frame(&root, frame)).unwrap();
+        lines(terminal.backend().buffer())
+    }
+
+    fn lines(buf: &Buffer) -> Vec<String> {
+        (0..buf.area.height)
+            .map(|y| (0..buf.area.width).map(|x| buf.get(x, y).symbol()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn tiny_terminals_show_the_minimum_size_instead_of_the_dashboard() {
+        let lines = draw(10, 3);
+        assert_eq!(lines[0].trim(), "");
+        assert!(lines[1].starts_with("terminal"), "{lines:?}");
+        assert_eq!(lines[2].trim(), "");
+    }
+
+    #[test]
+    fn terminals_of_the_minimum_size_show_the_dashboard() {
+        let lines = draw(40, 10);
+        assert!(lines.iter().all(|line| !line.contains("too small")), "{lines:?}");
+        assert!(lines.iter().any(|line| line.contains("task")), "{lines:?}");
+    }
+
+    #[test]
+    fn dashboards_draw_into_part_of_a_caller_owned_terminal_and_handle_events() {
+        let root = prodash::tree::Root::new();
+        let _task = root.add_child("task");
+        let mut dashboard = Dashboard::new(Options::default());
+        assert_eq!(
+            dashboard.handle_event(Event::SetTitle("Embedded".into())),
+            Outcome::Redraw
+        );
+        assert_eq!(dashboard.handle_event(Event::SetFps(0.0)), Outcome::Ignored);
+        assert_eq!(dashboard.handle_event(Event::SetFps(4.0)), Outcome::Redraw);
+        assert_eq!(dashboard.frames_per_second(), 4.0);
+        assert_eq!(
+            dashboard.handle_event(Event::Paste("text".into())),
+            Outcome::Ignored,
+            "pastes are only used while searching"
+        );
+
+        let mut terminal = Terminal::new(TestBackend::new(100, 10)).unwrap();
+        terminal
+            .draw(|frame| {
+                let area = frame.size();
+                let (left, right) = (
+                    Rect { width: 50, ..area },
+                    Rect {
+                        x: 50,
+                        width: 50,
+                        ..area
+                    },
+                );
+                frame.render_widget(Paragraph::new("other widget"), left);
+                dashboard.update(&root);
+                dashboard.draw(right, frame.buffer_mut());
+            })
+            .unwrap();
+        let lines = lines(terminal.backend().buffer());
+        assert!(lines[0].starts_with("other widget"), "{lines:?}");
+        assert!(lines[0][50..].contains("Embedded"), "{lines:?}");
+        assert!(lines.iter().any(|line| line[50..].contains("task")), "{lines:?}");
+    }
+
+    #[test]
+    fn columns_can_be_hidden_or_shown() {
+        let root = prodash::tree::Root::new();
+        let task = root.add_child("build");
+        task.init(Some(10), None);
+        task.set(3);
+        let draw = |columns: Columns| {
+            let mut terminal = Terminal::new(TestBackend::new(100, 10)).unwrap();
+            let mut dashboard = Dashboard::new(Options {
+                columns,
+                ..Default::default()
+            });
+            terminal.draw(|frame| dashboard.draw_frame(&root, frame)).unwrap();
+            lines(terminal.backend().buffer()).join("\n")
+        };
+
+        let frame = draw(Columns::default());
+        assert!(frame.contains("3/10"), "{frame}");
+        assert!(!frame.contains("▶"), "{frame}");
+
+        let frame = draw(Columns {
+            value: false,
+            status_glyph: true,
+            ..Default::default()
+        });
+        assert!(!frame.contains("3/10"), "{frame}");
+        assert!(frame.contains("▶"), "{frame}");
+        assert!(frame.contains("build"), "{frame}");
+    }
+
+    #[test]
+    fn control_filters_levels_pauses_drawing_and_sets_the_frame_rate() {
+        let root = prodash::tree::Root::new();
+        let mut parent = root.add_child("parent");
+        let _child = parent.add_child("child");
+        let control = Control::new();
+        let mut dashboard = Dashboard::new(Options {
+            control: Some(control.clone()),
+            ..Default::default()
+        });
+        let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+
+        control.set_level_filter(Some(2..=2));
+        terminal.draw(|frame| dashboard.draw_frame(&root, frame)).unwrap();
+        let lines = lines(terminal.backend().buffer());
+        assert!(lines.iter().any(|line| line.contains("child")), "{lines:?}");
+        assert!(lines.iter().all(|line| !line.contains("parent")), "{lines:?}");
+
+        control.pause();
+        assert_eq!(
+            dashboard.handle_event(Event::Tick),
+            Outcome::Ignored,
+            "no frames are drawn"
+        );
+        control.resume();
+        assert_eq!(dashboard.handle_event(Event::Tick), Outcome::Redraw);
+
+        assert_eq!(dashboard.frames_per_second(), 10.0);
+        control.set_frames_per_second(Some(2.0));
+        assert_eq!(dashboard.frames_per_second(), 2.0, "the control takes precedence");
+    }
+
+    #[test]
+    fn snapshots_are_written_as_text_or_json() {
+        let root = prodash::tree::Root::new();
+        let mut task = root.add_child("build");
+        task.init(Some(10), None);
+        task.set(3);
+        task.blocked("waiting for lock", None);
+        let _child = task.add_child("compile");
+        let mut dashboard = Dashboard::new(Options::default());
+        dashboard.update(&root);
+
+        let dir = std::env::temp_dir().join(format!("prodash-snapshot-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let text_path = dir.join("snapshot.txt");
+        assert_eq!(
+            dashboard.handle_event(Event::Snapshot(text_path.clone())),
+            Outcome::Redraw
+        );
+        assert_eq!(
+            std::fs::read_to_string(&text_path).unwrap(),
+            "Progress Dashboard\nbuild 3/10 [blocked: waiting for lock]\n  compile\n"
+        );
+
+        let json_path = dir.join("snapshot.json");
+        dashboard.write_snapshot(&json_path).unwrap();
+        let json: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&json_path).unwrap()).unwrap();
+        assert_eq!(json["title"], "Progress Dashboard");
+        assert_eq!(json["tasks"][0]["name"], "build");
+        assert_eq!(json["tasks"][0]["progress"], "3/10");
+        assert_eq!(json["tasks"][0]["state"], "blocked");
+        assert_eq!(json["tasks"][0]["reason"], "waiting for lock");
+        assert_eq!(json["tasks"][1]["level"], 2);
+        assert_eq!(json["tasks"][1]["progress"], serde_json::Value::Null);
+        assert_eq!(json["messages"], serde_json::json!([]));
+        std::fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn output_lines_are_plain_text() {
+        let output = Output::new(10);
+        output.push("\x1b[1;31merror\x1b[0m: build failed");
+        output.push("\x1b]0;title\x07\x1b]8;;https://example.com\x1b\\link\x1b]8;;\x1b\\ and\ttab");
+        output.push("  0%\r 50%\r100%\r");
+        output.push("bell\x07 and \x1b7cursor\x1b8 saved");
+        let mut lines = Vec::new();
+        output.copy_lines(&mut lines);
+        assert_eq!(
+            lines,
+            [
+                "error: build failed",
+                "link and    tab",
+                "100%",
+                "bell and cursor saved"
+            ]
+        );
+    }
+}
+
+#[cfg(feature = "render-callback")]
+mod callback {
+    use std::sync::{Arc, Mutex};
+
+    use prodash::{render::callback, Root};
+
+    #[test]
+    fn frames_contain_all_tasks_and_each_message_once() {
+        let root = prodash::tree::Root::new();
+        let mut task = root.add_child("task");
+        task.info("hello");
+        let frames = Arc::new(Mutex::new(Vec::new()));
+        let handle = callback::render(
+            root.downgrade(),
+            callback::Options {
+                frames_per_second: 100.0,
+                ..Default::default()
+            },
+            {
+                let frames = Arc::clone(&frames);
+                move |tasks, messages| {
+                    frames.lock().unwrap().push((
+                        tasks.iter().map(|(_, task)| task.name.clone()).collect::<Vec<_>>(),
+                        messages.iter().map(|m| m.message.clone()).collect::<Vec<_>>(),
+                    ))
+                }
+            },
+        );
+        std::thread::sleep(std::time::Duration::from_millis(50));
+        handle.shutdown_and_wait();
+
+        let frames = frames.lock().unwrap();
+        assert!(frames.len() > 1, "frames are passed in regular intervals");
+        assert!(frames.iter().all(|(tasks, _)| tasks == &["task"]));
+        let messages: Vec<_> = frames.iter().flat_map(|(_, messages)| messages).collect();
+        if cfg!(feature = "minimal") {
+            assert!(messages.is_empty());
+        } else {
+            assert_eq!(messages, ["hello"], "messages are only passed once");
+        }
+    }
+
+    #[test]
+    fn stops_once_the_progress_is_dropped() {
+        let root = prodash::tree::Root::new();
+        let handle = callback::render(root.downgrade(), callback::Options::default(), |_, _| {});
+        drop(root);
+        handle.wait();
+    }
+}
+
+#[cfg(feature = "render-line")]
+mod line {
+    use prodash::{
+        render::{line, OnConflict, RenderHandle, StreamKind},
+        Root,
+    };
+
+    fn options(on_conflict: OnConflict) -> line::Options {
+        line::Options {
+            stream: Some(StreamKind::Stdout),
+            on_conflict,
+            output_is_terminal: false,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn renderers_on_the_same_stream_fail_or_replace_each_other() {
+        let root = prodash::tree::Root::new();
+        let first = line::render(std::io::sink(), root.downgrade(), options(OnConflict::Fail));
+
+        let mut second = line::render(std::io::sink(), root.downgrade(), options(OnConflict::Fail));
+        assert_eq!(
+            second.take_error().map(|err| err.kind()),
+            Some(std::io::ErrorKind::AlreadyExists),
+            "the stream is taken"
+        );
+
+        let mut third = line::render(std::io::sink(), root.downgrade(), options(OnConflict::Replace));
+        first.wait();
+        assert!(
+            third.take_error().is_none(),
+            "the previous renderer was shut down instead"
+        );
+        third.shutdown_and_wait();
+    }
+
+    #[test]
+    fn switching_renderers_continues_with_the_same_progress() {
+        let root = prodash::tree::Root::new();
+        let options = || line::Options {
+            output_is_terminal: false,
+            ..Default::default()
+        };
+        let first = line::render(std::io::sink(), root.downgrade(), options());
+        let second = prodash::render::switch(
+            first.into(),
+            root.downgrade(),
+            prodash::render::RendererKind::Line(options()),
+        )
+        .expect("the first renderer released the stream");
+        second.shutdown_and_wait();
+    }
+
+    #[test]
+    fn render_handles_can_be_awaited_until_the_renderer_stopped() {
+        let root = prodash::tree::Root::new();
+        let options = || line::Options {
+            output_is_terminal: false,
+            frames_per_second: 100.0,
+            ..Default::default()
+        };
+
+        let mut handle: RenderHandle = line::render(std::io::sink(), root.downgrade(), options()).into();
+        handle.shutdown();
+        futures::executor::block_on(async { handle.await });
+
+        let handle: RenderHandle = line::render(std::io::sink(), root.downgrade(), options()).into();
+        drop(root);
+        futures::executor::block_on(async { handle.await });
+    }
+
+    #[cfg(feature = "render-line-async")]
+    #[test]
+    fn the_async_renderer_completes_once_progress_is_dropped() {
+        let root = prodash::tree::Root::new();
+        let progress = root.downgrade();
+        let task = root.add_child("task");
+        std::thread::spawn(move || {
+            std::thread::sleep(std::time::Duration::from_millis(50));
+            drop(task);
+            drop(root);
+        });
+        let options = line::Options {
+            output_is_terminal: false,
+            frames_per_second: 100.0,
+            ..Default::default()
+        };
+        futures::executor::block_on(line::render_async(std::io::sink(), progress, options))
+            .expect("writing to a sink never fails");
+    }
+
+    #[test]
+    fn templates_are_parsed_into_segments() {
+        use line::template::{Align, Field, Segment};
+
+        let template = line::Template::parse("{name:>12} {{{bar:40}}}").unwrap();
+        assert_eq!(
+            template.segments(),
+            [
+                Segment::Field {
+                    field: Field::Name,
+                    align: Align::Right,
+                    width: Some(12),
+                },
+                Segment::Literal(" {".into()),
+                Segment::Field {
+                    field: Field::Bar,
+                    align: Align::Left,
+                    width: Some(40),
+                },
+                Segment::Literal("}".into()),
+            ]
+        );
+
+        for invalid in ["{name", "name}", "{size}", "{bar:wide}"] {
+            assert_eq!(
+                line::Template::parse(invalid).map_err(|err| err.kind()),
+                Err(std::io::ErrorKind::InvalidInput),
+                "{invalid}"
+            );
+        }
+    }
+
+    /// Tests inspecting the frames drawn, which show units and messages that don't exist with the minimal feature.
+    #[cfg(not(feature = "minimal"))]
+    mod frames {
+        use std::{
+            io,
+            sync::{mpsc, Arc, Mutex},
+            time::{Duration, Instant},
+        };
+
+        use prodash::{
+            render::{line, line::AnsiMode},
+            Root,
+        };
+
+        /// A writer to inspect what a renderer drew on its own thread.
+        #[derive(Clone, Default)]
+        struct Output(Arc<Mutex<Vec<u8>>>);
+
+        impl io::Write for Output {
+            fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+                self.0.lock().unwrap().extend_from_slice(buf);
+                Ok(buf.len())
+            }
+
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+
+        impl Output {
+            /// Return true once `text` was drawn, or false if that doesn't happen in time.
+            fn wait_for(&self, text: &str) -> bool {
+                self.wait_for_at_most(text, Duration::from_secs(5))
+            }
+
+            /// Return true once `text` was drawn, or false if that doesn't happen within `timeout`.
+            fn wait_for_at_most(&self, text: &str, timeout: Duration) -> bool {
+                let deadline = Instant::now() + timeout;
+                while Instant::now() < deadline {
+                    if String::from_utf8_lossy(&self.0.lock().unwrap()).contains(text) {
+                        return true;
+                    }
+                    std::thread::sleep(Duration::from_millis(5));
+                }
+                false
+            }
+        }
+
+        /// Draw progress of `root` as plain text to `out`, one frame for each tick sent through the returned channel.
+        fn render_plain_frames(
+            out: &Output,
+            root: &Arc<prodash::tree::Root>,
+            options: line::Options,
+        ) -> (mpsc::Sender<()>, line::JoinHandle) {
+            let (ticks, tick_recv) = mpsc::channel();
+            let handle = line::render_with_ticks(
+                out.clone(),
+                root.downgrade(),
+                line::Options {
+                    colored: false,
+                    ansi: AnsiMode::Never,
+                    ..options
+                },
+                tick_recv.into_iter().map(|()| Instant::now()),
+            );
+            (ticks, handle)
+        }
+
+        #[test]
+        fn discovering_entries_of_a_walk_draws_a_new_frame() {
+            let root = prodash::tree::Root::new();
+            let walk = prodash::progress::Walk::new(root.add_child("index"), "files");
+            walk.discover();
+            let out = Output::default();
+            let (ticks, handle) = render_plain_frames(&out, &root, Default::default());
+
+            ticks.send(()).unwrap();
+            assert!(out.wait_for("0/1 files"));
+            walk.discover();
+            ticks.send(()).unwrap();
+            assert!(
+                out.wait_for("0/2 files"),
+                "the step didn't change, but the discovered entries did"
+            );
+            handle.shutdown_and_wait();
+        }
+
+        #[test]
+        fn collapsed_messages_are_drawn_again_with_their_count() {
+            let root = Arc::new(
+                prodash::tree::root::Options {
+                    message_dedupe_window: Some(Duration::from_secs(60)),
+                    ..Default::default()
+                }
+                .create(),
+            );
+            let mut task = root.add_child("fetch");
+            let out = Output::default();
+            let (ticks, handle) = render_plain_frames(&out, &root, Default::default());
+
+            task.fail("connection refused");
+            ticks.send(()).unwrap();
+            assert!(out.wait_for("connection refused"));
+            task.fail("connection refused");
+            ticks.send(()).unwrap();
+            assert!(out.wait_for("connection refused ×2"));
+            handle.shutdown_and_wait();
+        }
+
+        #[test]
+        fn templates_define_the_layout_of_progress_lines() {
+            let root = prodash::tree::Root::new();
+            let task = root.add_child("build");
+            task.init(Some(10), Some(prodash::unit::label("crates")));
+            task.set(3);
+            let out = Output::default();
+            let (ticks, handle) = render_plain_frames(
+                &out,
+                &root,
+                line::Options {
+                    template: Some("[{name:^9}] {pos}/{len} {unit} {percent}".parse().unwrap()),
+                    ..Default::default()
+                },
+            );
+
+            ticks.send(()).unwrap();
+            assert!(out.wait_for("[  build  ] 3/10 crates 30%"));
+            handle.shutdown_and_wait();
+        }
+
+        #[test]
+        fn rendering_stops_once_only_the_name_of_the_root_is_left() {
+            let root = prodash::tree::Root::new();
+            root.set_name("app");
+            let child = root.add_child("fetch");
+            let out = Output::default();
+            let (ticks, handle) = render_plain_frames(
+                &out,
+                &root,
+                line::Options {
+                    keep_running_if_progress_is_empty: false,
+                    ..Default::default()
+                },
+            );
+
+            ticks.send(()).unwrap();
+            assert!(out.wait_for("fetch"));
+            drop(child);
+            ticks.send(()).unwrap();
+            let (stopped, stopped_recv) = mpsc::channel();
+            std::thread::spawn(move || {
+                handle.wait();
+                stopped.send(()).ok();
+            });
+            assert!(
+                stopped_recv.recv_timeout(Duration::from_secs(5)).is_ok(),
+                "the named root isn't a task to keep rendering for"
+            );
+            drop(ticks);
+        }
+
+        #[test]
+        fn flashing_on_failure_restores_the_colors_on_the_next_frame() {
+            let root = prodash::tree::Root::new();
+            let mut task = root.add_child("fetch");
+            let out = Output::default();
+            let (ticks, tick_recv) = mpsc::channel();
+            let handle = line::render_with_ticks(
+                out.clone(),
+                root.downgrade(),
+                line::Options {
+                    colored: false,
+                    ansi: AnsiMode::Always,
+                    output_is_terminal: true,
+                    alert_on_failure: Some(line::AlertMode::Flash),
+                    ..Default::default()
+                },
+                tick_recv.into_iter().map(|()| Instant::now()),
+            );
+
+            task.fail("connection refused");
+            ticks.send(()).unwrap();
+            assert!(out.wait_for("\x1b[?5h"), "the colors are inverted");
+            assert!(
+                !out.wait_for_at_most("\x1b[?5l", Duration::from_millis(200)),
+                "the frame isn't held up to restore them"
+            );
+            ticks.send(()).unwrap();
+            assert!(out.wait_for("\x1b[?5l"), "they are restored with the next frame");
+            handle.shutdown_and_wait();
+        }
+    }
+}
+
+#[cfg(any(feature = "render-line", feature = "render-tui"))]
+mod theme {
+    use prodash::render::{
+        theme::{Quantized, Rgb},
+        ColorDepth,
+    };
+
+    #[test]
+    fn color_depth_is_detected_from_the_environment() {
+        assert_eq!(
+            ColorDepth::from_env(Some("truecolor"), Some("xterm")),
+            ColorDepth::TrueColor
+        );
+        assert_eq!(ColorDepth::from_env(None, Some("xterm-direct")), ColorDepth::TrueColor);
+        assert_eq!(ColorDepth::from_env(None, Some("xterm-256color")), ColorDepth::Ansi256);
+        assert_eq!(ColorDepth::from_env(None, Some("xterm")), ColorDepth::Ansi16);
+        assert_eq!(ColorDepth::from_env(None, None), ColorDepth::Ansi16);
+    }
+
+    #[test]
+    fn colors_are_downgraded_to_the_closest_available_one() {
+        let orange = Rgb(255, 135, 0);
+        assert_eq!(orange.quantize(ColorDepth::TrueColor), Quantized::Rgb(255, 135, 0));
+        assert_eq!(orange.quantize(ColorDepth::Ansi256), Quantized::Ansi256(208));
+        assert_eq!(orange.quantize(ColorDepth::Ansi16), Quantized::Ansi16(3), "yellow");
+        assert_eq!(
+            Rgb(128, 128, 128).quantize(ColorDepth::Ansi256),
+            Quantized::Ansi256(244),
+            "grays use the grayscale ramp"
+        );
+    }
+}
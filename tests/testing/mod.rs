@@ -0,0 +1,22 @@
+use std::time::Duration;
+
+use prodash::testing::{Options, Workload};
+
+#[test]
+fn workload_populates_the_tree_until_it_is_shut_down() {
+    let root = prodash::tree::Root::new();
+    let workload = Workload::spawn(
+        root.clone(),
+        Options {
+            speed_multiplier: 100.0,
+            chunks: 2..=2,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    std::thread::sleep(Duration::from_millis(50));
+    assert!(root.num_tasks() > 2, "chunks spawn tasks right away");
+
+    workload.shutdown_and_wait();
+    assert_eq!(root.num_tasks(), 0, "all tasks are removed once the workload stops");
+}
@@ -0,0 +1,75 @@
+use std::time::{Duration, SystemTime};
+
+use prodash::time::{TimestampFormat, Timezone};
+
+fn at(secs: u64, millis: u64) -> SystemTime {
+    SystemTime::UNIX_EPOCH + Duration::from_secs(secs) + Duration::from_millis(millis)
+}
+
+#[test]
+fn absolute_formats_in_utc() {
+    let time = at(1_581_555_105, 42);
+    let start = SystemTime::UNIX_EPOCH;
+    assert_eq!(TimestampFormat::Seconds.format(time, Timezone::Utc, start), "00:51:45");
+    assert_eq!(
+        TimestampFormat::Millis.format(time, Timezone::Utc, start),
+        "00:51:45.042"
+    );
+    assert_eq!(
+        TimestampFormat::Custom("%F %T%.3f %%".into()).format(time, Timezone::Utc, start),
+        "2020-02-13 00:51:45.042 %"
+    );
+}
+
+#[test]
+fn relative_format_is_based_on_start() {
+    let start = at(100, 0);
+    assert_eq!(
+        TimestampFormat::Relative.format(at(103, 200), Timezone::Utc, start),
+        "+3.2s"
+    );
+    assert_eq!(
+        TimestampFormat::Relative.format(at(99, 500), Timezone::Utc, start),
+        "-0.5s"
+    );
+}
+
+#[test]
+fn relative_to_first_message_uses_the_renderer_start_until_there_is_one() {
+    let (renderer_start, first_message) = (at(100, 0), at(110, 0));
+    let format = TimestampFormat::RelativeToFirstMessage;
+    assert_eq!(format.start(renderer_start, None), renderer_start);
+    assert_eq!(format.start(renderer_start, Some(first_message)), first_message);
+    assert_eq!(
+        TimestampFormat::Relative.start(renderer_start, Some(first_message)),
+        renderer_start
+    );
+    assert_eq!(
+        format.format(
+            at(112, 500),
+            Timezone::Utc,
+            format.start(renderer_start, Some(first_message))
+        ),
+        "+2.5s"
+    );
+}
+
+#[cfg(feature = "time-ticker")]
+mod ticker {
+    use std::time::{Duration, Instant};
+
+    use futures::StreamExt;
+
+    #[test]
+    fn ticks_with_jitter_stay_within_bounds() {
+        let started_at = Instant::now();
+        let ticks = futures::executor::block_on(
+            prodash::time::ticker_with_jitter(Duration::from_millis(20), Duration::from_millis(10))
+                .take(3)
+                .count(),
+        );
+        assert_eq!(ticks, 3);
+        let elapsed = started_at.elapsed();
+        assert!(elapsed >= Duration::from_millis(30), "{elapsed:?}");
+    }
+}
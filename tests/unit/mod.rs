@@ -34,6 +34,66 @@ mod dynamic {
             );
             assert_eq!(format!("{}", unit.display(100_002, None, None)), "100.0K objects");
         }
+
+        #[test]
+        fn compact_numbers_have_no_space() {
+            let unit = unit::dynamic(Human::compact("objects"));
+            assert_eq!(
+                format!("{}", unit.display(1_234, Some(3_400_000), None)),
+                "1.2k/3.4M objects"
+            );
+        }
+
+        #[test]
+        fn digits_can_be_grouped_and_separated_by_local_convention() {
+            let mut formatter = human::Formatter::new();
+            formatter.with_decimals(2).with_scales({
+                let mut scales = human::Scales::new();
+                scales.with_suffixes(vec![""]);
+                scales
+            });
+            let unit = unit::dynamic(Human::new(formatter, "objects").with_separators(human::Separators {
+                group: Some('.'),
+                group_size: 3,
+                decimal: ',',
+            }));
+            assert_eq!(
+                format!("{}", unit.display(1_234_567, Some(100), None)),
+                "1.234.567,00/100,00 objects"
+            );
+        }
+
+        #[test]
+        fn numbers_can_be_scaled_by_ten_thousands() {
+            let mut formatter = human::Formatter::new();
+            formatter.with_decimals(1).with_scales(human::myriad_scales());
+            let unit = unit::dynamic(Human::new(formatter, "件").with_separators(human::Separators {
+                group: Some(','),
+                group_size: 4,
+                ..Default::default()
+            }));
+            assert_eq!(
+                format!("{}", unit.display(123_456_789, Some(9_999_000_000_000_000), None)),
+                "1.2亿/9999.0兆 件"
+            );
+        }
+
+        #[test]
+        fn compact_numbers_compose_with_other_units() {
+            let unit = unit::dynamic_and_mode(
+                human::Compact::new(unit::Range::new("steps")),
+                display::Mode::with_percentage(),
+            );
+            assert_eq!(
+                format!("{}", unit.display(1_999, Some(3_400_000), None)),
+                "2.0k of 3.4M steps [0%]",
+                "the range still counts from one"
+            );
+            assert_eq!(format!("{}", unit.display(41, Some(999), None)), "42 of 999 steps [4%]");
+
+            let unit = unit::dynamic(human::Compact::new("files"));
+            assert_eq!(format!("{}", unit.display(12_345, None, None)), "12.3k files");
+        }
     }
     mod range {
         use prodash::unit::{self, display, Range};
@@ -47,7 +107,7 @@ mod dynamic {
     }
     #[cfg(feature = "unit-bytes")]
     mod bytes {
-        use prodash::unit::{self, display, Bytes};
+        use prodash::unit::{self, display, Bytes, BytesAsBits};
 
         #[test]
         fn value_and_upper_bound_use_own_unit() {
@@ -67,6 +127,34 @@ mod dynamic {
         fn just_value() {
             assert_eq!(format!("{}", unit::dynamic(Bytes).display(5540, None, None)), "5.5KB");
         }
+
+        #[test]
+        fn as_bits_shows_throughput_in_bits() {
+            let unit = unit::dynamic_and_mode(BytesAsBits, display::Mode::with_throughput());
+            assert_eq!(
+                format!(
+                    "{}",
+                    unit.display(
+                        5540,
+                        None,
+                        display::Throughput::new(1_500_000, std::time::Duration::from_secs(1))
+                    )
+                ),
+                "5.5KB |12.0Mb/s|",
+                "totals are still in bytes"
+            );
+            assert_eq!(
+                format!(
+                    "{}",
+                    unit.display(
+                        5540,
+                        None,
+                        display::Throughput::new(100, std::time::Duration::from_secs(1))
+                    )
+                ),
+                "5.5KB |800b/s|"
+            );
+        }
     }
 }
 
@@ -189,6 +277,30 @@ mod label {
             );
         }
 
+        #[test]
+        fn display_peak_throughput_only_if_enabled() {
+            let throughput = display::Throughput::new(5, time::Duration::from_secs(1)).with_peak(20);
+            assert_eq!(
+                format!(
+                    "{}",
+                    unit::label_and_mode("items", display::Mode::with_throughput()).display(
+                        700,
+                        None,
+                        throughput.clone()
+                    )
+                ),
+                "700 items |5/s|"
+            );
+            assert_eq!(
+                format!(
+                    "{}",
+                    unit::label_and_mode("items", display::Mode::with_percentage().and_peak_throughput())
+                        .display(700, None, throughput)
+                ),
+                "700 items |5/s (peak 20/s)|"
+            );
+        }
+
         #[test]
         fn display_current_value_no_upper_bound_shows_no_percentage() {
             assert_eq!(
@@ -238,6 +350,17 @@ mod label {
                 "123/500 items"
             );
         }
+        #[test]
+        fn write_to_reuses_the_given_buffer() {
+            let mut buf = String::with_capacity(64);
+            unit::label("items").write_to(&mut buf, 123, Some(500), None).unwrap();
+            assert_eq!(buf, "123/500 items");
+
+            buf.clear();
+            unit::label("").write_to(&mut buf, 1, None, None).unwrap();
+            assert_eq!(buf, "1", "there is no trailing space if there is no unit");
+            assert_eq!(buf.capacity(), 64);
+        }
     }
 }
 